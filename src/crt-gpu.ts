@@ -290,10 +290,5 @@ export class CrtGpuRenderer implements Renderer<CrtOptions> {
   }
 }
 
-export const CRT_PRESETS: Record<string, Partial<CrtOptions>> = {
-  default: {},
-  authentic: { warpX: 0.02, warpY: 0.025, scanHardness: -6.0, scanOpacity: 0.6, maskOpacity: 0.4 },
-  subtle: { warpX: 0.008, warpY: 0.01, scanHardness: -3.0, scanOpacity: 0.3, maskOpacity: 0.15 },
-  flat: { warpX: 0, warpY: 0, enableWarp: false, scanHardness: -4.0, scanOpacity: 0.5, maskOpacity: 0.3 },
-};
-
+// Preset catalogue generated from the Rust crate
+export { CRT_PRESETS } from './presets.generated.js';
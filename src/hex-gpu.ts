@@ -322,8 +322,5 @@ export class HexGpuRenderer implements Renderer<HexOptions> {
   }
 }
 
-export const HEX_PRESETS: Record<string, Partial<HexOptions>> = {
-  default: {},
-  bordered: { drawBorders: true, borderColor: '#282828', borderThickness: 1 },
-  pointy: { orientation: 'pointy-top', drawBorders: false },
-};
+// Preset catalogue generated from the Rust crate
+export { HEX_PRESETS } from './presets.generated.js';
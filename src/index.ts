@@ -13,6 +13,7 @@ export type {
   ImageOutput,
   Renderer,
   CrtOptions,
+  CrtCurvature,
   HexOptions,
   HexOrientation,
   XbrzOptions,
@@ -41,7 +42,11 @@ export const createRenderer = {
   xbrz: () => XbrzGpuRenderer.create(),
 };
 
-/** All available presets */
+/** All available presets (deprecated; generated from the Rust catalogue) */
 export { CRT_PRESETS as crtPresets } from './crt-gpu.js';
 export { HEX_PRESETS as hexPresets } from './hex-gpu.js';
 export { XBRZ_PRESETS as xbrzPresets } from './xbrz-gpu.js';
+
+/** Preset catalogue, read from the WASM module so both backends share one source */
+export { presetsFromWasm } from './wasm-wrapper.js';
+export type { RenderArtWasm } from './wasm-wrapper.js';
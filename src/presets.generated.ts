@@ -0,0 +1,32 @@
+// Generated from the Rust preset catalogue by `test_generated_presets_are_current`
+// in src/wasm/lib.rs. Do not edit; regenerate with
+// `UPDATE_PRESETS=1 cargo test generated_presets`.
+
+import type { CrtOptions, HexOptions, XbrzOptions } from './types.js';
+
+/** @deprecated Read presets with `presetsFromWasm(wasm).crt` */
+export const CRT_PRESETS: Record<string, Partial<CrtOptions>> = {
+  default: {"warpX":0.015,"warpY":0.02,"scanHardness":-4,"scanOpacity":0.5,"maskOpacity":0.3,"enableWarp":true,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"quadratic","curvatureRadius":2,"viewingDistance":1.5,"overscan":1,"tiltX":0,"tiltY":0},
+  authentic: {"warpX":0.02,"warpY":0.025,"scanHardness":-6,"scanOpacity":0.6,"maskOpacity":0.4,"enableWarp":true,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"quadratic","curvatureRadius":2,"viewingDistance":1.5,"overscan":1,"tiltX":0,"tiltY":0},
+  subtle: {"warpX":0.008,"warpY":0.01,"scanHardness":-3,"scanOpacity":0.3,"maskOpacity":0.15,"enableWarp":true,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"quadratic","curvatureRadius":2,"viewingDistance":1.5,"overscan":1,"tiltX":0,"tiltY":0},
+  flat: {"warpX":0,"warpY":0,"scanHardness":-4,"scanOpacity":0.5,"maskOpacity":0.3,"enableWarp":false,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"quadratic","curvatureRadius":2,"viewingDistance":1.5,"overscan":1,"tiltX":0,"tiltY":0},
+  trinitron: {"warpX":0.015,"warpY":0.02,"scanHardness":-8,"scanOpacity":0.4,"maskOpacity":0.35,"enableWarp":true,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"cylindrical","curvatureRadius":2.5,"viewingDistance":1.5,"overscan":1,"tiltX":0,"tiltY":0},
+  consumer: {"warpX":0.015,"warpY":0.02,"scanHardness":-4,"scanOpacity":0.55,"maskOpacity":0.45,"enableWarp":true,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"spherical","curvatureRadius":1.5,"viewingDistance":1.5,"overscan":1.05,"tiltX":0,"tiltY":0},
+};
+
+/** @deprecated Read presets with `presetsFromWasm(wasm).hex` */
+export const HEX_PRESETS: Record<string, Partial<HexOptions>> = {
+  default: {"orientation":"flat-top","drawBorders":false,"borderColor":673720575,"borderThickness":1,"backgroundColor":0,"supersample":1,"gap":0,"bevelWidth":0,"bevelStrength":0,"gradient":0,"shadowOffsetX":0,"shadowOffsetY":0,"shadowColor":0,"shadowSoftness":0,"composite":false,"skipTransparentBorders":false},
+  bordered: {"orientation":"flat-top","drawBorders":true,"borderColor":673720575,"borderThickness":1,"backgroundColor":0,"supersample":1,"gap":0,"bevelWidth":0,"bevelStrength":0,"gradient":0,"shadowOffsetX":0,"shadowOffsetY":0,"shadowColor":0,"shadowSoftness":0,"composite":false,"skipTransparentBorders":false},
+  pointy: {"orientation":"pointy-top","drawBorders":false,"borderColor":673720575,"borderThickness":1,"backgroundColor":0,"supersample":1,"gap":0,"bevelWidth":0,"bevelStrength":0,"gradient":0,"shadowOffsetX":0,"shadowOffsetY":0,"shadowColor":0,"shadowSoftness":0,"composite":false,"skipTransparentBorders":false},
+  beads: {"orientation":"flat-top","drawBorders":false,"borderColor":673720575,"borderThickness":1,"backgroundColor":269488383,"supersample":4,"gap":2,"bevelWidth":0,"bevelStrength":0,"gradient":0.45,"shadowOffsetX":1.5,"shadowOffsetY":1.5,"shadowColor":153,"shadowSoftness":2,"composite":false,"skipTransparentBorders":false},
+  tiles: {"orientation":"flat-top","drawBorders":false,"borderColor":673720575,"borderThickness":1,"backgroundColor":538976511,"supersample":2,"gap":1,"bevelWidth":2,"bevelStrength":0.35,"gradient":0,"shadowOffsetX":0,"shadowOffsetY":0,"shadowColor":0,"shadowSoftness":0,"composite":false,"skipTransparentBorders":false},
+};
+
+/** @deprecated Read presets with `presetsFromWasm(wasm).xbrz` */
+export const XBRZ_PRESETS: Record<string, Partial<XbrzOptions>> = {
+  default: {"equalColorTolerance":30,"centerDirectionBias":4,"dominantDirectionThreshold":3.6,"steepDirectionThreshold":2.2},
+  sharp: {"equalColorTolerance":20,"centerDirectionBias":4,"dominantDirectionThreshold":3.2,"steepDirectionThreshold":2},
+  smooth: {"equalColorTolerance":40,"centerDirectionBias":4,"dominantDirectionThreshold":4,"steepDirectionThreshold":2.4},
+  standard: {"equalColorTolerance":30,"centerDirectionBias":4,"dominantDirectionThreshold":3.6,"steepDirectionThreshold":2.2},
+};
@@ -54,8 +54,25 @@ export interface CrtOptions {
   enableScanlines?: boolean;
   /** Enable shadow mask (default: true) */
   enableMask?: boolean;
+  /** Width / height ratio of a source pixel (default: 1.0) */
+  pixelAspect?: number;
+  /** Curvature model used when warp is enabled (default: 'quadratic') */
+  curvature?: CrtCurvature;
+  /** Tube radius in screen widths for spherical / cylindrical curvature (default: 2.0) */
+  curvatureRadius?: number;
+  /** Eye distance from the screen face in screen widths (default: 1.5) */
+  viewingDistance?: number;
+  /** Zoom factor; values above 1 crop the picture edges (default: 1.0) */
+  overscan?: number;
+  /** Screen rotation about its vertical axis in radians (default: 0) */
+  tiltX?: number;
+  /** Screen rotation about its horizontal axis in radians (default: 0) */
+  tiltY?: number;
 }
 
+/** CRT screen curvature model */
+export type CrtCurvature = 'quadratic' | 'spherical' | 'cylindrical';
+
 /** Hexagonal grid orientation */
 export type HexOrientation = 'flat-top' | 'pointy-top';
 
@@ -73,6 +90,28 @@ export interface HexOptions {
   borderThickness?: number;
   /** Background color for out-of-bounds areas (default: 'transparent') */
   backgroundColor?: string | number;
+  /** Subsamples per axis for anti-aliased hex edges (1-8, default: 1) */
+  supersample?: number;
+  /** Gap between neighbouring cells in pixels (default: 0) */
+  gap?: number;
+  /** Width of the bevel band along the cell edge in pixels (default: 0) */
+  bevelWidth?: number;
+  /** Bevel strength, negative for a sunken emboss (-1 to 1, default: 0) */
+  bevelStrength?: number;
+  /** Radial darkening from cell centre to edge (0-1, default: 0) */
+  gradient?: number;
+  /** Drop shadow horizontal offset in pixels (default: 0) */
+  shadowOffsetX?: number;
+  /** Drop shadow vertical offset in pixels (default: 0) */
+  shadowOffsetY?: number;
+  /** Drop shadow color; its alpha is the shadow opacity (default: transparent) */
+  shadowColor?: string | number;
+  /** Width of the soft shadow edge in pixels (default: 0) */
+  shadowSoftness?: number;
+  /** Composite cells and borders over the background color (default: false) */
+  composite?: boolean;
+  /** Leave fully transparent cells without borders (default: false) */
+  skipTransparentBorders?: boolean;
 }
 
 /** xBRZ scaling options */
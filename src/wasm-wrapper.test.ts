@@ -0,0 +1,97 @@
+/**
+ * WasmRenderer tests against a stand-in for the WASM module
+ *
+ * Run with `npm run build:ts && npm test`.
+ */
+
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+
+import { WasmRenderer, curvatureToNumber, presetsFromWasm } from './wasm-wrapper.js';
+import type { RenderArtWasm, WasmCrtRenderOptions } from './wasm-wrapper.js';
+
+/** Preset JSON as produced by `crt_preset_json` */
+const CRT_PRESET_JSON: Record<string, string> = {
+  trinitron: '{"warpX":0.015,"warpY":0.02,"scanHardness":-8,"scanOpacity":0.4,"maskOpacity":0.35,"enableWarp":true,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"cylindrical","curvatureRadius":2.5,"viewingDistance":1.5,"overscan":1,"tiltX":0,"tiltY":0}',
+  consumer: '{"warpX":0.015,"warpY":0.02,"scanHardness":-4,"scanOpacity":0.55,"maskOpacity":0.45,"enableWarp":true,"enableScanlines":true,"enableMask":true,"pixelAspect":1,"curvature":"spherical","curvatureRadius":1.5,"viewingDistance":1.5,"overscan":1.05,"tiltX":0,"tiltY":0}',
+};
+
+/** `CrtRenderOptions` holding the Rust defaults */
+class FakeCrtRenderOptions implements WasmCrtRenderOptions {
+  warp_x = 0.015;
+  warp_y = 0.02;
+  scan_hardness = -4.0;
+  scan_opacity = 0.5;
+  mask_opacity = 0.3;
+  enable_warp = true;
+  enable_scanlines = true;
+  enable_mask = true;
+  output_width = 0;
+  output_height = 0;
+  pixel_aspect = 1.0;
+  curvature = 0;
+  curvature_radius = 2.0;
+  viewing_distance = 1.5;
+  overscan = 1.0;
+  tilt_x = 0;
+  tilt_y = 0;
+  freed = false;
+  free(): void {
+    this.freed = true;
+  }
+}
+
+/** Module stand-in recording the options passed to `crt_upscale_options` */
+function fakeWasm(): { wasm: RenderArtWasm; calls: FakeCrtRenderOptions[] } {
+  const memory = new WebAssembly.Memory({ initial: 1 });
+  const calls: FakeCrtRenderOptions[] = [];
+  const wasm = {
+    get_memory: () => memory,
+    CrtRenderOptions: FakeCrtRenderOptions,
+    crt_upscale_options: (_data: Uint8Array, _width: number, _height: number, _scale: number, options: FakeCrtRenderOptions) => {
+      calls.push(Object.assign(new FakeCrtRenderOptions(), options));
+      return { ptr: 0, len: 4, width: 1, height: 1, color_space: 0 };
+    },
+    crt_preset_names: () => Object.keys(CRT_PRESET_JSON),
+    crt_preset_json: (name: string) => CRT_PRESET_JSON[name],
+    hex_preset_names: () => [],
+    hex_preset_json: () => undefined,
+    xbrz_preset_names: () => [],
+    xbrz_preset_json: () => undefined,
+  };
+  return { wasm: wasm as unknown as RenderArtWasm, calls };
+}
+
+// renderCrt checks for canvas ImageData, which Node does not provide
+if (!('ImageData' in globalThis)) {
+  Object.assign(globalThis, { ImageData: class {} });
+}
+
+test('CRT presets reach the WASM renderer whole', () => {
+  const { wasm, calls } = fakeWasm();
+  const renderer = new WasmRenderer(wasm);
+  const input = { data: new Uint8Array(4), width: 1, height: 1 };
+
+  for (const [name, preset] of Object.entries(presetsFromWasm(wasm).crt)) {
+    renderer.renderCrt(input, preset);
+    const options = calls[calls.length - 1];
+    const json = JSON.parse(CRT_PRESET_JSON[name]);
+
+    assert.equal(options.curvature, curvatureToNumber(json.curvature), name);
+    assert.equal(options.curvature_radius, json.curvatureRadius, name);
+    assert.equal(options.viewing_distance, json.viewingDistance, name);
+    assert.equal(options.overscan, json.overscan, name);
+    assert.equal(options.pixel_aspect, json.pixelAspect, name);
+    assert.equal(options.tilt_x, json.tiltX, name);
+    assert.equal(options.tilt_y, json.tiltY, name);
+    assert.equal(options.scan_hardness, json.scanHardness, name);
+    assert.equal(options.mask_opacity, json.maskOpacity, name);
+  }
+  assert.equal(calls.length, 2);
+});
+
+test('CRT options left unset keep the WASM defaults', () => {
+  const { wasm, calls } = fakeWasm();
+  new WasmRenderer(wasm).renderCrt({ data: new Uint8Array(4), width: 1, height: 1 });
+  assert.deepEqual(calls[0], new FakeCrtRenderOptions());
+});
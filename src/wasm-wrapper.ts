@@ -5,7 +5,7 @@
  * for the WebAssembly module.
  */
 
-import type { CrtOptions, HexOptions, HexOrientation, ImageOutput, XbrzOptions } from './types.js';
+import type { CrtCurvature, CrtOptions, HexOptions, HexOrientation, ImageOutput, XbrzOptions } from './types.js';
 
 /** WASM upscale result structure */
 export interface WasmUpscaleResult {
@@ -17,15 +17,77 @@ export interface WasmUpscaleResult {
   width: number;
   /** Output height in pixels */
   height: number;
+  /** Colour space of the output (0 = sRGB, 1 = Display-P3, 2 = linear sRGB) */
+  color_space: number;
 }
 
-/** WASM module interface */
+/** Complete CRT configuration (Rust `CrtRenderOptions`) */
+export interface WasmCrtRenderOptions {
+  warp_x: number;
+  warp_y: number;
+  scan_hardness: number;
+  scan_opacity: number;
+  mask_opacity: number;
+  enable_warp: boolean;
+  enable_scanlines: boolean;
+  enable_mask: boolean;
+  /** Explicit output width in pixels (0 = derive from scale) */
+  output_width: number;
+  /** Explicit output height in pixels (0 = derive from scale) */
+  output_height: number;
+  pixel_aspect: number;
+  /** 0 = quadratic, 1 = spherical, 2 = cylindrical */
+  curvature: number;
+  curvature_radius: number;
+  viewing_distance: number;
+  overscan: number;
+  tilt_x: number;
+  tilt_y: number;
+  /** Release the WASM-side object */
+  free(): void;
+}
+
+/** Complete HEX configuration (Rust `HexRenderOptions`) */
+export interface WasmHexRenderOptions {
+  /** 0 = flat-top, 1 = pointy-top */
+  orientation: number;
+  draw_borders: boolean;
+  border_color: number;
+  border_thickness: number;
+  background_color: number;
+  /** Subsamples per axis for anti-aliased cell edges (1 = off, max 8) */
+  supersample: number;
+  gap: number;
+  bevel_width: number;
+  bevel_strength: number;
+  gradient: number;
+  shadow_offset_x: number;
+  shadow_offset_y: number;
+  shadow_color: number;
+  shadow_softness: number;
+  composite: boolean;
+  skip_transparent_borders: boolean;
+  /** Resample the input onto a grid this many cells wide (0 = one cell per pixel) */
+  columns: number;
+  /** Resampling filter: 0 = area average, 1 = centre sample */
+  sampling: number;
+  /** Release the WASM-side object */
+  free(): void;
+}
+
+/**
+ * WASM module interface. Exports that take or return pixels accept trailing
+ * per-call format arguments: `alpha_mode` (omitted or 0 = straight,
+ * 1 = premultiplied), `color_space` of the input (omitted or 0 = sRGB,
+ * 1 = Display-P3, 2 = linear sRGB) and `output_space` (omitted = same as the
+ * input). Engines and config colours work in the output space.
+ */
 export interface RenderArtWasm {
   /** Get WASM memory for reading output buffers */
   get_memory(): WebAssembly.Memory;
   
   /** CRT upscale with default config */
-  crt_upscale(data: Uint8Array, width: number, height: number, scale: number): WasmUpscaleResult;
+  crt_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
   
   /** CRT upscale with full config */
   crt_upscale_config(
@@ -41,10 +103,137 @@ export interface RenderArtWasm {
     enable_warp: boolean,
     enable_scanlines: boolean,
     enable_mask: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
   ): WasmUpscaleResult;
   
+  /** CRT upscale to an explicit output size with pixel aspect correction (0 = derive from scale) */
+  crt_upscale_sized(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+    pixel_aspect: number,
+    warp_x: number,
+    warp_y: number,
+    scan_hardness: number,
+    scan_opacity: number,
+    mask_opacity: number,
+    enable_warp: boolean,
+    enable_scanlines: boolean,
+    enable_mask: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** CRT upscale with explicit screen geometry (curvature: 0 quadratic, 1 spherical, 2 cylindrical) */
+  crt_upscale_geometry(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    curvature: number,
+    curvature_radius: number,
+    viewing_distance: number,
+    overscan: number,
+    tilt_x: number,
+    tilt_y: number,
+    warp_x: number,
+    warp_y: number,
+    scan_hardness: number,
+    scan_opacity: number,
+    mask_opacity: number,
+    enable_warp: boolean,
+    enable_scanlines: boolean,
+    enable_mask: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** CRT options object; a new instance holds the `crt_upscale` defaults */
+  CrtRenderOptions: new () => WasmCrtRenderOptions;
+  
+  /** CRT upscale with every option from a `CrtRenderOptions` object */
+  crt_upscale_options(data: Uint8Array, width: number, height: number, scale: number, options: WasmCrtRenderOptions, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Output dimensions of crt_upscale_options */
+  crt_options_get_dimensions(width: number, height: number, scale: number, options: WasmCrtRenderOptions): Uint32Array;
+  
+  /** Get CRT output dimensions */
+  crt_get_dimensions(
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+    pixel_aspect: number,
+  ): Uint32Array;
+  
+  /** Nearest-neighbour upscale with default config */
+  nearest_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Nearest-neighbour upscale into an optional canvas (0 = derive from scale) */
+  nearest_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+    pixel_aspect: number,
+    integer: boolean,
+    background_color: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get nearest-neighbour output dimensions */
+  nearest_get_dimensions(
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+    pixel_aspect: number,
+    integer: boolean,
+  ): Uint32Array;
+  
+  /** Sharp bilinear upscale with default config */
+  sharp_bilinear_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Sharp bilinear upscale into an optional canvas (0 = derive from scale) */
+  sharp_bilinear_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+    pixel_aspect: number,
+    background_color: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get sharp bilinear output dimensions */
+  sharp_bilinear_get_dimensions(
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+    pixel_aspect: number,
+  ): Uint32Array;
+  
   /** HEX upscale with default config */
-  hex_upscale(data: Uint8Array, width: number, height: number, scale: number): WasmUpscaleResult;
+  hex_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
   
   /** HEX upscale with full config */
   hex_upscale_config(
@@ -57,13 +246,153 @@ export interface RenderArtWasm {
     border_color: number,
     border_thickness: number,
     background_color: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
   ): WasmUpscaleResult;
   
+  /** HEX options object; a new instance holds the `hex_upscale` defaults */
+  HexRenderOptions: new () => WasmHexRenderOptions;
+  
+  /** HEX upscale with every option from a `HexRenderOptions` object */
+  hex_upscale_options(data: Uint8Array, width: number, height: number, scale: number, options: WasmHexRenderOptions, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Output dimensions of hex_upscale_options */
+  hex_options_get_dimensions(width: number, height: number, scale: number, options: WasmHexRenderOptions): Uint32Array;
+  
+  /** HEX rendering as an SVG document */
+  hex_upscale_svg(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    orientation: number,
+    draw_borders: boolean,
+    border_color: number,
+    border_thickness: number,
+    background_color: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): string;
+  
   /** Get HEX output dimensions */
   hex_get_dimensions(width: number, height: number, scale: number, orientation: number): Uint32Array;
   
+  /** Grid cell [col, row] under output position (x, y) */
+  hex_pixel_to_cell(x: number, y: number, scale: number, orientation: number): Int32Array;
+  
+  /** Centre [x, y] of a grid cell in output pixels */
+  hex_cell_center(col: number, row: number, scale: number, orientation: number): Float32Array;
+  
+  /** Corner polygon of a grid cell as [x0, y0, ..., x5, y5] */
+  hex_cell_corners(col: number, row: number, scale: number, orientation: number): Float32Array;
+  
+  /** The six neighbours of a grid cell as [col0, row0, ..., col5, row5] */
+  hex_cell_neighbours(col: number, row: number, orientation: number): Int32Array;
+  
+  /** SVG outlines of the hex grid */
+  hex_grid_svg(width: number, height: number, scale: number, orientation: number): string;
+  
+  /** JSON description of the hex grid */
+  hex_grid_json(width: number, height: number, scale: number, orientation: number): string;
+  
+  /** Triangle upscale with default config */
+  triangle_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Triangle upscale (mode: 0 pair per pixel, 1 resample with triangles of side cell_size) */
+  triangle_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    mode: number,
+    cell_size: number,
+    draw_borders: boolean,
+    border_color: number,
+    border_thickness: number,
+    background_color: number,
+    supersample: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get triangle output dimensions */
+  triangle_get_dimensions(width: number, height: number, scale: number, mode: number): Uint32Array;
+  
+  /** Brick upscale with default config */
+  brick_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Brick upscale (offset: shift of odd rows in cell widths) */
+  brick_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    offset: number,
+    draw_borders: boolean,
+    border_color: number,
+    border_thickness: number,
+    background_color: number,
+    supersample: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get brick output dimensions */
+  brick_get_dimensions(width: number, height: number, scale: number, offset: number): Uint32Array;
+  
+  /** Diamond upscale with default config */
+  diamond_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Diamond upscale (aspect: rhombus height / width, 0.5 = isometric) */
+  diamond_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    aspect: number,
+    draw_borders: boolean,
+    border_color: number,
+    border_thickness: number,
+    background_color: number,
+    supersample: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get diamond output dimensions */
+  diamond_get_dimensions(width: number, height: number, scale: number, aspect: number): Uint32Array;
+  
+  /** Voronoi upscale with default config */
+  voronoi_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Voronoi upscale (jitter 0..1 moves the cell sites, seed picks the pattern) */
+  voronoi_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    jitter: number,
+    seed: number,
+    draw_borders: boolean,
+    border_color: number,
+    border_thickness: number,
+    background_color: number,
+    supersample: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get Voronoi output dimensions */
+  voronoi_get_dimensions(width: number, height: number, scale: number): Uint32Array;
+  
   /** xBRZ upscale with default config */
-  xbrz_upscale(data: Uint8Array, width: number, height: number, scale: number): WasmUpscaleResult;
+  xbrz_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
   
   /** xBRZ upscale with full config */
   xbrz_upscale_config(
@@ -75,7 +404,306 @@ export interface RenderArtWasm {
     center_direction_bias: number,
     dominant_direction_threshold: number,
     steep_direction_threshold: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Scale2x / Scale3x / Scale4x upscale with default config */
+  scale2x_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Scale2x family upscale (transparent_edges: treat pixels outside the image as transparent) */
+  scale2x_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    transparent_edges: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** HQ2x / HQ3x / HQ4x upscale with default thresholds */
+  hqx_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** HQx upscale with custom YUV and alpha thresholds (defaults 48 / 7 / 6 / 16) */
+  hqx_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    y_threshold: number,
+    u_threshold: number,
+    v_threshold: number,
+    alpha_threshold: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** MMPX upscale with default config (scale 2 or 4) */
+  mmpx_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** MMPX upscale (transparent_edges: treat pixels outside the image as transparent) */
+  mmpx_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    transparent_edges: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Super-xBR upscale with default config (scale 2, 4 or 8) */
+  super_xbr_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Super-xBR upscale (edge_sharpness: 0-2, 1 = reference) */
+  super_xbr_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    edge_sharpness: number,
+    anti_ringing: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get Super-xBR output dimensions [width, height] */
+  super_xbr_get_dimensions(width: number, height: number, scale: number): Uint32Array;
+  
+  /** Depixelizing (vector-like) upscale with default config, any scale up to 64 */
+  depixel_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Depixelizing upscale (output_width/output_height: 0 = derive from scale) */
+  depixel_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+    similarity_threshold: number,
+    smoothness: number,
+    edge_softness: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get depixel output dimensions [width, height] */
+  depixel_get_dimensions(
+    width: number,
+    height: number,
+    scale: number,
+    output_width: number,
+    output_height: number,
+  ): Uint32Array;
+  
+  /** Trace the image into an SVG document (mode: 0 = rectilinear, 1 = smooth) */
+  vectorize_svg(data: Uint8Array, width: number, height: number, scale: number, mode: number, alpha_mode?: number, color_space?: number, output_space?: number): string;
+  
+  /** Trace into SVG with a background fill and the xBRZ tolerance used by the smooth mode */
+  vectorize_svg_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    mode: number,
+    background_color: number,
+    equal_color_tolerance: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): string;
+  
+  /** Detect the native grid of nearest-upscaled art: [cell_width, cell_height, offset_x, offset_y, columns, rows] */
+  detect_pixel_grid(data: Uint8Array, width: number, height: number, alpha_mode?: number, color_space?: number): Uint32Array;
+  
+  /** Reduce every grid block to its dominant colour (cell size 0 = detect the grid first) */
+  downscale_to_grid(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    cell_width: number,
+    cell_height: number,
+    offset_x: number,
+    offset_y: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Game Boy (DMG) LCD upscale with default config (previous = last output frame, ghosted into this one) */
+  lcd_upscale(data: Uint8Array, width: number, height: number, scale: number, previous?: Uint8Array, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** LCD upscale with full config (model: 0 = DMG, 1 = GBC / GBA, 2 = dot matrix; empty shades / color_matrix = defaults) */
+  lcd_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    model: number,
+    shades: Uint32Array,
+    color_matrix: Float32Array,
+    luminance: number,
+    grid_width: number,
+    grid_opacity: number,
+    subpixel_opacity: number,
+    shadow: number,
+    backlight_bleed: number,
+    ghosting: number,
+    background_color: number,
+    previous?: Uint8Array,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
   ): WasmUpscaleResult;
+  
+  /** Get LCD output dimensions */
+  lcd_get_dimensions(width: number, height: number, scale: number): Uint32Array;
+  
+  /** LED mosaic upscale with default config */
+  mosaic_upscale(data: Uint8Array, width: number, height: number, scale: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Mosaic upscale with full config (style: 0 = LED, 1 = brick studs, 2 = cross-stitch, 3 = perler beads) */
+  mosaic_upscale_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    scale: number,
+    style: number,
+    background_color: number,
+    shading: number,
+    glow: number,
+    supersample: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get mosaic output dimensions */
+  mosaic_get_dimensions(width: number, height: number, scale: number, style: number): Uint32Array;
+  
+  /** Pieces needed per colour, most used first: [rgba, count, rgba, count, ...] */
+  mosaic_bill_of_materials(data: Uint8Array, alpha_mode?: number, color_space?: number): Uint32Array;
+  
+  /** Exact palette, most used colours first: [rgba, count, rgba, count, ...] */
+  palette_extract(data: Uint8Array, alpha_mode?: number, color_space?: number): Uint32Array;
+  
+  /** Quantise to at most max_colors 0xRRGGBBAA colours (method: 0 = median cut, 1 = k-means) */
+  palette_quantize(data: Uint8Array, max_colors: number, method: number, alpha_mode?: number, color_space?: number): Uint32Array;
+  
+  /** Map every pixel onto the nearest palette colour */
+  palette_remap(data: Uint8Array, width: number, height: number, palette: Uint32Array, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Quantise and remap in one call */
+  palette_quantize_image(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    max_colors: number,
+    method: number,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Dither to bits_per_channel levels (method: 0-2 = Bayer 2/4/8, 3 = blue noise, 4 = Floyd-Steinberg, 5 = Atkinson, 6 = Sierra) */
+  dither_image(data: Uint8Array, width: number, height: number, method: number, bits_per_channel: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Dither with full options; a non-empty palette (0xRRGGBBAA) replaces the bit-depth target */
+  dither_image_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    method: number,
+    bits_per_channel: number,
+    palette: Uint32Array,
+    serpentine: boolean,
+    linear_light: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Add a 1px black outline around the sprite */
+  sprite_fx(data: Uint8Array, width: number, height: number, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Outline, drop shadow and glow; sizes are in source pixels, multiplied by pixel_scale */
+  sprite_fx_config(
+    data: Uint8Array,
+    width: number,
+    height: number,
+    pixel_scale: number,
+    outline_color: number,
+    outline_thickness: number,
+    inner_outline: boolean,
+    eight_connected: boolean,
+    shadow_color: number,
+    shadow_offset_x: number,
+    shadow_offset_y: number,
+    glow_color: number,
+    glow_radius: number,
+    glow_strength: number,
+    expand_canvas: boolean,
+    alpha_mode?: number,
+    color_space?: number,
+    output_space?: number,
+  ): WasmUpscaleResult;
+  
+  /** Get sprite effects output dimensions */
+  sprite_fx_get_dimensions(
+    width: number,
+    height: number,
+    pixel_scale: number,
+    outline_thickness: number,
+    inner_outline: boolean,
+    shadow_color: number,
+    shadow_offset_x: number,
+    shadow_offset_y: number,
+    glow_radius: number,
+    expand_canvas: boolean,
+  ): Uint32Array;
+  
+  /** Names of the built-in CRT presets */
+  crt_preset_names(): string[];
+  /** CRT preset as a JSON options object */
+  crt_preset_json(name: string): string | undefined;
+  /** CRT upscale with a named preset */
+  crt_upscale_preset(data: Uint8Array, width: number, height: number, scale: number, name: string, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Names of the built-in HEX presets */
+  hex_preset_names(): string[];
+  /** HEX preset as a JSON options object */
+  hex_preset_json(name: string): string | undefined;
+  /** HEX upscale with a named preset */
+  hex_upscale_preset(data: Uint8Array, width: number, height: number, scale: number, name: string, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Names of the built-in xBRZ presets */
+  xbrz_preset_names(): string[];
+  /** xBRZ preset as a JSON options object */
+  xbrz_preset_json(name: string): string | undefined;
+  /** xBRZ upscale with a named preset */
+  xbrz_upscale_preset(data: Uint8Array, width: number, height: number, scale: number, name: string, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Names of the built-in LCD presets */
+  lcd_preset_names(): string[];
+  /** LCD preset as a JSON options object */
+  lcd_preset_json(name: string): string | undefined;
+  /** LCD upscale with a named preset */
+  lcd_upscale_preset(data: Uint8Array, width: number, height: number, scale: number, name: string, previous?: Uint8Array, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
+  
+  /** Names of the built-in mosaic presets */
+  mosaic_preset_names(): string[];
+  /** Mosaic preset as a JSON options object */
+  mosaic_preset_json(name: string): string | undefined;
+  /** Mosaic upscale with a named preset */
+  mosaic_upscale_preset(data: Uint8Array, width: number, height: number, scale: number, name: string, alpha_mode?: number, color_space?: number, output_space?: number): WasmUpscaleResult;
 }
 
 /** Helper to read WASM output into ImageOutput */
@@ -111,6 +739,33 @@ export function colorToRgba(color: string | number | undefined, defaultValue: nu
   return defaultValue;
 }
 
+/** Read the preset catalogue defined in the Rust crate */
+export function presetsFromWasm(wasm: RenderArtWasm): {
+  crt: Record<string, Partial<CrtOptions>>;
+  hex: Record<string, Partial<HexOptions>>;
+  xbrz: Record<string, Partial<XbrzOptions>>;
+} {
+  const collect = <T>(names: string[], lookup: (name: string) => string | undefined): Record<string, Partial<T>> => {
+    const presets: Record<string, Partial<T>> = {};
+    for (const name of names) {
+      const json = lookup(name);
+      if (json !== undefined) presets[name] = JSON.parse(json);
+    }
+    return presets;
+  };
+  
+  return {
+    crt: collect<CrtOptions>(wasm.crt_preset_names(), (n) => wasm.crt_preset_json(n)),
+    hex: collect<HexOptions>(wasm.hex_preset_names(), (n) => wasm.hex_preset_json(n)),
+    xbrz: collect<XbrzOptions>(wasm.xbrz_preset_names(), (n) => wasm.xbrz_preset_json(n)),
+  };
+}
+
+/** Convert CrtCurvature string to number for WASM */
+export function curvatureToNumber(curvature: CrtCurvature | undefined): number {
+  return curvature === 'spherical' ? 1 : curvature === 'cylindrical' ? 2 : 0;
+}
+
 /** Convert HexOrientation string to number for WASM */
 export function orientationToNumber(orientation: HexOrientation | undefined): number {
   return orientation === 'pointy-top' ? 1 : 0;
@@ -134,22 +789,29 @@ export class WasmRenderer {
     const { width, height } = input;
     const scale = Math.min(32, Math.max(2, options.scale ?? 3));
     
-    const result = this.wasm.crt_upscale_config(
-      data,
-      width,
-      height,
-      scale,
-      options.warpX ?? 0.015,
-      options.warpY ?? 0.02,
-      options.scanHardness ?? -4.0,
-      options.scanOpacity ?? 0.5,
-      options.maskOpacity ?? 0.3,
-      options.enableWarp !== false,
-      options.enableScanlines !== false,
-      options.enableMask !== false,
-    );
-    
-    return readWasmOutput(this.wasm, result);
+    const crt = new this.wasm.CrtRenderOptions();
+    try {
+      crt.warp_x = options.warpX ?? crt.warp_x;
+      crt.warp_y = options.warpY ?? crt.warp_y;
+      crt.scan_hardness = options.scanHardness ?? crt.scan_hardness;
+      crt.scan_opacity = options.scanOpacity ?? crt.scan_opacity;
+      crt.mask_opacity = options.maskOpacity ?? crt.mask_opacity;
+      crt.enable_warp = options.enableWarp ?? crt.enable_warp;
+      crt.enable_scanlines = options.enableScanlines ?? crt.enable_scanlines;
+      crt.enable_mask = options.enableMask ?? crt.enable_mask;
+      crt.pixel_aspect = options.pixelAspect ?? crt.pixel_aspect;
+      crt.curvature = options.curvature === undefined ? crt.curvature : curvatureToNumber(options.curvature);
+      crt.curvature_radius = options.curvatureRadius ?? crt.curvature_radius;
+      crt.viewing_distance = options.viewingDistance ?? crt.viewing_distance;
+      crt.overscan = options.overscan ?? crt.overscan;
+      crt.tilt_x = options.tiltX ?? crt.tilt_x;
+      crt.tilt_y = options.tiltY ?? crt.tilt_y;
+      
+      const result = this.wasm.crt_upscale_options(data, width, height, scale, crt);
+      return readWasmOutput(this.wasm, result);
+    } finally {
+      crt.free();
+    }
   }
   
   /** Render hexagonal effect */
@@ -158,19 +820,30 @@ export class WasmRenderer {
     const { width, height } = input;
     const scale = Math.min(32, Math.max(2, options.scale ?? 16));
     
-    const result = this.wasm.hex_upscale_config(
-      data,
-      width,
-      height,
-      scale,
-      orientationToNumber(options.orientation),
-      options.drawBorders ?? false,
-      colorToRgba(options.borderColor, 0x282828FF),
-      options.borderThickness ?? 1,
-      colorToRgba(options.backgroundColor, 0x00000000),
-    );
-    
-    return readWasmOutput(this.wasm, result);
+    const hex = new this.wasm.HexRenderOptions();
+    try {
+      hex.orientation = orientationToNumber(options.orientation);
+      hex.draw_borders = options.drawBorders ?? hex.draw_borders;
+      hex.border_color = colorToRgba(options.borderColor, hex.border_color);
+      hex.border_thickness = options.borderThickness ?? hex.border_thickness;
+      hex.background_color = colorToRgba(options.backgroundColor, hex.background_color);
+      hex.supersample = options.supersample ?? hex.supersample;
+      hex.gap = options.gap ?? hex.gap;
+      hex.bevel_width = options.bevelWidth ?? hex.bevel_width;
+      hex.bevel_strength = options.bevelStrength ?? hex.bevel_strength;
+      hex.gradient = options.gradient ?? hex.gradient;
+      hex.shadow_offset_x = options.shadowOffsetX ?? hex.shadow_offset_x;
+      hex.shadow_offset_y = options.shadowOffsetY ?? hex.shadow_offset_y;
+      hex.shadow_color = colorToRgba(options.shadowColor, hex.shadow_color);
+      hex.shadow_softness = options.shadowSoftness ?? hex.shadow_softness;
+      hex.composite = options.composite ?? hex.composite;
+      hex.skip_transparent_borders = options.skipTransparentBorders ?? hex.skip_transparent_borders;
+      
+      const result = this.wasm.hex_upscale_options(data, width, height, scale, hex);
+      return readWasmOutput(this.wasm, result);
+    } finally {
+      hex.free();
+    }
   }
   
   /** Render xBRZ effect */
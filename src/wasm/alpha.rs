@@ -0,0 +1,94 @@
+//! Alpha Modes
+//! Every engine reads and writes straight (non-premultiplied) RGBA and does
+//! its filtering on premultiplied values internally, so transparent texels
+//! never bleed their colour into soft edges. Callers holding premultiplied
+//! buffers (WebGL canvases, compositors) convert at the boundary with
+//! [`to_straight`] and [`from_straight`].
+
+use std::borrow::Cow;
+
+/// How colour channels relate to alpha in an RGBA buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AlphaMode {
+    /// Colour independent of alpha (canvas `ImageData`, PNG)
+    #[default]
+    Straight = 0,
+    /// Colour already multiplied by alpha
+    Premultiplied = 1,
+}
+
+/// Scale colour channels by alpha
+#[inline]
+pub(crate) fn premultiply(c: [u8; 4]) -> [u8; 4] {
+    let a = c[3] as u32;
+    let mul = |v: u8| ((v as u32 * a + 127) / 255) as u8;
+    [mul(c[0]), mul(c[1]), mul(c[2]), c[3]]
+}
+
+/// Divide colour channels by alpha; fully transparent pixels become transparent black
+#[inline]
+pub(crate) fn unpremultiply(c: [u8; 4]) -> [u8; 4] {
+    let a = c[3] as u32;
+    if a == 0 {
+        return [0; 4];
+    }
+    let div = |v: u8| ((v as u32 * 255 + a / 2) / a).min(255) as u8;
+    [div(c[0]), div(c[1]), div(c[2]), c[3]]
+}
+
+fn map_pixels(buffer: &mut [u8], f: fn([u8; 4]) -> [u8; 4]) {
+    for px in buffer.chunks_exact_mut(4) {
+        let c = f([px[0], px[1], px[2], px[3]]);
+        px.copy_from_slice(&c);
+    }
+}
+
+/// Copy of `input` with premultiplied colour channels
+pub(crate) fn premultiplied(input: &[u8]) -> Vec<u8> {
+    let mut output = input.to_vec();
+    map_pixels(&mut output, premultiply);
+    output
+}
+
+/// Straight-alpha view of `input`, which is in `mode`
+pub fn to_straight(input: &[u8], mode: AlphaMode) -> Cow<'_, [u8]> {
+    match mode {
+        AlphaMode::Straight => Cow::Borrowed(input),
+        AlphaMode::Premultiplied => {
+            let mut straight = input.to_vec();
+            map_pixels(&mut straight, unpremultiply);
+            Cow::Owned(straight)
+        }
+    }
+}
+
+/// Convert straight-alpha `output` to `mode`
+pub fn from_straight(mut output: Vec<u8>, mode: AlphaMode) -> Vec<u8> {
+    if mode == AlphaMode::Premultiplied {
+        map_pixels(&mut output, premultiply);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_of_soft_edges() {
+        // Opaque and transparent pixels are exact; soft edges stay within rounding
+        let soft: Vec<u8> = (0..=255u8).flat_map(|a| [200, 100, 30, a]).collect();
+        let pre = from_straight(soft.clone(), AlphaMode::Premultiplied);
+        assert_eq!(pre[255 * 4..], [200, 100, 30, 255]);
+        assert_eq!(pre[64 * 4..65 * 4], [50, 25, 8, 64]);
+
+        let back = to_straight(&pre, AlphaMode::Premultiplied);
+        assert_eq!(back[..4], [0, 0, 0, 0]);
+        for (a, (orig, round)) in soft.chunks(4).zip(back.chunks(4)).enumerate().skip(1) {
+            let tolerance = 255 / a + 1;
+            assert!(orig.iter().zip(round).all(|(o, r)| o.abs_diff(*r) as usize <= tolerance), "alpha {a}");
+        }
+
+        assert!(matches!(to_straight(&soft, AlphaMode::Straight), Cow::Borrowed(_)));
+    }
+}
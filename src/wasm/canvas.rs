@@ -0,0 +1,156 @@
+//! Output Canvas
+//! Helpers shared by the engines that draw into a canvas: placing a scaled
+//! picture inside an explicit output size (letterboxing) and unpacking
+//! `0xRRGGBBAA` colours.
+
+/// Largest canvas side in pixels; explicit output sizes from the caller are
+/// clamped to it
+pub(crate) const MAX_CANVAS_SIDE: usize = 16384;
+
+/// Accepted pixel aspect ratios (width per unit of height)
+const PIXEL_ASPECT_RANGE: (f32, f32) = (0.125, 8.0);
+
+/// Placement of the picture inside the output canvas; anything outside the
+/// content rectangle is letterboxed
+pub(crate) struct Letterbox {
+    pub out_w: usize,
+    pub out_h: usize,
+    pub content_x: f32,
+    pub content_y: f32,
+    pub content_w: f32,
+    pub content_h: f32,
+}
+
+impl Letterbox {
+    /// Fit a `src_w` x `src_h` picture scaled by `scale` (clamped to 1..=32)
+    /// with source pixels `pixel_aspect` wide per unit of height. A missing
+    /// output dimension follows the picture's aspect ratio. With `integer`
+    /// every source pixel covers the same whole number of output pixels
+    /// per axis. Both canvas sides are clamped to `1..=MAX_CANVAS_SIDE`.
+    pub(crate) fn new(
+        src_w: usize,
+        src_h: usize,
+        scale: usize,
+        output_width: Option<usize>,
+        output_height: Option<usize>,
+        pixel_aspect: f32,
+        integer: bool,
+    ) -> Self {
+        let scale = scale.clamp(1, 32) as f32;
+        let par = if pixel_aspect.is_finite() && pixel_aspect > 0.0 {
+            pixel_aspect.clamp(PIXEL_ASPECT_RANGE.0, PIXEL_ASPECT_RANGE.1)
+        } else {
+            1.0
+        };
+        let (src_wf, src_hf) = (src_w.max(1) as f32, src_h.max(1) as f32);
+
+        // Picture size in output pixels before fitting to an explicit canvas
+        let natural_w = (src_wf * par * scale).max(1.0);
+        let natural_h = (src_hf * scale).max(1.0);
+
+        let (out_w, out_h) = match (output_width, output_height) {
+            (Some(w), Some(h)) => (w, h),
+            (Some(w), None) => (w, (w as f32 * natural_h / natural_w).round() as usize),
+            (None, Some(h)) => ((h as f32 * natural_w / natural_h).round() as usize, h),
+            (None, None) => (natural_w.round() as usize, natural_h.round() as usize),
+        };
+        let out_w = out_w.clamp(1, MAX_CANVAS_SIDE);
+        let out_h = out_h.clamp(1, MAX_CANVAS_SIDE);
+
+        // Fit the picture inside the canvas, preserving its aspect ratio
+        let fit = (out_w as f32 / natural_w).min(out_h as f32 / natural_h);
+        let (content_w, content_h) = if integer {
+            // Largest vertical factor whose rounded horizontal partner still fits
+            let mut sy = ((natural_h * fit / src_hf) as usize).max(1);
+            let sx_for = |sy: usize| ((sy as f32 * par).round() as usize).max(1);
+            while sy > 1 && sx_for(sy) as f32 * src_wf > out_w as f32 {
+                sy -= 1;
+            }
+            (sx_for(sy) as f32 * src_wf, sy as f32 * src_hf)
+        } else {
+            (natural_w * fit, natural_h * fit)
+        };
+
+        // Whole-pixel offsets keep integer-scaled pictures on the pixel grid
+        Self {
+            out_w,
+            out_h,
+            content_x: ((out_w as f32 - content_w) * 0.5).floor(),
+            content_y: ((out_h as f32 - content_h) * 0.5).floor(),
+            content_w,
+            content_h,
+        }
+    }
+
+    /// Size in bytes of the RGBA canvas
+    pub(crate) fn buffer_len(&self) -> Option<usize> {
+        rgba_len(self.out_w, self.out_h)
+    }
+
+    /// Source coordinate (in source pixels) of output pixel centre `x` / `y`,
+    /// or `None` in the letterbox
+    #[inline(always)]
+    pub(crate) fn source_x(&self, x: usize, src_w: usize) -> Option<f32> {
+        let u = (x as f32 + 0.5 - self.content_x) / self.content_w;
+        (0.0..1.0).contains(&u).then_some(u * src_w as f32)
+    }
+
+    #[inline(always)]
+    pub(crate) fn source_y(&self, y: usize, src_h: usize) -> Option<f32> {
+        let v = (y as f32 + 0.5 - self.content_y) / self.content_h;
+        (0.0..1.0).contains(&v).then_some(v * src_h as f32)
+    }
+}
+
+/// Size in bytes of a `width` x `height` RGBA buffer, or `None` if it does not
+/// fit the address space
+pub(crate) fn rgba_len(width: usize, height: usize) -> Option<usize> {
+    width.checked_mul(height)?.checked_mul(4)
+}
+
+/// Unpack a `0xRRGGBBAA` colour
+pub(crate) fn rgba_parts(color: u32) -> [u8; 4] {
+    [
+        ((color >> 24) & 0xFF) as u8,
+        ((color >> 16) & 0xFF) as u8,
+        ((color >> 8) & 0xFF) as u8,
+        (color & 0xFF) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letterbox_fit() {
+        // 8:7 pixels fitted into 1080p: pillarboxed, centred on whole pixels
+        let fit = Letterbox::new(256, 224, 4, Some(1920), Some(1080), 8.0 / 7.0, false);
+        assert_eq!((fit.out_w, fit.out_h), (1920, 1080));
+        assert_eq!(fit.content_h, 1080.0);
+        assert_eq!(fit.content_x, ((1920.0 - fit.content_w) * 0.5).floor());
+        assert_eq!(fit.source_x(0, 256), None);
+        assert!(fit.source_x(960, 256).is_some());
+
+        // Integer fit: 4x vertically, 5x (4 * 8/7 rounded) horizontally
+        let fit = Letterbox::new(256, 224, 4, Some(1920), Some(1080), 8.0 / 7.0, true);
+        assert_eq!((fit.content_w, fit.content_h), (1280.0, 896.0));
+        assert_eq!((fit.content_x, fit.content_y), (320.0, 92.0));
+    }
+
+    #[test]
+    fn test_letterbox_bounds() {
+        // Oversized canvases and extreme pixel aspects stay allocatable
+        let fit = Letterbox::new(4, 4, 4, Some(usize::MAX), Some(1 << 20), 1e9, false);
+        assert_eq!((fit.out_w, fit.out_h), (MAX_CANVAS_SIDE, MAX_CANVAS_SIDE));
+        assert_eq!(fit.buffer_len(), Some(MAX_CANVAS_SIDE * MAX_CANVAS_SIDE * 4));
+        let fit = Letterbox::new(4, 4, 4, None, None, 1e9, false);
+        assert_eq!((fit.out_w, fit.out_h), (128, 16));
+        assert_eq!(rgba_len(usize::MAX, 2), None);
+    }
+
+    #[test]
+    fn test_rgba_parts() {
+        assert_eq!(rgba_parts(0x11223344), [0x11, 0x22, 0x33, 0x44]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Colour Spaces
+//! Pixel data is 8-bit RGBA in one of a few RGB spaces. Each call converts
+//! its input to the requested output space with [`convert_in_place`] and
+//! the engines work in that space; the colour-aware stages (CRT gamma, xBRZ
+//! colour distance, linear-light dithering) take it from their config.
+
+use std::sync::OnceLock;
+
+/// RGB colour space of an RGBA buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// sRGB primaries and transfer curve (canvas `ImageData`, PNG)
+    #[default]
+    Srgb = 0,
+    /// DCI-P3 primaries with a D65 white point and the sRGB transfer curve
+    DisplayP3 = 1,
+    /// sRGB primaries storing linear-light values (EXR-derived data)
+    LinearSrgb = 2,
+}
+
+/// Linear sRGB -> linear Display-P3
+const SRGB_TO_P3: [[f32; 3]; 3] = [
+    [0.822_462, 0.177_538, 0.0],
+    [0.033_194, 0.966_806, 0.0],
+    [0.017_083, 0.072_397, 0.910_520],
+];
+
+/// Linear Display-P3 -> linear sRGB
+const P3_TO_SRGB: [[f32; 3]; 3] = [
+    [1.224_94, -0.224_94, 0.0],
+    [-0.042_057, 1.042_057, 0.0],
+    [-0.019_638, -0.078_636, 1.098_274],
+];
+
+/// Entries of the linear -> stored value table used by [`convert_in_place`]
+const ENCODE_STEPS: usize = 1 << 14;
+
+#[inline]
+pub(crate) fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+#[inline]
+pub(crate) fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+impl ColorSpace {
+    /// Whether stored values are linear light
+    pub fn is_linear(self) -> bool {
+        self == ColorSpace::LinearSrgb
+    }
+
+    /// Linear-light value of a stored channel value in `0..=1`
+    pub fn decode(self, c: f32) -> f32 {
+        if self.is_linear() { c } else { srgb_to_linear(c) }
+    }
+
+    /// Stored channel value of a linear-light value in `0..=1`
+    pub fn encode(self, c: f32) -> f32 {
+        if self.is_linear() { c } else { linear_to_srgb(c) }
+    }
+}
+
+/// 8-bit sRGB encoding of each 8-bit linear value
+pub(crate) fn linear_to_srgb_lut() -> &'static [u8; 256] {
+    static LUT: OnceLock<[u8; 256]> = OnceLock::new();
+    LUT.get_or_init(|| std::array::from_fn(|i| (linear_to_srgb(i as f32 / 255.0) * 255.0 + 0.5) as u8))
+}
+
+/// Convert RGBA `buffer` from `from` to `to` in place. Alpha is untouched and
+/// colours outside the target gamut are clipped.
+pub fn convert_in_place(buffer: &mut [u8], from: ColorSpace, to: ColorSpace) {
+    if from == to {
+        return;
+    }
+
+    let decode: [f32; 256] = std::array::from_fn(|i| from.decode(i as f32 / 255.0));
+    let encode: Vec<u8> = (0..ENCODE_STEPS)
+        .map(|i| (to.encode(i as f32 / (ENCODE_STEPS - 1) as f32) * 255.0 + 0.5) as u8)
+        .collect();
+    let matrix = match (from == ColorSpace::DisplayP3, to == ColorSpace::DisplayP3) {
+        (false, true) => Some(SRGB_TO_P3),
+        (true, false) => Some(P3_TO_SRGB),
+        _ => None,
+    };
+
+    for px in buffer.chunks_exact_mut(4) {
+        let rgb = [decode[px[0] as usize], decode[px[1] as usize], decode[px[2] as usize]];
+        let rgb = match &matrix {
+            Some(m) => m.map(|row| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]),
+            None => rgb,
+        };
+        for (c, v) in px.iter_mut().zip(rgb) {
+            *c = encode[(v.clamp(0.0, 1.0) * (ENCODE_STEPS - 1) as f32 + 0.5) as usize];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(input: &[u8], from: ColorSpace, to: ColorSpace) -> Vec<u8> {
+        let mut output = input.to_vec();
+        convert_in_place(&mut output, from, to);
+        output
+    }
+
+    #[test]
+    fn test_conversions() {
+        let red = [255, 0, 0, 200];
+        let p3 = convert(&red, ColorSpace::Srgb, ColorSpace::DisplayP3);
+        assert_eq!(*p3, [234, 51, 35, 200]);
+
+        // sRGB 188 is half linear light
+        let grey = [188, 188, 188, 255];
+        assert_eq!(*convert(&grey, ColorSpace::Srgb, ColorSpace::LinearSrgb), [128, 128, 128, 255]);
+        assert_eq!(*convert(&[128, 128, 128, 255], ColorSpace::LinearSrgb, ColorSpace::Srgb), grey);
+
+        // Everything in the sRGB gamut survives a round trip through P3, up to
+        // 8-bit quantisation (amplified near black on saturated colours)
+        let colors: Vec<u8> = (0..=255u8).step_by(15).flat_map(|v| [v, 255 - v, v / 2, 255]).collect();
+        let p3 = convert(&colors, ColorSpace::Srgb, ColorSpace::DisplayP3);
+        let back = convert(&p3, ColorSpace::DisplayP3, ColorSpace::Srgb);
+        assert!(colors.iter().zip(back.iter()).all(|(a, b)| a.abs_diff(*b) <= 3));
+
+        // P3's most saturated green lies outside sRGB and clips
+        let green = convert(&[0, 255, 0, 255], ColorSpace::DisplayP3, ColorSpace::Srgb);
+        assert_eq!([green[0], green[1]], [0, 255]);
+    }
+}
@@ -1,6 +1,28 @@
 //! CRT Effect Rendering Engine
 //! Optimized with Integer Math, separable warp logic, and Gamma LUT.
 
+mod simd;
+
+use crate::alpha;
+use crate::canvas::Letterbox;
+use crate::color_space::ColorSpace;
+
+/// Horizontal strength of the quadratic warp relative to `warp_x`
+const QUADRATIC_WARP_X: f32 = 0.3;
+/// Vertical strength of the quadratic warp relative to `warp_y`
+const QUADRATIC_WARP_Y: f32 = 0.4;
+
+/// Screen geometry used when `enable_warp` is set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrtCurvature {
+    /// Separable quadratic barrel warp driven by `warp_x` / `warp_y`
+    Quadratic = 0,
+    /// Spherical tube face seen from `viewing_distance`
+    Spherical = 1,
+    /// Horizontal-only curvature of aperture-grille (Trinitron) tubes
+    Cylindrical = 2,
+}
+
 /// CRT configuration
 #[derive(Clone, Copy)]
 pub struct CrtConfig {
@@ -12,6 +34,27 @@ pub struct CrtConfig {
     pub enable_warp: bool,
     pub enable_scanlines: bool,
     pub enable_mask: bool,
+    /// Explicit output width in pixels (`None` derives it from `scale`)
+    pub output_width: Option<usize>,
+    /// Explicit output height in pixels (`None` derives it from `scale`)
+    pub output_height: Option<usize>,
+    /// Width / height ratio of a source pixel (e.g. 8/7 for 256x224 console output)
+    pub pixel_aspect: f32,
+    /// Curvature model (only applied when `enable_warp` is set)
+    pub curvature: CrtCurvature,
+    /// Tube radius in screen widths for the spherical / cylindrical models
+    pub curvature_radius: f32,
+    /// Eye distance from the screen face in screen widths
+    pub viewing_distance: f32,
+    /// Zoom factor; values above 1.0 crop the picture edges like a real overscan
+    pub overscan: f32,
+    /// Rotation of the screen about its vertical axis, in radians
+    pub tilt_x: f32,
+    /// Rotation of the screen about its horizontal axis, in radians
+    pub tilt_y: f32,
+    /// Colour space of the input; linear data skips gamma expansion and
+    /// comes out linear
+    pub color_space: ColorSpace,
 }
 
 impl Default for CrtConfig {
@@ -25,10 +68,286 @@ impl Default for CrtConfig {
             enable_warp: true,
             enable_scanlines: true,
             enable_mask: true,
+            output_width: None,
+            output_height: None,
+            pixel_aspect: 1.0,
+            curvature: CrtCurvature::Quadratic,
+            curvature_radius: 2.0,
+            viewing_distance: 1.5,
+            overscan: 1.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            color_space: ColorSpace::Srgb,
         }
     }
 }
 
+impl CrtConfig {
+    /// Names accepted by [`CrtConfig::preset`], in display order
+    pub const PRESET_NAMES: &'static [&'static str] =
+        &["default", "authentic", "subtle", "flat", "trinitron", "consumer"];
+
+    /// Look up a named preset. This catalogue is the one shared with the
+    /// TypeScript front end through the wasm exports.
+    pub fn preset(name: &str) -> Option<Self> {
+        let base = Self::default();
+        Some(match name {
+            "default" => base,
+            "authentic" => Self {
+                warp_x: 0.02,
+                warp_y: 0.025,
+                scan_hardness: -6.0,
+                scan_opacity: 0.6,
+                mask_opacity: 0.4,
+                ..base
+            },
+            "subtle" => Self {
+                warp_x: 0.008,
+                warp_y: 0.01,
+                scan_hardness: -3.0,
+                scan_opacity: 0.3,
+                mask_opacity: 0.15,
+                ..base
+            },
+            "flat" => Self {
+                warp_x: 0.0,
+                warp_y: 0.0,
+                enable_warp: false,
+                ..base
+            },
+            "trinitron" => Self {
+                curvature: CrtCurvature::Cylindrical,
+                curvature_radius: 2.5,
+                scan_hardness: -8.0,
+                scan_opacity: 0.4,
+                mask_opacity: 0.35,
+                ..base
+            },
+            "consumer" => Self {
+                curvature: CrtCurvature::Spherical,
+                curvature_radius: 1.5,
+                viewing_distance: 1.5,
+                overscan: 1.05,
+                scan_hardness: -4.0,
+                scan_opacity: 0.55,
+                mask_opacity: 0.45,
+                ..base
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Ray-cast screen model for curvature and tilt that cannot be expressed
+/// with the separable quadratic warp. Maps normalized output coordinates to
+/// normalized picture coordinates.
+pub(crate) struct CrtProjection {
+    curvature: Option<CrtCurvature>,
+    quadratic: (f32, f32),
+    /// Picture height / width
+    aspect: f32,
+    radius: f32,
+    distance: f32,
+    eye: [f32; 3],
+    /// Inverse screen tilt, applied to view rays
+    rotation: [[f32; 3]; 3],
+    /// Surface units -> picture units, including overscan
+    fit: f32,
+}
+
+impl CrtProjection {
+    fn new(config: &CrtConfig, layout: &Letterbox) -> Self {
+        let curvature = config.enable_warp.then_some(config.curvature);
+        let distance = config.viewing_distance.max(0.1);
+        let (sx, cx) = (-config.tilt_x).sin_cos();
+        let (sy, cy) = (-config.tilt_y).sin_cos();
+        // Ry(-tilt_x) * Rx(-tilt_y)
+        let rotation = [
+            [cx, sx * sy, sx * cy],
+            [0.0, cy, -sy],
+            [-sx, cx * sy, cx * cy],
+        ];
+
+        let mut projection = Self {
+            curvature,
+            quadratic: (QUADRATIC_WARP_X * config.warp_x, QUADRATIC_WARP_Y * config.warp_y),
+            aspect: layout.content_h / layout.content_w,
+            radius: config.curvature_radius.max(0.5),
+            distance,
+            eye: [0.0, 0.0, -distance],
+            rotation: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            fit: 1.0,
+        };
+
+        // Scale so the untilted picture edges touch the canvas along the tighter axis
+        if let Some(CrtCurvature::Spherical | CrtCurvature::Cylindrical) = curvature {
+            let half_h = 0.5 * projection.aspect;
+            let edge_x = projection.surface(0.5, 0.0).map_or(0.5, |(x, _)| x);
+            let edge_y = projection.surface(0.0, half_h).map_or(half_h, |(_, y)| y);
+            projection.fit = (0.5 / edge_x).max(half_h / edge_y);
+        }
+        projection.fit /= overscan_factor(config);
+
+        projection.eye = mat_mul(&rotation, projection.eye);
+        projection.rotation = rotation;
+        projection
+    }
+
+    /// Whether the geometry reduces to the separable row-linear warp
+    fn is_separable(config: &CrtConfig) -> bool {
+        config.tilt_x == 0.0
+            && config.tilt_y == 0.0
+            && (!config.enable_warp || config.curvature == CrtCurvature::Quadratic)
+    }
+
+    /// Intersect the view ray through screen point (px, py) with the tube face.
+    /// Returns surface coordinates (arc lengths) in screen-width units.
+    fn surface(&self, px: f32, py: f32) -> Option<(f32, f32)> {
+        let o = self.eye;
+        let d = mat_mul(&self.rotation, [px, py, self.distance]);
+        let r = self.radius;
+
+        match self.curvature {
+            Some(CrtCurvature::Spherical) => {
+                let oc = [o[0], o[1], o[2] - r];
+                let t = ray_quadratic(dot(d, d), 2.0 * dot(d, oc), dot(oc, oc) - r * r)?;
+                let h = [o[0] + t * d[0], o[1] + t * d[1], o[2] + t * d[2]];
+                let rho = (h[0] * h[0] + h[1] * h[1]).sqrt();
+                if rho < 1e-6 {
+                    return Some((0.0, 0.0));
+                }
+                let cos_phi = ((r - h[2]) / r).clamp(-1.0, 1.0);
+                let arc = r * cos_phi.acos();
+                Some((h[0] / rho * arc, h[1] / rho * arc))
+            }
+            Some(CrtCurvature::Cylindrical) => {
+                let ocx = o[0];
+                let ocz = o[2] - r;
+                let t = ray_quadratic(
+                    d[0] * d[0] + d[2] * d[2],
+                    2.0 * (d[0] * ocx + d[2] * ocz),
+                    ocx * ocx + ocz * ocz - r * r,
+                )?;
+                let hx = o[0] + t * d[0];
+                let hy = o[1] + t * d[1];
+                let hz = o[2] + t * d[2];
+                Some((r * hx.atan2(r - hz), hy))
+            }
+            // Flat face (quadratic warp is applied afterwards in picture space)
+            _ => {
+                if d[2].abs() < 1e-6 {
+                    return None;
+                }
+                let t = -o[2] / d[2];
+                if t <= 0.0 {
+                    return None;
+                }
+                Some((o[0] + t * d[0], o[1] + t * d[1]))
+            }
+        }
+    }
+
+    /// Map normalized output coordinates to normalized picture coordinates.
+    /// Points that miss the tube map outside `[0, 1)`.
+    #[inline]
+    pub(crate) fn map(&self, u: f32, v: f32) -> (f32, f32) {
+        let Some((sx, sy)) = self.surface(u - 0.5, (v - 0.5) * self.aspect) else {
+            return (-1.0, -1.0);
+        };
+        let mut wu = sx * self.fit + 0.5;
+        let mut wv = sy * self.fit / self.aspect + 0.5;
+
+        if self.curvature == Some(CrtCurvature::Quadratic) {
+            let (coeff_x, coeff_y) = self.quadratic;
+            let dc_x = wu - 0.5;
+            let dc_y = wv - 0.5;
+            wu = dc_x * (1.0 + dc_y * dc_y * coeff_x) + 0.5;
+            wv = dc_y * (1.0 + dc_x * dc_x * coeff_y) + 0.5;
+        }
+
+        (wu, wv)
+    }
+}
+
+fn overscan_factor(config: &CrtConfig) -> f32 {
+    if config.overscan.is_finite() && config.overscan > 0.0 {
+        config.overscan
+    } else {
+        1.0
+    }
+}
+
+#[inline(always)]
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline(always)]
+fn mat_mul(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    [dot(m[0], v), dot(m[1], v), dot(m[2], v)]
+}
+
+/// Nearest positive root of `a*t^2 + b*t + c = 0`
+#[inline(always)]
+fn ray_quadratic(a: f32, b: f32, c: f32) -> Option<f32> {
+    let disc = b * b - 4.0 * a * c;
+    if disc < 0.0 || a == 0.0 {
+        return None;
+    }
+    let sq = disc.sqrt();
+    let t0 = (-b - sq) / (2.0 * a);
+    let t1 = (-b + sq) / (2.0 * a);
+    if t0 > 0.0 {
+        Some(t0)
+    } else if t1 > 0.0 {
+        Some(t1)
+    } else {
+        None
+    }
+}
+
+/// Letterbox placement of the (possibly aspect-corrected) picture
+fn layout(src_w: usize, src_h: usize, scale: usize, config: &CrtConfig) -> Letterbox {
+    Letterbox::new(
+        src_w, src_h, scale.clamp(2, 32),
+        config.output_width, config.output_height, config.pixel_aspect, false,
+    )
+}
+
+/// Compute the output dimensions `crt_upscale` will produce for the given config
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &CrtConfig,
+) -> (usize, usize) {
+    if src_w == 0 || src_h == 0 {
+        return (0, 0);
+    }
+    let layout = layout(src_w, src_h, scale, config);
+    (layout.out_w, layout.out_h)
+}
+
+/// Row-invariant state shared by the scalar loop and the SIMD span kernel
+pub(crate) struct CrtRow<'a> {
+    pub input: &'a [u8],
+    pub src_w: usize,
+    pub src_h: usize,
+    pub content_x: f32,
+    pub inv_content_w: f32,
+    pub v_norm: f32,
+    /// Per-pixel geometry; `None` selects the row-linear warp below
+    pub projection: Option<&'a CrtProjection>,
+    pub row_warp_scale: f32,
+    pub row_warp_offset: f32,
+    pub y_warp_base: f32,
+    pub y_warp_coeff: f32,
+    pub scan_val: f32,
+    pub mask_lut: &'a [[f32; 3]; 6],
+    /// Input is linear light, so the x^2 gamma expansion is skipped
+    pub linear_input: bool,
+}
+
 pub fn crt_upscale(
     input: &[u8],
     src_w: usize,
@@ -36,16 +355,42 @@ pub fn crt_upscale(
     scale: usize,
     config: &CrtConfig,
 ) -> Vec<u8> {
-    let scale = scale.clamp(2, 32);
-    let out_w = src_w * scale;
-    let out_h = src_h * scale;
-    let mut output = vec![0u8; out_w * out_h * 4];
+    render(input, src_w, src_h, scale, config, true)
+}
+
+fn render(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &CrtConfig,
+    use_simd: bool,
+) -> Vec<u8> {
+    if src_w == 0 || src_h == 0 {
+        return Vec::new();
+    }
+    let layout = layout(src_w, src_h, scale, config);
+    let out_w = layout.out_w;
+    let out_h = layout.out_h;
+    let Some(len) = layout.buffer_len() else {
+        return Vec::new();
+    };
+    let mut output = vec![0u8; len];
 
     // --- Pre-calculation Phase ---
 
+    // 0. Premultiplied copy of the input so bilinear taps on transparent
+    // texels do not drag their colour into soft edges
+    let premultiplied = alpha::premultiplied(input);
+    let input = premultiplied.as_slice();
+
     // 1. Gamma Correction LUT (Linear -> sRGB approximation)
-    // Avoids per-pixel sqrt()
+    // Avoids per-pixel sqrt(); linear input stays linear on the way out
+    let linear_input = config.color_space.is_linear();
     let gamma_lut: Vec<u8> = (0..=255).map(|i| {
+        if linear_input {
+            return i as u8;
+        }
         let f = (i as f32 / 255.0).sqrt();
         (f * 255.0).clamp(0.0, 255.0) as u8
     }).collect();
@@ -79,44 +424,76 @@ pub fn crt_upscale(
 
     let src_w_f = src_w as f32;
     let src_h_f = src_h as f32;
-    let out_w_f = out_w as f32;
-    let out_h_f = out_h as f32;
+    let inv_content_w = 1.0 / layout.content_w;
+    let inv_content_h = 1.0 / layout.content_h;
+
+    let projection = (!CrtProjection::is_separable(config))
+        .then(|| CrtProjection::new(config, &layout));
+    let zoom = 1.0 / overscan_factor(config);
+    let y_warp_coeff = if config.enable_warp { QUADRATIC_WARP_Y * config.warp_y } else { 0.0 };
 
     // --- Processing Phase ---
 
     for y in 0..out_h {
-        let v_norm = y as f32 / out_h_f;
+        let v_norm = (y as f32 - layout.content_y) * inv_content_h;
+        if !(0.0..1.0).contains(&v_norm) {
+            continue; // Letterbox row
+        }
         let dc_y = (v_norm - 0.5).abs();
         let dc2_y = dc_y * dc_y;
 
         // Optimization: Calculate Row-Invariant Warp factors
         // For a specific Y, the X-warp function is linear: u' = u * factor + offset
-        let (row_warp_scale, row_warp_offset) = if config.enable_warp {
-            let warp_x_factor = 1.0 + (dc2_y * (0.3 * config.warp_x));
+        // Overscan zoom is linear too and folds into the same factors
+        let (row_warp_scale, row_warp_offset) = {
+            let warp_x_factor = if config.enable_warp {
+                1.0 + (dc2_y * (QUADRATIC_WARP_X * config.warp_x))
+            } else {
+                1.0
+            } * zoom;
             // u' = (u - 0.5) * factor + 0.5
             // u' = u * factor - 0.5 * factor + 0.5
             (warp_x_factor, 0.5 - 0.5 * warp_x_factor)
-        } else {
-            (1.0, 0.0)
         };
         
         // Y-warp depends on X, so we calculate the constant part of the Y-warp equation
-        let y_warp_base = if config.enable_warp {
-             v_norm - 0.5
-        } else {
-             0.0 
-        };
+        let y_warp_base = (v_norm - 0.5) * zoom;
 
-        // Scanline intensity for this row
+        // Scanline intensity for this row (one scanline per source row)
         let src_y_pos = v_norm * src_h_f;
         let scan_idx = (src_y_pos.fract() * 100.0) as usize;
         let scan_val = unsafe { *scan_lut.get_unchecked(scan_idx.min(100)) };
 
-        for x in 0..out_w {
-            let u_norm = x as f32 / out_w_f;
+        // Vectorised span first (4 pixels per iteration); the scalar loop handles the tail
+        let x_start = if use_simd {
+            let row = CrtRow {
+                input,
+                src_w,
+                src_h,
+                content_x: layout.content_x,
+                inv_content_w,
+                v_norm,
+                projection: projection.as_ref(),
+                row_warp_scale,
+                row_warp_offset,
+                y_warp_base,
+                y_warp_coeff,
+                scan_val,
+                mask_lut: &mask_lut,
+                linear_input,
+            };
+            simd::shade_span(&row, &mut output[y * out_w * 4..(y + 1) * out_w * 4])
+        } else {
+            0
+        };
+
+        for x in x_start..out_w {
+            let u_norm = (x as f32 - layout.content_x) * inv_content_w;
 
             // Optimized Warp Logic
-            let (warped_u, warped_v) = if config.enable_warp {
+            let (warped_u, warped_v) = if let Some(projection) = &projection {
+                projection.map(u_norm, v_norm)
+            } else {
                 // Apply pre-calculated linear X-warp
                 let wu = u_norm * row_warp_scale + row_warp_offset;
 
@@ -124,15 +501,13 @@ pub fn crt_upscale(
                 // wv = (v - 0.5) * (1.0 + dc2_x * coeff) + 0.5
                 let dc_x = (u_norm - 0.5).abs();
                 let dc2_x = dc_x * dc_x;
-                let wv = y_warp_base * (1.0 + (dc2_x * (0.4 * config.warp_y))) + 0.5;
+                let wv = y_warp_base * (1.0 + (dc2_x * y_warp_coeff)) + 0.5;
 
                 (wu, wv)
-            } else {
-                (u_norm, v_norm)
             };
 
             // Bounds check
-            if warped_u < 0.0 || warped_u >= 1.0 || warped_v < 0.0 || warped_v >= 1.0 {
+            if !(0.0..1.0).contains(&warped_u) || !(0.0..1.0).contains(&warped_v) {
                 continue; // Pixel remains 0 (black)
             }
 
@@ -166,35 +541,38 @@ pub fn crt_upscale(
                  )
             };
 
-            // Calculate Alpha first to early exit
+            // Calculate Alpha first to early exit on pixels that round to transparent
             let a_f = unsafe {
                 (*p00.add(3) as f32 * iwx + *p10.add(3) as f32 * wx) * iwy +
                 (*p01.add(3) as f32 * iwx + *p11.add(3) as f32 * wx) * wy
             };
 
-            if a_f < 1.0 { continue; }
+            if a_f < 0.5 { continue; }
 
-            // Color Interpolation
+            // Color Interpolation (premultiplied, divided back to straight colour)
             // We do the multiplication in floats, but avoid powi(2) for gamma expansion.
             // Approximating Gamma 2.0 expansion as simple squaring is fast and accurate enough for CRT effects.
             
             let mut r = unsafe {
                 ((*p00 as f32 * iwx + *p10 as f32 * wx) * iwy +
-                 (*p01 as f32 * iwx + *p11 as f32 * wx) * wy) / 255.0
-            };
+                 (*p01 as f32 * iwx + *p11 as f32 * wx) * wy) / a_f
+            }.min(1.0);
             let mut g = unsafe {
                 ((*p00.add(1) as f32 * iwx + *p10.add(1) as f32 * wx) * iwy +
-                 (*p01.add(1) as f32 * iwx + *p11.add(1) as f32 * wx) * wy) / 255.0
-            };
+                 (*p01.add(1) as f32 * iwx + *p11.add(1) as f32 * wx) * wy) / a_f
+            }.min(1.0);
             let mut b = unsafe {
                 ((*p00.add(2) as f32 * iwx + *p10.add(2) as f32 * wx) * iwy +
-                 (*p01.add(2) as f32 * iwx + *p11.add(2) as f32 * wx) * wy) / 255.0
-            };
+                 (*p01.add(2) as f32 * iwx + *p11.add(2) as f32 * wx) * wy) / a_f
+            }.min(1.0);
 
             // Apply Gamma Expansion (Approximate sRGB -> Linear with x^2)
-            r *= r;
-            g *= g;
-            b *= b;
+            // Display-P3 shares the sRGB curve; linear input needs none
+            if !linear_input {
+                r *= r;
+                g *= g;
+                b *= b;
+            }
 
             // Bloom Estimation
             let luma = r * 0.299 + g * 0.587 + b * 0.114;
@@ -209,9 +587,9 @@ pub fn crt_upscale(
             let mask = unsafe { mask_lut.get_unchecked(x % 6) };
             let ibloom = 1.0 - bloom;
             
-            r = r * (mask[0] * ibloom + bloom);
-            g = g * (mask[1] * ibloom + bloom);
-            b = b * (mask[2] * ibloom + bloom);
+            r *= mask[0] * ibloom + bloom;
+            g *= mask[1] * ibloom + bloom;
+            b *= mask[2] * ibloom + bloom;
 
             // Output with Gamma Correction LUT (Linear -> sRGB)
             let out_idx = (y * out_w + x) * 4;
@@ -219,10 +597,149 @@ pub fn crt_upscale(
                 *output.get_unchecked_mut(out_idx)     = *gamma_lut.get_unchecked((r * 255.0) as usize & 0xFF);
                 *output.get_unchecked_mut(out_idx + 1) = *gamma_lut.get_unchecked((g * 255.0) as usize & 0xFF);
                 *output.get_unchecked_mut(out_idx + 2) = *gamma_lut.get_unchecked((b * 255.0) as usize & 0xFF);
-                *output.get_unchecked_mut(out_idx + 3) = 255;
+                *output.get_unchecked_mut(out_idx + 3) = (a_f + 0.5).min(255.0) as u8;
             }
         }
     }
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient_image(w: usize, h: usize) -> Vec<u8> {
+        let mut data = vec![0u8; w * h * 4];
+        for y in 0..h {
+            for x in 0..w {
+                let i = (y * w + x) * 4;
+                data[i] = (x * 255 / w) as u8;
+                data[i + 1] = (y * 255 / h) as u8;
+                data[i + 2] = ((x ^ y) * 37 % 256) as u8;
+                data[i + 3] = if (x + y) % 7 == 0 { 0 } else { 255 };
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        let img = gradient_image(37, 29);
+        let configs = [
+            CrtConfig::default(),
+            CrtConfig { enable_warp: false, ..Default::default() },
+            CrtConfig { pixel_aspect: 8.0 / 7.0, output_width: Some(301), output_height: Some(250), ..Default::default() },
+            CrtConfig { curvature: CrtCurvature::Spherical, tilt_x: 0.1, ..Default::default() },
+        ];
+
+        for config in &configs {
+            for scale in [2, 3, 4] {
+                let scalar = render(&img, 37, 29, scale, config, false);
+                let simd = render(&img, 37, 29, scale, config, true);
+                assert_eq!(scalar.len(), simd.len());
+                for (i, (a, b)) in scalar.iter().zip(&simd).enumerate() {
+                    assert!(
+                        (*a as i16 - *b as i16).abs() <= 1,
+                        "byte {} differs: scalar {} vs simd {}", i, a, b
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let sized = CrtConfig { output_width: Some(64), output_height: Some(48), ..Default::default() };
+        for config in [CrtConfig::default(), sized] {
+            assert_eq!(get_output_dimensions(0, 4, 3, &config), (0, 0));
+            for use_simd in [false, true] {
+                assert!(render(&[], 0, 4, 3, &config, use_simd).is_empty());
+                assert!(render(&[], 4, 0, 3, &config, use_simd).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_soft_edges_keep_colour() {
+        // White sprite with a half-transparent rim on transparent black
+        let mut img = vec![0u8; 8 * 8 * 4];
+        for y in 1..7 {
+            for x in 1..7 {
+                let rim = x == 1 || x == 6 || y == 1 || y == 6;
+                img[(y * 8 + x) * 4..][..4].copy_from_slice(&[255, 255, 255, if rim { 128 } else { 255 }]);
+            }
+        }
+        let config = CrtConfig { enable_warp: false, enable_scanlines: false, enable_mask: false, ..Default::default() };
+
+        for use_simd in [false, true] {
+            let out = render(&img, 8, 8, 4, &config, use_simd);
+            let visible: Vec<&[u8]> = out.chunks(4).filter(|px| px[3] > 0).collect();
+            assert!(visible.iter().any(|px| px[3] < 128), "soft edges are hard-cut");
+            assert!(visible.iter().any(|px| px[3] == 255));
+            for px in visible {
+                assert!(px[..3].iter().all(|&c| c >= 250), "dark fringe {:?}", px);
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_input_skips_gamma() {
+        let img = [128u8, 128, 128, 255].repeat(8 * 8);
+        let srgb = CrtConfig { enable_warp: false, enable_mask: false, ..Default::default() };
+        let linear = CrtConfig { color_space: ColorSpace::LinearSrgb, ..srgb };
+
+        let darkest = |config: &CrtConfig, use_simd: bool| {
+            let out = render(&img, 8, 8, 4, config, use_simd);
+            out.chunks(4).map(|px| px[0]).min().unwrap()
+        };
+        // Scanlines dim linear light directly instead of through the x^2 expansion
+        for use_simd in [false, true] {
+            assert!(darkest(&linear, use_simd) + 10 < darkest(&srgb, use_simd));
+        }
+        let brightest = |config: &CrtConfig| {
+            let out = render(&img, 8, 8, 4, config, false);
+            out.chunks(4).map(|px| px[0]).max().unwrap()
+        };
+        assert!(brightest(&srgb).abs_diff(128) <= 1 && brightest(&linear).abs_diff(128) <= 1);
+    }
+
+    fn opaque_coverage(out: &[u8], w: usize, x: usize, y: usize) -> bool {
+        out[(y * w + x) * 4 + 3] == 255
+    }
+
+    #[test]
+    fn test_curvature_modes_cut_corners() {
+        let img = vec![255u8; 32 * 24 * 4];
+        for curvature in [CrtCurvature::Quadratic, CrtCurvature::Spherical, CrtCurvature::Cylindrical] {
+            let config = CrtConfig { curvature, warp_x: 0.5, warp_y: 0.5, ..Default::default() };
+            let (w, h) = get_output_dimensions(32, 24, 4, &config);
+            let out = crt_upscale(&img, 32, 24, 4, &config);
+            assert!(opaque_coverage(&out, w, w / 2, h / 2), "{:?} centre", curvature);
+            assert!(!opaque_coverage(&out, w, 0, 0), "{:?} corner", curvature);
+        }
+
+        // Trinitron tubes are flat vertically: the top edge centre stays lit
+        let config = CrtConfig { curvature: CrtCurvature::Cylindrical, ..Default::default() };
+        let (w, _) = get_output_dimensions(32, 24, 4, &config);
+        let out = crt_upscale(&img, 32, 24, 4, &config);
+        assert!(opaque_coverage(&out, w, w / 2, 0));
+    }
+
+    #[test]
+    fn test_overscan_and_tilt() {
+        let img = vec![255u8; 32 * 24 * 4];
+        let flat = CrtConfig { enable_warp: false, ..Default::default() };
+
+        // Overscan crops the edges so the corners fill in even with warp
+        let config = CrtConfig { overscan: 1.2, ..Default::default() };
+        let out = crt_upscale(&img, 32, 24, 4, &config);
+        assert!(opaque_coverage(&out, 128, 0, 0));
+
+        // Tilting a flat screen away on the right shrinks that side
+        let config = CrtConfig { tilt_x: 0.4, ..flat };
+        let out = crt_upscale(&img, 32, 24, 4, &config);
+        let lit_rows = |x: usize| (0..96).filter(|&y| opaque_coverage(&out, 128, x, y)).count();
+        assert_ne!(lit_rows(2), lit_rows(125));
+    }
+}
@@ -0,0 +1,266 @@
+//! Depixelizing Upscaling Engine
+//! Vector-like magnification to any size, in the spirit of OmniScale and
+//! Kopf & Lischinski's "Depixelizing Pixel Art":
+//!
+//! 1. A similarity graph links neighbouring pixels whose colours are within
+//!    the xBRZ YCbCr tolerance, and crossing diagonals are resolved with the
+//!    curve / sparse-pixel / island heuristics (see `graph`).
+//! 2. Pixel cells are reshaped along the surviving diagonals: a cell loses
+//!    the corner that two diagonally linked neighbours cut across.
+//! 3. Connected pixels form regions. Each output pixel takes a vote of the
+//!    regions under a disc around it, which rounds off staircase corners into
+//!    smooth curves; the vote margin doubles as an anti-aliasing coverage.
+//!
+//! Unlike the pixel-pattern scalers the result has no natural factor, so
+//! the output can be any size.
+
+mod graph;
+
+use std::f32::consts::FRAC_PI_2;
+
+use crate::canvas::{rgba_len, MAX_CANVAS_SIDE};
+use crate::xbrz::pixel::Rgba8;
+use self::graph::SimilarityGraph;
+
+/// Depixel configuration
+#[derive(Clone)]
+pub struct DepixelConfig {
+    /// Explicit output width; `None` derives it from `scale`
+    pub output_width: Option<usize>,
+    /// Explicit output height; `None` derives it from `scale`
+    pub output_height: Option<usize>,
+    /// YCbCr distance below which neighbours belong to the same shape
+    pub similarity_threshold: f32,
+    /// Corner rounding radius in source pixels, doubled (0 = reshaped cells only)
+    pub smoothness: f32,
+    /// Width of the anti-aliased transition in output pixels
+    pub edge_softness: f32,
+}
+
+impl Default for DepixelConfig {
+    fn default() -> Self {
+        Self {
+            output_width: None,
+            output_height: None,
+            similarity_threshold: 30.0,
+            smoothness: 1.0,
+            edge_softness: 1.0,
+        }
+    }
+}
+
+/// Samples per axis of the voting disc
+const VOTE_SAMPLES: usize = 5;
+
+/// Compute the output dimensions `depixel_upscale` will produce for the given config
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &DepixelConfig,
+) -> (usize, usize) {
+    let scale = scale.clamp(1, 64);
+    let (natural_w, natural_h) = (src_w.max(1) * scale, src_h.max(1) * scale);
+
+    let (w, h) = match (config.output_width, config.output_height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, (w as f32 * src_h as f32 / src_w.max(1) as f32).round() as usize),
+        (None, Some(h)) => ((h as f32 * src_w as f32 / src_h.max(1) as f32).round() as usize, h),
+        (None, None) => (natural_w, natural_h),
+    };
+    (w.clamp(1, MAX_CANVAS_SIDE), h.clamp(1, MAX_CANVAS_SIDE))
+}
+
+/// Reshaped pixel cells and their regions
+struct Cells {
+    width: usize,
+    height: usize,
+    graph: SimilarityGraph,
+    regions: Vec<usize>,
+    /// Premultiplied colours
+    colors: Vec<[f32; 4]>,
+}
+
+impl Cells {
+    /// Source pixel owning point `(u, v)` (in source pixels) after reshaping
+    fn owner(&self, u: f32, v: f32) -> usize {
+        let cx = (u.floor().max(0.0) as usize).min(self.width - 1);
+        let cy = (v.floor().max(0.0) as usize).min(self.height - 1);
+        let own = cy * self.width + cx;
+        let fx = (u - cx as f32).clamp(0.0, 1.0);
+        let fy = (v - cy as f32).clamp(0.0, 1.0);
+
+        // Nearest corner and the distances to its two edges
+        let (hx, ex) = if fx < 0.5 { (cx.checked_sub(1), fx) } else { (Some(cx + 1), 1.0 - fx) };
+        let (vy, ey) = if fy < 0.5 { (cy.checked_sub(1), fy) } else { (Some(cy + 1), 1.0 - fy) };
+        let (Some(hx), Some(vy)) = (hx.filter(|&x| x < self.width), vy.filter(|&y| y < self.height)) else {
+            return own;
+        };
+
+        // A diagonal between the horizontal and vertical neighbour cuts the corner off
+        if ex + ey < 0.5 && self.graph.linked_at(hx, cy, cx, vy) {
+            return if ex < ey { cy * self.width + hx } else { vy * self.width + cx };
+        }
+        own
+    }
+}
+
+/// Running vote of one region
+struct Vote {
+    region: usize,
+    weight: f32,
+    color: [f32; 4],
+}
+
+pub fn depixel_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &DepixelConfig,
+) -> Vec<u8> {
+    let (out_w, out_h) = get_output_dimensions(src_w, src_h, scale, config);
+    let Some(len) = rgba_len(out_w, out_h) else {
+        return Vec::new();
+    };
+    let mut output = vec![0u8; len];
+    if src_w == 0 || src_h == 0 {
+        return output;
+    }
+    assert_eq!(input.len(), src_w * src_h * 4);
+    let (_, pixels, _) = unsafe { input.align_to::<Rgba8>() };
+
+    let graph = SimilarityGraph::new(pixels, src_w, src_h, config.similarity_threshold);
+    let cells = Cells {
+        width: src_w,
+        height: src_h,
+        regions: graph.components(),
+        graph,
+        colors: input
+            .chunks_exact(4)
+            .map(|p| {
+                let a = p[3] as f32;
+                [p[0] as f32 * a, p[1] as f32 * a, p[2] as f32 * a, a]
+            })
+            .collect(),
+    };
+
+    let scale_x = out_w as f32 / src_w as f32;
+    let scale_y = out_h as f32 / src_h as f32;
+    let min_scale = scale_x.min(scale_y);
+    // Never vote over less than one output pixel, so there is always some anti-aliasing
+    let radius = (config.smoothness.max(0.0) * 0.5).max(0.5 / min_scale);
+    let softness = config.edge_softness.max(0.01);
+
+    // Sample offsets inside the unit disc
+    let offsets: Vec<(f32, f32)> = (0..VOTE_SAMPLES * VOTE_SAMPLES)
+        .map(|i| {
+            let step = |k: usize| ((k as f32 + 0.5) / VOTE_SAMPLES as f32) * 2.0 - 1.0;
+            (step(i % VOTE_SAMPLES), step(i / VOTE_SAMPLES))
+        })
+        .filter(|(x, y)| x * x + y * y <= 1.0)
+        .collect();
+
+    let mut votes: Vec<Vote> = Vec::with_capacity(offsets.len());
+    for (y, row) in output.chunks_exact_mut(out_w * 4).enumerate() {
+        let v = (y as f32 + 0.5) / scale_y;
+        for (x, px) in row.chunks_exact_mut(4).enumerate() {
+            let u = (x as f32 + 0.5) / scale_x;
+
+            votes.clear();
+            for &(ox, oy) in &offsets {
+                let owner = cells.owner(u + ox * radius, v + oy * radius);
+                let region = cells.regions[owner];
+                let color = cells.colors[owner];
+                let vote = match votes.iter_mut().find(|vote| vote.region == region) {
+                    Some(vote) => vote,
+                    None => {
+                        votes.push(Vote { region, weight: 0.0, color: [0.0; 4] });
+                        votes.last_mut().unwrap()
+                    }
+                };
+                vote.weight += 1.0;
+                for (acc, c) in vote.color.iter_mut().zip(color) {
+                    *acc += c;
+                }
+            }
+            votes.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+            let mean = |vote: &Vote| vote.color.map(|c| c / vote.weight);
+            let winner = mean(&votes[0]);
+            let [r, g, b, a] = match votes.get(1) {
+                None => winner,
+                Some(second) => {
+                    // Vote share -> signed distance to the region boundary -> coverage
+                    let share = votes[0].weight / (votes[0].weight + second.weight);
+                    let distance = (share - 0.5) * FRAC_PI_2 * radius * min_scale;
+                    let coverage = (0.5 + distance / softness).clamp(0.5, 1.0);
+                    let runner_up = mean(second);
+                    std::array::from_fn(|c| winner[c] * coverage + runner_up[c] * (1.0 - coverage))
+                }
+            };
+
+            if a > 0.0 {
+                px.copy_from_slice(&[
+                    (r / a).round().min(255.0) as u8,
+                    (g / a).round().min(255.0) as u8,
+                    (b / a).round().min(255.0) as u8,
+                    a.round() as u8,
+                ]);
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    fn pixel(out: &[u8], width: usize, x: usize, y: usize) -> &[u8] {
+        &out[(y * width + x) * 4..][..4]
+    }
+
+    #[test]
+    fn test_dimensions_beyond_xbrz_limit() {
+        let config = DepixelConfig::default();
+        assert_eq!(get_output_dimensions(8, 4, 12, &config), (96, 48));
+
+        let sized = DepixelConfig { output_width: Some(100), ..DepixelConfig::default() };
+        assert_eq!(get_output_dimensions(8, 4, 1, &sized), (100, 50));
+    }
+
+    #[test]
+    fn test_flat_image_stays_flat() {
+        let img = [30, 60, 90, 255].repeat(6);
+        let out = depixel_upscale(&img, 3, 2, 10, &DepixelConfig::default());
+        assert!(out.chunks(4).all(|p| p == [30, 60, 90, 255]));
+    }
+
+    #[test]
+    fn test_corners_round_and_diagonals_join() {
+        // 4x4 red square on an 8x8 white background
+        let mut img = WHITE.repeat(64);
+        for y in 2..6 {
+            for x in 2..6 {
+                img[(y * 8 + x) * 4..][..4].copy_from_slice(&RED);
+            }
+        }
+        let out = depixel_upscale(&img, 8, 8, 10, &DepixelConfig::default());
+        // Just inside the square's outer corner: rounded off
+        assert_eq!(pixel(&out, 80, 20, 20), WHITE);
+        // Just inside the middle of an edge: still the square
+        assert_eq!(pixel(&out, 80, 40, 21), RED);
+        assert_eq!(pixel(&out, 80, 40, 40), RED);
+
+        // Red diagonal line: the shared corner of two line pixels is red
+        let img: Vec<u8> = (0..16).flat_map(|i| if i % 5 == 0 { RED } else { WHITE }).collect();
+        let out = depixel_upscale(&img, 4, 4, 10, &DepixelConfig::default());
+        assert_eq!(pixel(&out, 40, 20, 20), RED);
+        assert_eq!(pixel(&out, 40, 29, 10), WHITE);
+    }
+}
@@ -0,0 +1,259 @@
+//! Similarity graph of the source pixels, after Kopf & Lischinski.
+//!
+//! Every pixel starts linked to each of its eight neighbours whose colour is
+//! within the YCbCr tolerance. Crossing diagonals inside a 2x2 block are then
+//! resolved: in a fully connected block both are redundant, otherwise the
+//! curve, sparse-pixel and island heuristics decide which one survives.
+
+use crate::xbrz::pixel::Rgba8;
+use crate::xbrz::ycbcr_lookup::YCbCrLookup;
+
+/// Neighbour offsets; bit `i` of a link mask refers to `DIRS[i]`, and the
+/// opposite direction is `(i + 4) % 8`
+const DIRS: [(isize, isize); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+
+const EAST: usize = 0;
+const SOUTH_EAST: usize = 1;
+const SOUTH: usize = 2;
+const SOUTH_WEST: usize = 3;
+
+/// Side of the window the sparse-pixel heuristic counts components in
+const SPARSE_WINDOW: isize = 8;
+/// Weight of a diagonal that keeps a pixel from becoming an island
+const ISLAND_WEIGHT: usize = 5;
+
+pub(crate) struct SimilarityGraph {
+    width: usize,
+    height: usize,
+    links: Vec<u8>,
+}
+
+impl SimilarityGraph {
+    pub(crate) fn new(pixels: &[Rgba8], width: usize, height: usize, threshold: f32) -> Self {
+        let ycbcr = YCbCrLookup::instance();
+        let mut graph = Self { width, height, links: vec![0; width * height] };
+
+        for y in 0..height {
+            for x in 0..width {
+                // Forward directions only; `set` adds the reverse link
+                for dir in [EAST, SOUTH_EAST, SOUTH, SOUTH_WEST] {
+                    if let Some(n) = graph.neighbour(x, y, dir) {
+                        if ycbcr.dist(pixels[y * width + x], pixels[n]) < threshold {
+                            graph.set(y * width + x, dir, true);
+                        }
+                    }
+                }
+            }
+        }
+
+        graph.resolve_crossings();
+        graph
+    }
+
+    fn neighbour(&self, x: usize, y: usize, dir: usize) -> Option<usize> {
+        let (dx, dy) = DIRS[dir];
+        let nx = x.checked_add_signed(dx).filter(|&nx| nx < self.width)?;
+        let ny = y.checked_add_signed(dy).filter(|&ny| ny < self.height)?;
+        Some(ny * self.width + nx)
+    }
+
+    fn set(&mut self, idx: usize, dir: usize, linked: bool) {
+        let (x, y) = (idx % self.width, idx / self.width);
+        let Some(n) = self.neighbour(x, y, dir) else { return };
+        let opposite = (dir + 4) % 8;
+        if linked {
+            self.links[idx] |= 1 << dir;
+            self.links[n] |= 1 << opposite;
+        } else {
+            self.links[idx] &= !(1 << dir);
+            self.links[n] &= !(1 << opposite);
+        }
+    }
+
+    /// Whether pixel `idx` is linked to its neighbour in direction `dir`
+    #[inline]
+    pub(crate) fn linked(&self, idx: usize, dir: usize) -> bool {
+        self.links[idx] & (1 << dir) != 0
+    }
+
+    /// Whether pixels `(x0, y0)` and `(x1, y1)`, at most one step apart, are linked
+    pub(crate) fn linked_at(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> bool {
+        let (dx, dy) = (x1 as isize - x0 as isize, y1 as isize - y0 as isize);
+        DIRS.iter()
+            .position(|&d| d == (dx, dy))
+            .is_some_and(|dir| self.linked(y0 * self.width + x0, dir))
+    }
+
+    fn valence(&self, idx: usize) -> usize {
+        self.links[idx].count_ones() as usize
+    }
+
+    fn neighbours(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = (idx % self.width, idx / self.width);
+        (0..8).filter(move |&dir| self.linked(idx, dir)).filter_map(move |dir| self.neighbour(x, y, dir))
+    }
+
+    fn resolve_crossings(&mut self) {
+        if self.width < 2 || self.height < 2 {
+            return;
+        }
+        let (width, height) = (self.width, self.height);
+        let blocks = (0..height - 1).flat_map(|y| (0..width - 1).map(move |x| (x, y)));
+
+        // Fully connected blocks: both diagonals are redundant
+        for (x, y) in blocks.clone() {
+            let tl = y * self.width + x;
+            let tr = tl + 1;
+            if self.linked(tl, SOUTH_EAST)
+                && self.linked(tr, SOUTH_WEST)
+                && self.linked(tl, EAST)
+                && self.linked(tl, SOUTH)
+                && self.linked(tr, SOUTH)
+                && self.linked(tl + self.width, EAST)
+            {
+                self.set(tl, SOUTH_EAST, false);
+                self.set(tr, SOUTH_WEST, false);
+            }
+        }
+
+        // Remaining crossings: keep the diagonal the heuristics favour
+        for (x, y) in blocks {
+            let tl = y * self.width + x;
+            let tr = tl + 1;
+            if !(self.linked(tl, SOUTH_EAST) && self.linked(tr, SOUTH_WEST)) {
+                continue;
+            }
+            let (bl, br) = (tl + self.width, tr + self.width);
+            let (main, anti) = self.diagonal_weights((x, y), (tl, br), (tr, bl));
+
+            if main <= anti {
+                self.set(tl, SOUTH_EAST, false);
+            }
+            if anti <= main {
+                self.set(tr, SOUTH_WEST, false);
+            }
+        }
+    }
+
+    /// Heuristic weights of two crossing diagonals of the block at `origin`
+    fn diagonal_weights(
+        &self,
+        origin: (usize, usize),
+        main: (usize, usize),
+        anti: (usize, usize),
+    ) -> (usize, usize) {
+        let mut weights = [main, anti].map(|(a, b)| {
+            let island = if self.valence(a) == 1 || self.valence(b) == 1 { ISLAND_WEIGHT } else { 0 };
+            self.curve_length(a, b) + island
+        });
+
+        // Sparse pixels: the smaller component is the foreground and wins by the difference
+        let sizes = [main.0, anti.0].map(|start| self.component_size_in_window(start, origin));
+        if sizes[0] < sizes[1] {
+            weights[0] += sizes[1] - sizes[0];
+        } else {
+            weights[1] += sizes[0] - sizes[1];
+        }
+
+        (weights[0], weights[1])
+    }
+
+    /// Number of edges in the chain of valence-2 pixels through edge `a`–`b`
+    fn curve_length(&self, a: usize, b: usize) -> usize {
+        let limit = self.width + self.height;
+        let mut length = 1;
+
+        for (start, from) in [(a, b), (b, a)] {
+            let (mut prev, mut node) = (from, start);
+            while self.valence(node) == 2 && length < limit {
+                let Some(next) = self.neighbours(node).find(|&n| n != prev) else { break };
+                if next == a || next == b {
+                    // Closed loop
+                    return length + 1;
+                }
+                length += 1;
+                prev = node;
+                node = next;
+            }
+        }
+        length
+    }
+
+    /// Size of the component containing `start` within the window around the block at `origin`
+    fn component_size_in_window(&self, start: usize, origin: (usize, usize)) -> usize {
+        let half = SPARSE_WINDOW / 2;
+        let (ox, oy) = (origin.0 as isize, origin.1 as isize);
+        let inside = |idx: usize| {
+            let (x, y) = ((idx % self.width) as isize, (idx / self.width) as isize);
+            (ox - half + 1..=ox + half).contains(&x) && (oy - half + 1..=oy + half).contains(&y)
+        };
+
+        let mut seen = vec![start];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for n in self.neighbours(node) {
+                if inside(n) && !seen.contains(&n) {
+                    seen.push(n);
+                    stack.push(n);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    /// Label every pixel with the id of its connected component
+    pub(crate) fn components(&self) -> Vec<usize> {
+        let mut labels = vec![usize::MAX; self.links.len()];
+        let mut stack = Vec::new();
+
+        for start in 0..labels.len() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+            labels[start] = start;
+            stack.push(start);
+            while let Some(node) = stack.pop() {
+                for n in self.neighbours(node) {
+                    if labels[n] == usize::MAX {
+                        labels[n] = start;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xbrz::pixel::Pixel;
+
+    #[test]
+    fn test_thin_diagonal_beats_background() {
+        // Red diagonal across white: both diagonals of each block are similar,
+        // the line is the sparser, longer curve and must stay connected
+        let red = Rgba8::from_rgba([255, 0, 0, 255]);
+        let white = Rgba8::from_rgba([255, 255, 255, 255]);
+        let pixels: Vec<Rgba8> = (0..16).map(|i| if i % 5 == 0 { red } else { white }).collect();
+
+        let graph = SimilarityGraph::new(&pixels, 4, 4, 30.0);
+        for i in 0..3 {
+            assert!(graph.linked_at(i, i, i + 1, i + 1));
+            assert!(!graph.linked_at(i + 1, i, i, i + 1));
+        }
+        let labels = graph.components();
+        assert_eq!(labels[0], labels[15]);
+        assert_ne!(labels[0], labels[1]);
+    }
+
+    #[test]
+    fn test_solid_block_has_no_diagonals() {
+        let pixels = vec![Rgba8::from_rgba([9, 9, 9, 255]); 9];
+        let graph = SimilarityGraph::new(&pixels, 3, 3, 30.0);
+        assert!(graph.linked_at(0, 0, 1, 0));
+        assert!(!graph.linked_at(0, 0, 1, 1));
+        assert!(!graph.linked_at(1, 0, 0, 1));
+    }
+}
@@ -0,0 +1,469 @@
+//! Dithering Engine
+//! Ordered (Bayer, blue noise) and error-diffusion (Floyd–Steinberg,
+//! Atkinson, Sierra) dithering towards a fixed palette or a per-channel bit
+//! depth, usually run after colour reduction and before a display filter
+//! such as CRT.
+//!
+//! Values are used as stored by default; with `linear_light` the thresholds
+//! and the diffused error live in linear light, which keeps dithered
+//! gradients from drifting darker. Palette matches are always made perceptually (see
+//! `palette::nearest`). Alpha passes through unchanged and fully transparent
+//! pixels neither receive nor spread error.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::color_space::ColorSpace;
+use crate::canvas::rgba_parts;
+use crate::palette;
+
+/// Dithering algorithm
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DitherMethod {
+    Bayer2 = 0,
+    Bayer4 = 1,
+    Bayer8 = 2,
+    /// Ordered dithering with a void-and-cluster threshold texture
+    BlueNoise = 3,
+    #[default]
+    FloydSteinberg = 4,
+    Atkinson = 5,
+    /// Full three-row Sierra kernel
+    Sierra = 6,
+}
+
+/// Dithering parameters
+#[derive(Clone, Debug, PartialEq)]
+pub struct DitherConfig {
+    pub method: DitherMethod,
+    /// Alternate the scan direction every row (error diffusion only)
+    pub serpentine: bool,
+    /// Threshold and diffuse error in linear light instead of stored values
+    pub linear_light: bool,
+    /// Colour space of the image and the palette; linear data is already
+    /// linear light
+    pub color_space: ColorSpace,
+    /// Levels per channel, as bits (1-8), used when `palette` is empty
+    pub bits_per_channel: u8,
+    /// Target colours as `0xRRGGBBAA` (matched on RGB)
+    pub palette: Vec<u32>,
+}
+
+impl Default for DitherConfig {
+    fn default() -> Self {
+        Self {
+            method: DitherMethod::FloydSteinberg,
+            serpentine: true,
+            linear_light: false,
+            color_space: ColorSpace::Srgb,
+            bits_per_channel: 2,
+            palette: Vec::new(),
+        }
+    }
+}
+
+/// Error-diffusion taps `(dx, dy, weight)` and their divisor
+struct Kernel {
+    taps: &'static [(isize, usize, f32)],
+    divisor: f32,
+}
+
+const FLOYD_STEINBERG: Kernel = Kernel {
+    taps: &[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)],
+    divisor: 16.0,
+};
+
+/// Diffuses only 6/8 of the error, which keeps contrast high
+const ATKINSON: Kernel = Kernel {
+    taps: &[(1, 0, 1.0), (2, 0, 1.0), (-1, 1, 1.0), (0, 1, 1.0), (1, 1, 1.0), (0, 2, 1.0)],
+    divisor: 8.0,
+};
+
+const SIERRA: Kernel = Kernel {
+    taps: &[
+        (1, 0, 5.0), (2, 0, 3.0),
+        (-2, 1, 2.0), (-1, 1, 4.0), (0, 1, 5.0), (1, 1, 4.0), (2, 1, 2.0),
+        (-1, 2, 2.0), (0, 2, 3.0), (1, 2, 2.0),
+    ],
+    divisor: 32.0,
+};
+
+const BLUE_NOISE_SIZE: usize = 64;
+/// Width of the Gaussian used to measure clusters and voids
+const BLUE_NOISE_SIGMA: f32 = 1.5;
+
+/// Recursive Bayer matrix of side `n` (a power of two) as thresholds in `0..1`
+fn bayer_matrix(n: usize) -> Vec<f32> {
+    let mut ranks = vec![0u32];
+    let mut size = 1;
+    while size < n {
+        let next = size * 2;
+        ranks = (0..next * next)
+            .map(|i| {
+                let (x, y) = (i % next, i / next);
+                let quadrant = [0, 2, 3, 1][(y / size) * 2 + x / size];
+                ranks[(y % size) * size + x % size] * 4 + quadrant
+            })
+            .collect();
+        size = next;
+    }
+    ranks.iter().map(|&r| (r as f32 + 0.5) / (n * n) as f32).collect()
+}
+
+/// Tileable blue-noise thresholds, built once with Ulichney's void-and-cluster
+fn blue_noise() -> &'static [f32] {
+    static TEXTURE: OnceLock<Vec<f32>> = OnceLock::new();
+    TEXTURE.get_or_init(void_and_cluster)
+}
+
+fn void_and_cluster() -> Vec<f32> {
+    let n = BLUE_NOISE_SIZE;
+    let len = n * n;
+
+    // Gaussian splat on the torus, indexed by wrapped offset
+    let splat: Vec<f32> = (0..len)
+        .map(|i| {
+            let (dx, dy) = (i % n, i / n);
+            let (dx, dy) = (dx.min(n - dx) as f32, dy.min(n - dy) as f32);
+            (-(dx * dx + dy * dy) / (2.0 * BLUE_NOISE_SIGMA * BLUE_NOISE_SIGMA)).exp()
+        })
+        .collect();
+
+    let mut on = vec![false; len];
+    let mut energy = vec![0.0f32; len];
+    let toggle = |on: &mut [bool], energy: &mut [f32], idx: usize| {
+        on[idx] = !on[idx];
+        let sign = if on[idx] { 1.0 } else { -1.0 };
+        let (px, py) = (idx % n, idx / n);
+        for (i, e) in energy.iter_mut().enumerate() {
+            let (x, y) = (i % n, i / n);
+            *e += sign * splat[((y + n - py) % n) * n + (x + n - px) % n];
+        }
+    };
+    let tightest_cluster = |on: &[bool], energy: &[f32]| {
+        (0..len).filter(|&i| on[i]).max_by(|&a, &b| energy[a].total_cmp(&energy[b])).unwrap_or(0)
+    };
+    let largest_void = |on: &[bool], energy: &[f32]| {
+        (0..len).filter(|&i| !on[i]).min_by(|&a, &b| energy[a].total_cmp(&energy[b])).unwrap_or(0)
+    };
+
+    // Sparse pseudo-random start (xorshift, fixed seed)
+    let mut seed = 0x2545_F491u32;
+    for _ in 0..len / 10 {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        let idx = seed as usize % len;
+        if !on[idx] {
+            toggle(&mut on, &mut energy, idx);
+        }
+    }
+
+    // Relax: move the tightest cluster into the largest void until stable
+    for _ in 0..len {
+        let cluster = tightest_cluster(&on, &energy);
+        toggle(&mut on, &mut energy, cluster);
+        let void = largest_void(&on, &energy);
+        toggle(&mut on, &mut energy, void);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let ones = on.iter().filter(|&&o| o).count();
+    let mut rank = vec![0usize; len];
+
+    // Rank the initial points by repeatedly removing the tightest cluster ...
+    let (mut phase_on, mut phase_energy) = (on.clone(), energy.clone());
+    for r in (0..ones).rev() {
+        let cluster = tightest_cluster(&phase_on, &phase_energy);
+        toggle(&mut phase_on, &mut phase_energy, cluster);
+        rank[cluster] = r;
+    }
+    // ... and the rest by repeatedly filling the largest void
+    for r in ones..len {
+        let void = largest_void(&on, &energy);
+        toggle(&mut on, &mut energy, void);
+        rank[void] = r;
+    }
+
+    rank.iter().map(|&r| (r as f32 + 0.5) / len as f32).collect()
+}
+
+/// Working-value range of palette error diffusion. Colours the palette
+/// cannot reach would otherwise pile up error without bound and smear it
+/// into everything after them.
+const PALETTE_DIFFUSION_RANGE: (f32, f32) = (-0.5, 1.5);
+
+/// Colours the dither may emit
+enum Target {
+    /// Highest level per channel (`2^bits - 1`)
+    Levels(u32),
+    Palette {
+        colors: Vec<[u8; 4]>,
+        /// Palette colours in working space
+        working: Vec<[f32; 3]>,
+        /// Typical distance between palette colours, the ordered-dither amplitude
+        spread: f32,
+        cache: HashMap<[u8; 3], usize>,
+    },
+}
+
+struct Quantizer {
+    target: Target,
+    linear_light: bool,
+    color_space: ColorSpace,
+}
+
+impl Quantizer {
+    fn new(config: &DitherConfig) -> Self {
+        let (linear_light, color_space) = (config.linear_light, config.color_space);
+        let decode = |c: u8| {
+            let c = c as f32 / 255.0;
+            if linear_light { color_space.decode(c) } else { c }
+        };
+
+        let target = if config.palette.is_empty() {
+            Target::Levels((1u32 << config.bits_per_channel.clamp(1, 8)) - 1)
+        } else {
+            let colors: Vec<[u8; 4]> = config.palette.iter().map(|&c| rgba_parts(c)).collect();
+            let working: Vec<[f32; 3]> = colors.iter().map(|c| [c[0], c[1], c[2]].map(decode)).collect();
+
+            // Mean distance to the closest other colour, spread over three channels
+            let spread = if working.len() < 2 {
+                0.0
+            } else {
+                let closest = working.iter().enumerate().map(|(i, a)| {
+                    working
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .map(|(_, b)| (0..3).map(|k| (a[k] - b[k]).powi(2)).sum::<f32>().sqrt())
+                        .fold(f32::INFINITY, f32::min)
+                });
+                closest.sum::<f32>() / working.len() as f32 / 3f32.sqrt()
+            };
+            Target::Palette { colors, working, spread, cache: HashMap::new() }
+        };
+        Self { target, linear_light, color_space }
+    }
+
+    #[inline]
+    fn decode(&self, c: f32) -> f32 {
+        if self.linear_light { self.color_space.decode(c) } else { c }
+    }
+
+    #[inline]
+    fn encode(&self, v: f32) -> f32 {
+        if self.linear_light { self.color_space.encode(v) } else { v }
+    }
+
+    /// Working value of every input pixel
+    fn working(&self, input: &[u8]) -> Vec<[f32; 3]> {
+        let lut: Vec<f32> = (0..=255).map(|c| self.decode(c as f32 / 255.0)).collect();
+        input.chunks_exact(4).map(|px| [px[0], px[1], px[2]].map(|c| lut[c as usize])).collect()
+    }
+
+    /// Levels `(lo, hi)` around working value `v`, with their working values
+    fn bracket(&self, v: f32, max: u32) -> ((u32, f32), (u32, f32)) {
+        let e = self.encode(v.clamp(0.0, 1.0)) * max as f32;
+        let lo = (e.floor() as u32).min(max - 1);
+        let level = |l: u32| (l, self.decode(l as f32 / max as f32));
+        (level(lo), level(lo + 1))
+    }
+
+    /// Nearest target colour: its working value and its output RGB
+    fn nearest(&mut self, v: [f32; 3]) -> ([f32; 3], [u8; 3]) {
+        match &mut self.target {
+            &mut Target::Levels(max) => {
+                let mut working = [0.0; 3];
+                let mut rgb = [0; 3];
+                for c in 0..3 {
+                    let ((lo, w_lo), (hi, w_hi)) = self.bracket(v[c], max);
+                    let (level, w) = if v[c] - w_lo <= w_hi - v[c] { (lo, w_lo) } else { (hi, w_hi) };
+                    working[c] = w;
+                    rgb[c] = (level * 255 / max) as u8;
+                }
+                (working, rgb)
+            }
+            Target::Palette { colors, working, cache, .. } => {
+                let space = self.color_space;
+                let key = v.map(|c| {
+                    let e = if self.linear_light { space.encode(c.clamp(0.0, 1.0)) } else { c };
+                    (e.clamp(0.0, 1.0) * 255.0).round() as u8
+                });
+                let idx = *cache
+                    .entry(key)
+                    .or_insert_with(|| palette::nearest(colors, [key[0], key[1], key[2], 255]));
+                let c = colors[idx];
+                (working[idx], [c[0], c[1], c[2]])
+            }
+        }
+    }
+
+    /// Ordered dithering of working value `v` against threshold `t` in `0..1`
+    fn ordered(&mut self, v: [f32; 3], t: f32) -> [u8; 3] {
+        match self.target {
+            Target::Levels(max) => v.map(|c| {
+                let ((lo, w_lo), (hi, w_hi)) = self.bracket(c, max);
+                let frac = (c - w_lo) / (w_hi - w_lo);
+                (if frac > t { hi } else { lo } * 255 / max) as u8
+            }),
+            Target::Palette { spread, .. } => self.nearest(v.map(|c| c + (t - 0.5) * spread)).1,
+        }
+    }
+}
+
+/// Dither an RGBA image; the output has the input's dimensions
+pub fn dither(input: &[u8], width: usize, height: usize, config: &DitherConfig) -> Vec<u8> {
+    assert_eq!(input.len(), width * height * 4);
+    let mut quantizer = Quantizer::new(config);
+    let mut output = input.to_vec();
+
+    let ordered = |n: usize, thresholds: &[f32], quantizer: &mut Quantizer, output: &mut [u8]| {
+        let work = quantizer.working(input);
+        for (i, px) in output.chunks_exact_mut(4).enumerate() {
+            if px[3] == 0 {
+                continue;
+            }
+            let t = thresholds[(i / width % n) * n + i % width % n];
+            px[..3].copy_from_slice(&quantizer.ordered(work[i], t));
+        }
+    };
+
+    let kernel = match config.method {
+        DitherMethod::Bayer2 | DitherMethod::Bayer4 | DitherMethod::Bayer8 => {
+            let n = match config.method {
+                DitherMethod::Bayer2 => 2,
+                DitherMethod::Bayer4 => 4,
+                _ => 8,
+            };
+            ordered(n, &bayer_matrix(n), &mut quantizer, &mut output);
+            return output;
+        }
+        DitherMethod::BlueNoise => {
+            ordered(BLUE_NOISE_SIZE, blue_noise(), &mut quantizer, &mut output);
+            return output;
+        }
+        DitherMethod::FloydSteinberg => &FLOYD_STEINBERG,
+        DitherMethod::Atkinson => &ATKINSON,
+        DitherMethod::Sierra => &SIERRA,
+    };
+
+    let mut work = quantizer.working(input);
+    let palette = matches!(quantizer.target, Target::Palette { .. });
+    for y in 0..height {
+        let reverse = config.serpentine && y % 2 == 1;
+        for i in 0..width {
+            let x = if reverse { width - 1 - i } else { i };
+            let idx = y * width + x;
+            if input[idx * 4 + 3] == 0 {
+                continue;
+            }
+
+            let v = work[idx];
+            let (q, rgb) = quantizer.nearest(v);
+            output[idx * 4..idx * 4 + 3].copy_from_slice(&rgb);
+
+            let err = [0, 1, 2].map(|c| v[c] - q[c]);
+            for &(dx, dy, weight) in kernel.taps {
+                let dx = if reverse { -dx } else { dx };
+                let Some(nx) = x.checked_add_signed(dx).filter(|&nx| nx < width) else { continue };
+                let ny = y + dy;
+                if ny >= height || input[(ny * width + nx) * 4 + 3] == 0 {
+                    continue;
+                }
+                let n = &mut work[ny * width + nx];
+                for c in 0..3 {
+                    n[c] += err[c] * weight / kernel.divisor;
+                    if palette {
+                        n[c] = n[c].clamp(PALETTE_DIFFUSION_RANGE.0, PALETTE_DIFFUSION_RANGE.1);
+                    }
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grey(w: usize, h: usize, v: u8) -> Vec<u8> {
+        (0..w * h).flat_map(|_| [v, v, v, 255]).collect()
+    }
+
+    fn white_fraction(img: &[u8]) -> f32 {
+        let white = img.chunks(4).filter(|px| px[0] == 255).count();
+        white as f32 / (img.len() / 4) as f32
+    }
+
+    #[test]
+    fn test_threshold_maps_are_permutations() {
+        for (n, map) in [(8, bayer_matrix(8)), (BLUE_NOISE_SIZE, blue_noise().to_vec())] {
+            let mut ranks: Vec<usize> = map.iter().map(|t| (t * (n * n) as f32) as usize).collect();
+            ranks.sort_unstable();
+            assert_eq!(ranks, (0..n * n).collect::<Vec<_>>());
+        }
+        assert_eq!(bayer_matrix(2), vec![0.125, 0.625, 0.875, 0.375]);
+    }
+
+    #[test]
+    fn test_methods_preserve_mean_and_levels() {
+        let img = grey(32, 32, 128);
+        for method in [
+            DitherMethod::Bayer2, DitherMethod::Bayer4, DitherMethod::Bayer8, DitherMethod::BlueNoise,
+            DitherMethod::FloydSteinberg, DitherMethod::Atkinson, DitherMethod::Sierra,
+        ] {
+            let config = DitherConfig { method, bits_per_channel: 1, ..DitherConfig::default() };
+            let out = dither(&img, 32, 32, &config);
+            assert!(out.iter().all(|&c| c == 0 || c == 255), "{method:?}");
+            assert!((white_fraction(&out) - 0.5).abs() < 0.05, "{method:?}");
+        }
+
+        // Linear light: sRGB 128 is about 21.6% of the white light
+        let config = DitherConfig { bits_per_channel: 1, linear_light: true, ..DitherConfig::default() };
+        let out = dither(&img, 32, 32, &config);
+        assert!((white_fraction(&out) - 0.216).abs() < 0.03);
+
+        // Linear data is not linearised a second time
+        let config = DitherConfig { color_space: ColorSpace::LinearSrgb, ..config };
+        let out = dither(&img, 32, 32, &config);
+        assert!((white_fraction(&out) - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_palette_target_and_transparency() {
+        let mut img: Vec<u8> = (0..64).flat_map(|i| [i as u8 * 4, 0, 255 - i as u8 * 4, 255]).collect();
+        img[4 * 5..4 * 6].copy_from_slice(&[7, 7, 7, 0]);
+        let palette = vec![0xFF0000FF, 0x0000FFFF, 0x800080FF];
+
+        for method in [DitherMethod::Bayer4, DitherMethod::Sierra] {
+            let config = DitherConfig { method, palette: palette.clone(), ..DitherConfig::default() };
+            let out = dither(&img, 8, 8, &config);
+            assert_eq!(out[4 * 5..4 * 6], [7, 7, 7, 0]);
+            for (i, px) in out.chunks(4).enumerate().filter(|&(i, _)| i != 5) {
+                let rgba = u32::from_be_bytes([px[0], px[1], px[2], px[3]]);
+                assert!(palette.contains(&rgba), "pixel {i}: {rgba:08X}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_palette_error_stays_bounded_out_of_gamut() {
+        // Red a blue palette cannot reach, then a blue it holds exactly: the
+        // red band's error may only spill into the first columns of the blue
+        let (w, h) = (64, 16);
+        let img: Vec<u8> = (0..w * h)
+            .flat_map(|i| if i % w < 32 { [255, 0, 0, 255] } else { [0, 0, 255, 255] })
+            .collect();
+        let palette = vec![0x000080FF, 0x0000FFFF, 0x8080FFFF];
+        let config = DitherConfig { method: DitherMethod::FloydSteinberg, palette, ..DitherConfig::default() };
+        let out = dither(&img, w, h, &config);
+
+        for (i, px) in out.chunks(4).enumerate().filter(|&(i, _)| i % w >= 34) {
+            assert_eq!(px, [0, 0, 255, 255], "pixel ({}, {})", i % w, i / w);
+        }
+    }
+}
@@ -1,6 +1,18 @@
 //! Hexagonal Pixel Art Upscaling Engine
 //! Optimized with analytical border detection and pre-computed geometry.
 
+mod grid;
+mod resample;
+mod svg;
+
+pub use self::grid::{grid_json, grid_svg};
+pub use self::resample::{resample_dimensions, resample_grid, HexSampling};
+pub use self::svg::hex_svg;
+pub(crate) use self::grid::fmt_coord;
+pub(crate) use self::svg::fill_attrs;
+
+use crate::canvas::rgba_parts;
+
 /// Hexagon orientation
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HexOrientation {
@@ -8,16 +20,75 @@ pub enum HexOrientation {
     PointyTop = 1,
 }
 
+/// Width in pixels, measured from the cell edge, of the border band drawn
+/// for `thickness` units of `border_thickness`. HEX borders have this width
+/// at edge midpoints; the tessellation engines use it along every edge.
+pub(crate) fn border_band_pixels(thickness: f32) -> f32 {
+    thickness * 0.55 * 3.0_f32.sqrt()
+}
+
 /// HEX configuration
 #[derive(Clone)]
 pub struct HexConfig {
     pub orientation: HexOrientation,
     pub draw_borders: bool,
     pub border_color: u32,
+    /// Border band width: `0.55 * sqrt(3) * thickness` pixels (about 0.95 px
+    /// per unit, see [`border_band_pixels`]) at edge midpoints, widening
+    /// towards the corners
     pub border_thickness: usize,
     pub background_color: u32,
+    /// Subsamples per axis for anti-aliased hex edges (1 = off, max 8)
+    pub supersample: usize,
+    /// Per-cell styling (gaps, bevel, gradient, drop shadow)
+    pub style: HexStyle,
+    /// Transparency handling
+    pub alpha: HexAlpha,
+}
+
+/// How translucent source pixels are written. The default copies source RGBA
+/// into cells verbatim.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HexAlpha {
+    /// Composite cells and borders over `background_color` instead of
+    /// replacing it
+    pub composite: bool,
+    /// Treat fully transparent cells as empty: no border, background shows through
+    pub skip_transparent_borders: bool,
+}
+
+/// Cell styling for "bead" and "tile" looks. All effects are driven by the
+/// pixel distance to the cell edge; the default disables every effect.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HexStyle {
+    /// Gap between neighbouring cells in pixels; the background shows through
+    pub gap: f32,
+    /// Width of the bevel band along the cell edge in pixels (0 = off)
+    pub bevel_width: f32,
+    /// Bevel strength (0..1); edges facing the top-left light are lightened and
+    /// the opposite ones darkened. Negative values give a sunken emboss
+    pub bevel_strength: f32,
+    /// Radial darkening from cell centre to edge (0 = flat, 1 = black rim)
+    pub gradient: f32,
+    /// Drop shadow offset in pixels
+    pub shadow_offset_x: f32,
+    pub shadow_offset_y: f32,
+    /// Drop shadow colour as RGBA; its alpha is the shadow opacity (0 = off)
+    pub shadow_color: u32,
+    /// Width of the soft shadow edge in pixels (0 = hard)
+    pub shadow_softness: f32,
+}
+
+impl HexStyle {
+    fn has_shadow(&self) -> bool {
+        (self.shadow_color & 0xFF) > 0
+            && (self.shadow_offset_x != 0.0 || self.shadow_offset_y != 0.0 || self.shadow_softness > 0.0)
+    }
 }
 
+/// Light direction for bevels (towards the top-left, screen y pointing down)
+const BEVEL_LIGHT: (f32, f32) = (-std::f32::consts::FRAC_1_SQRT_2, -std::f32::consts::FRAC_1_SQRT_2);
+
 impl Default for HexConfig {
     fn default() -> Self {
         Self {
@@ -26,11 +97,70 @@ impl Default for HexConfig {
             border_color: 0x282828FF,
             border_thickness: 1,
             background_color: 0x00000000,
+            supersample: 1,
+            style: HexStyle::default(),
+            alpha: HexAlpha::default(),
         }
     }
 }
 
-struct HexGeometry {
+impl HexConfig {
+    /// Names accepted by [`HexConfig::preset`], in display order
+    pub const PRESET_NAMES: &'static [&'static str] = &["default", "bordered", "pointy", "beads", "tiles"];
+
+    /// Look up a named preset
+    pub fn preset(name: &str) -> Option<Self> {
+        let base = Self::default();
+        Some(match name {
+            "default" => base,
+            "bordered" => Self {
+                draw_borders: true,
+                border_color: 0x282828FF,
+                border_thickness: 1,
+                ..base
+            },
+            "pointy" => Self {
+                orientation: HexOrientation::PointyTop,
+                draw_borders: false,
+                ..base
+            },
+            "beads" => Self {
+                background_color: 0x101010FF,
+                supersample: 4,
+                style: HexStyle {
+                    gap: 2.0,
+                    gradient: 0.45,
+                    shadow_offset_x: 1.5,
+                    shadow_offset_y: 1.5,
+                    shadow_color: 0x00000099,
+                    shadow_softness: 2.0,
+                    ..HexStyle::default()
+                },
+                ..base
+            },
+            "tiles" => Self {
+                background_color: 0x202020FF,
+                supersample: 2,
+                style: HexStyle {
+                    gap: 1.0,
+                    bevel_width: 2.0,
+                    bevel_strength: 0.35,
+                    ..HexStyle::default()
+                },
+                ..base
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Hex grid geometry shared by the rasteriser and the vector/grid exports.
+///
+/// Positions are in output pixel sample space: the raster pixel `(x, y)` is
+/// coloured by the cell containing the point `(x, y)`. Grid coordinates are
+/// "offset" `(col, row)` pairs that index the source image; axial `(q, r)`
+/// coordinates (cube `s = -q - r`) are available for hex arithmetic.
+pub struct HexGeometry {
     orientation: HexOrientation,
     scale: f32,
     m00: f32, m01: f32,
@@ -39,9 +169,13 @@ struct HexGeometry {
     offset_y: f32,
 }
 
+/// Axial direction vectors, in corner order
+const AXIAL_DIRECTIONS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
 impl HexGeometry {
-    fn new(scale: u32, orientation: HexOrientation) -> Self {
-        let size = scale.max(2) as f32;
+    /// Geometry for the given `scale` (clamped to 2..32 like `hex_upscale`)
+    pub fn new(scale: u32, orientation: HexOrientation) -> Self {
+        let size = scale.clamp(2, 32) as f32;
         let sqrt3 = 3.0_f32.sqrt();
 
         let (offset_x, offset_y) = match orientation {
@@ -72,7 +206,12 @@ impl HexGeometry {
         }
     }
 
-    fn output_dimensions(&self, input_width: u32, input_height: u32) -> (u32, u32) {
+    /// Hexagon circumradius in output pixels
+    pub fn size(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn output_dimensions(&self, input_width: u32, input_height: u32) -> (u32, u32) {
         let w = (input_width as f32) - 1.0;
         let h = (input_height as f32) - 1.0;
         let size = self.scale;
@@ -148,24 +287,125 @@ impl HexGeometry {
         }
     }
 
+    /// Normalized distance from the nearest hex centre: 0.0 at the centre, 0.5 on
+    /// every point of the cell edge (a hexagonal metric aligned with the cell)
     #[inline(always)]
-    fn is_in_border(&self, q: f32, r: f32, thickness: f32) -> bool {
+    fn edge_distance(&self, q: f32, r: f32) -> f32 {
         let s = -q - r;
-        // Suppress unused warnings by using _ prefix
-        let _rq = q.round();
-        let _rr = r.round();
-        let _rs = s.round(); 
-
         let (cq, cr) = self.hex_round(q, r);
         let cs = -cq - cr;
+
+        let dq = q - cq as f32;
+        let dr = r - cr as f32;
+        let ds = s - cs as f32;
         
-        let dist = (q - cq as f32).abs()
+        (dq - dr).abs()
+            .max((dr - ds).abs())
+            .max((ds - dq).abs()) * 0.5
+    }
+
+    /// Cube-coordinate distance from the nearest hex centre: 0.5 at edge
+    /// midpoints, 2/3 at corners. Plain borders are drawn with this metric,
+    /// which widens the band towards the corners.
+    #[inline(always)]
+    fn cube_distance(&self, q: f32, r: f32) -> f32 {
+        let s = -q - r;
+        let (cq, cr) = self.hex_round(q, r);
+        let cs = -cq - cr;
+
+        (q - cq as f32).abs()
             .max((r - cr as f32).abs())
-            .max((s - cs as f32).abs());
+            .max((s - cs as f32).abs())
+    }
 
-        let thresh = 0.5 - (thickness * 0.55 / self.scale);
-        
-        dist > thresh
+    /// Distance in pixels from the nearest cell edge (the apothem at the centre)
+    #[inline(always)]
+    fn edge_pixels(&self, q: f32, r: f32) -> f32 {
+        (0.5 - self.edge_distance(q, r)) * self.scale * 3.0_f32.sqrt()
+    }
+
+    /// Width in pixels of the band `is_in_border` marks for `thickness`
+    #[inline(always)]
+    fn border_pixels(&self, thickness: f32) -> f32 {
+        border_band_pixels(thickness)
+    }
+
+    /// Outward normal of the cell edge nearest to offset (dx, dy) from the cell centre
+    #[inline(always)]
+    fn edge_normal(&self, dx: f32, dy: f32) -> (f32, f32) {
+        let sector = std::f32::consts::FRAC_PI_3;
+        let base = match self.orientation {
+            HexOrientation::FlatTop => sector * 0.5,
+            HexOrientation::PointyTop => 0.0,
+        };
+        let k = ((dy.atan2(dx) - base) / sector).round();
+        let (sin, cos) = (base + k * sector).sin_cos();
+        (cos, sin)
+    }
+
+    #[inline(always)]
+    fn border_threshold(&self, thickness: f32) -> f32 {
+        0.5 - (thickness * 0.55 / self.scale)
+    }
+
+    /// Grid cell `(col, row)` covering output position `(x, y)`.
+    /// May be outside the source bounds.
+    pub fn pixel_to_cell(&self, x: f32, y: f32) -> (i32, i32) {
+        let (q, r) = self.pixel_to_hex_fractional(x, y);
+        self.fractional_to_grid(q, r)
+    }
+
+    /// Offset grid coordinates to axial `(q, r)`
+    pub fn grid_to_axial(&self, col: i32, row: i32) -> (i32, i32) {
+        match self.orientation {
+            HexOrientation::FlatTop => (col, row - (col - (col & 1)) / 2),
+            HexOrientation::PointyTop => (col - (row - (row & 1)) / 2, row),
+        }
+    }
+
+    /// Axial `(q, r)` to offset grid coordinates
+    pub fn axial_to_grid(&self, q: i32, r: i32) -> (i32, i32) {
+        match self.orientation {
+            HexOrientation::FlatTop => (q, r + (q - (q & 1)) / 2),
+            HexOrientation::PointyTop => (q + (r - (r & 1)) / 2, r),
+        }
+    }
+
+    /// Centre of grid cell `(col, row)` in output pixels
+    pub fn cell_center(&self, col: i32, row: i32) -> (f32, f32) {
+        let (q, r) = self.grid_to_axial(col, row);
+        let (q, r) = (q as f32, r as f32);
+        let sqrt3 = 3.0_f32.sqrt();
+        let size = self.scale;
+        match self.orientation {
+            HexOrientation::FlatTop => (
+                size * 1.5 * q + self.offset_x,
+                size * sqrt3 * (r + q * 0.5) + self.offset_y,
+            ),
+            HexOrientation::PointyTop => (
+                size * sqrt3 * (q + r * 0.5) + self.offset_x,
+                size * 1.5 * r + self.offset_y,
+            ),
+        }
+    }
+
+    /// Corner polygon of grid cell `(col, row)`, clockwise in screen space
+    pub fn cell_corners(&self, col: i32, row: i32) -> [(f32, f32); 6] {
+        let (cx, cy) = self.cell_center(col, row);
+        let start = match self.orientation {
+            HexOrientation::FlatTop => 0.0_f32,
+            HexOrientation::PointyTop => 30.0_f32,
+        };
+        std::array::from_fn(|i| {
+            let angle = (start + 60.0 * i as f32).to_radians();
+            (cx + self.scale * angle.cos(), cy + self.scale * angle.sin())
+        })
+    }
+
+    /// The six neighbours of grid cell `(col, row)`; may be outside the source bounds
+    pub fn neighbours(&self, col: i32, row: i32) -> [(i32, i32); 6] {
+        let (q, r) = self.grid_to_axial(col, row);
+        AXIAL_DIRECTIONS.map(|(dq, dr)| self.axial_to_grid(q + dq, r + dr))
     }
 }
 
@@ -193,19 +433,9 @@ pub fn hex_upscale(
     let (out_w, out_h) = geometry.output_dimensions(src_w as u32, src_h as u32);
     let mut output = vec![0u8; (out_w * out_h * 4) as usize];
 
-    let bg = [
-        ((config.background_color >> 24) & 0xFF) as u8,
-        ((config.background_color >> 16) & 0xFF) as u8,
-        ((config.background_color >> 8) & 0xFF) as u8,
-        (config.background_color & 0xFF) as u8,
-    ];
+    let bg = rgba_parts(config.background_color);
 
-    let border = [
-        ((config.border_color >> 24) & 0xFF) as u8,
-        ((config.border_color >> 16) & 0xFF) as u8,
-        ((config.border_color >> 8) & 0xFF) as u8,
-        (config.border_color & 0xFF) as u8,
-    ];
+    let border = rgba_parts(config.border_color);
 
     let check_borders = config.draw_borders && config.border_thickness > 0;
     let border_thickness_f = config.border_thickness as f32;
@@ -213,27 +443,447 @@ pub fn hex_upscale(
     let src_w_i = src_w as i32;
     let src_h_i = src_h as i32;
 
+    let style = &config.style;
+    let half_gap = style.gap.max(0.0) * 0.5;
+    let border_px = if check_borders { geometry.border_pixels(border_thickness_f) } else { 0.0 };
+    let inner_apothem = (geometry.scale * 3.0_f32.sqrt() * 0.5 - half_gap).max(1.0);
+    let shade_cells = style.gradient != 0.0 || (style.bevel_width > 0.0 && style.bevel_strength != 0.0);
+    let has_shadow = style.has_shadow();
+    let shadow = rgba_parts(style.shadow_color);
+
+    // Without a gap the border keeps the cube-distance band of plain
+    // `draw_borders`; with one it follows the visible (gapped) cell edge
+    let plain_border = check_borders && half_gap == 0.0;
+    let border_threshold = geometry.border_threshold(border_thickness_f);
+
+    // Source pixel of the cell containing (x, y), the distance from the
+    // visible cell edge (after the gap) in pixels, and whether it is border
+    let locate = |x: f32, y: f32| -> Option<(i32, i32, f32, bool)> {
+        let (q, r) = geometry.pixel_to_hex_fractional(x, y);
+        let (hex_col, hex_row) = geometry.fractional_to_grid(q, r);
+        if hex_col >= 0 && hex_row >= 0 && hex_col < src_w_i && hex_row < src_h_i {
+            let edge = geometry.edge_pixels(q, r) - half_gap;
+            let in_border = if plain_border {
+                geometry.cube_distance(q, r) > border_threshold
+            } else {
+                edge < border_px
+            };
+            Some((hex_col, hex_row, edge, in_border))
+        } else {
+            None
+        }
+    };
+    let source = |col: i32, row: i32| -> [u8; 4] {
+        let src_idx = (row as usize * src_w + col as usize) * 4;
+        [input[src_idx], input[src_idx + 1], input[src_idx + 2], input[src_idx + 3]]
+    };
+
+    let alpha = &config.alpha;
+
+    let sample = |x: f32, y: f32| -> [u8; 4] {
+        if let Some((col, row, edge, in_border)) = locate(x, y) {
+            let color = source(col, row);
+            let hidden = alpha.skip_transparent_borders && color[3] == 0;
+            if edge >= 0.0 && !hidden {
+                let cell = if in_border {
+                    border
+                } else if shade_cells {
+                    shade_cell(&geometry, style, color, edge - border_px, inner_apothem, x, y, col, row)
+                } else {
+                    color
+                };
+                return if alpha.composite { composite_over(cell, 1.0, bg) } else { cell };
+            }
+        }
+
+        if has_shadow {
+            if let Some((col, row, edge, _)) = locate(x - style.shadow_offset_x, y - style.shadow_offset_y) {
+                let coverage = if style.shadow_softness > 0.0 {
+                    (edge / style.shadow_softness + 0.5).clamp(0.0, 1.0)
+                } else if edge >= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+                if coverage > 0.0 && source(col, row)[3] > 0 {
+                    return composite_over(shadow, coverage, bg);
+                }
+            }
+        }
+        bg
+    };
+
+    // Supersampling setup: subsample offsets centred on the pixel, and the
+    // bands around every distance where a pixel can straddle two colours
+    let n = config.supersample.clamp(1, 8);
+    let offsets: Vec<f32> = (0..n).map(|i| (i as f32 + 0.5) / n as f32 - 0.5).collect();
+    let mut transitions = vec![0.0, half_gap];
+    if check_borders && !plain_border {
+        transitions.push(half_gap + border_px);
+    }
+    let cube_px = geometry.scale * 3.0_f32.sqrt();
+    if style.bevel_width > 0.0 {
+        transitions.push(half_gap + border_px + style.bevel_width);
+    }
+
     for y in 0..out_h {
         let y_f = y as f32;
         for x in 0..out_w {
             let x_f = x as f32;
-            
-            let (q, r) = geometry.pixel_to_hex_fractional(x_f, y_f);
-            let (hex_col, hex_row) = geometry.fractional_to_grid(q, r);
             let out_idx = ((y * out_w + x) * 4) as usize;
 
-            if hex_col >= 0 && hex_row >= 0 && hex_col < src_w_i && hex_row < src_h_i {
-                if check_borders && geometry.is_in_border(q, r, border_thickness_f) {
-                    output[out_idx..out_idx+4].copy_from_slice(&border);
-                } else {
-                    let src_idx = (hex_row as usize * src_w + hex_col as usize) * 4;
-                    output[out_idx..out_idx+4].copy_from_slice(&input[src_idx..src_idx+4]);
-                }
+            let near_edge = n > 1 && {
+                let (q, r) = geometry.pixel_to_hex_fractional(x_f, y_f);
+                let edge = geometry.edge_pixels(q, r);
+                transitions.iter().any(|t| (edge - t).abs() < 1.0)
+                    || (plain_border && ((geometry.cube_distance(q, r) - border_threshold) * cube_px).abs() < 1.0)
+                    || (has_shadow && locate(x_f, y_f).is_none_or(|(_, _, e, _)| e < 0.0))
+            };
+
+            let color = if near_edge {
+                supersample_pixel(&offsets, |dx, dy| sample(x_f + dx, y_f + dy))
             } else {
-                output[out_idx..out_idx+4].copy_from_slice(&bg);
-            }
+                sample(x_f, y_f)
+            };
+            output[out_idx..out_idx+4].copy_from_slice(&color);
         }
     }
 
     output
 }
+
+/// Apply gradient and bevel shading to a cell colour. `edge` is the distance
+/// in pixels from the inner edge of the border (or of the gap)
+#[allow(clippy::too_many_arguments)]
+fn shade_cell(
+    geometry: &HexGeometry,
+    style: &HexStyle,
+    color: [u8; 4],
+    edge: f32,
+    inner_apothem: f32,
+    x: f32,
+    y: f32,
+    col: i32,
+    row: i32,
+) -> [u8; 4] {
+    let mut rgb = [color[0] as f32, color[1] as f32, color[2] as f32];
+
+    if style.gradient != 0.0 {
+        let t = (1.0 - edge / inner_apothem).clamp(0.0, 1.0);
+        let factor = (1.0 - style.gradient * t * t).max(0.0);
+        rgb = rgb.map(|c| c * factor);
+    }
+
+    if style.bevel_width > 0.0 && edge < style.bevel_width {
+        let (cx, cy) = geometry.cell_center(col, row);
+        let (nx, ny) = geometry.edge_normal(x - cx, y - cy);
+        let amount = (style.bevel_strength * (nx * BEVEL_LIGHT.0 + ny * BEVEL_LIGHT.1)).clamp(-1.0, 1.0);
+        rgb = if amount > 0.0 {
+            rgb.map(|c| c + (255.0 - c) * amount)
+        } else {
+            rgb.map(|c| c * (1.0 + amount))
+        };
+    }
+
+    [
+        rgb[0].round().clamp(0.0, 255.0) as u8,
+        rgb[1].round().clamp(0.0, 255.0) as u8,
+        rgb[2].round().clamp(0.0, 255.0) as u8,
+        color[3],
+    ]
+}
+
+/// `top` with its alpha scaled by `coverage`, composited over `bottom`
+fn composite_over(top: [u8; 4], coverage: f32, bottom: [u8; 4]) -> [u8; 4] {
+    let ta = top[3] as f32 / 255.0 * coverage;
+    let ba = bottom[3] as f32 / 255.0;
+    let out_a = ta + ba * (1.0 - ta);
+    if out_a <= 0.0 {
+        return [0, 0, 0, 0];
+    }
+    let mix = |t: u8, b: u8| ((t as f32 * ta + b as f32 * ba * (1.0 - ta)) / out_a).round() as u8;
+    [
+        mix(top[0], bottom[0]),
+        mix(top[1], bottom[1]),
+        mix(top[2], bottom[2]),
+        (out_a * 255.0).round() as u8,
+    ]
+}
+
+/// Average the colours sampled at `offsets` x `offsets` around a pixel.
+/// Colours are averaged with alpha weighting so transparent samples do not
+/// darken the result.
+pub(crate) fn supersample_pixel(offsets: &[f32], mut sample: impl FnMut(f32, f32) -> [u8; 4]) -> [u8; 4] {
+    let mut acc = [0u32; 4];
+    for &dy in offsets {
+        for &dx in offsets {
+            let c = sample(dx, dy);
+            let a = c[3] as u32;
+            acc[0] += c[0] as u32 * a;
+            acc[1] += c[1] as u32 * a;
+            acc[2] += c[2] as u32 * a;
+            acc[3] += a;
+        }
+    }
+
+    let count = (offsets.len() * offsets.len()) as u32;
+    if acc[3] == 0 {
+        return [0, 0, 0, 0];
+    }
+    [
+        ((acc[0] + acc[3] / 2) / acc[3]) as u8,
+        ((acc[1] + acc[3] / 2) / acc[3]) as u8,
+        ((acc[2] + acc[3] / 2) / acc[3]) as u8,
+        ((acc[3] + count / 2) / count) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_colour_image() -> Vec<u8> {
+        // Left column red, right column blue
+        let mut data = vec![0u8; 2 * 2 * 4];
+        for y in 0..2 {
+            data[(y * 2) * 4..(y * 2) * 4 + 4].copy_from_slice(&[255, 0, 0, 255]);
+            data[(y * 2 + 1) * 4..(y * 2 + 1) * 4 + 4].copy_from_slice(&[0, 0, 255, 255]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_supersample_blends_edges() {
+        let img = two_colour_image();
+        let hard = hex_upscale(&img, 2, 2, 8, &HexConfig::default());
+        let soft = hex_upscale(&img, 2, 2, 8, &HexConfig { supersample: 4, ..Default::default() });
+        assert_eq!(hard.len(), soft.len());
+
+        let is_mixed = |p: &[u8]| p[0] > 0 && p[2] > 0;
+        assert!(!hard.chunks(4).any(is_mixed));
+        assert!(soft.chunks(4).any(is_mixed));
+
+        // Cell interiors are untouched
+        let differing = hard.chunks(4).zip(soft.chunks(4)).filter(|(a, b)| a != b).count();
+        assert!(differing < hard.len() / 4 / 2);
+    }
+
+    #[test]
+    fn test_plain_borders_keep_cube_metric() {
+        // Plain `draw_borders` output is pinned to the original cube-distance
+        // band, independent of the edge metric used by gaps and bevels
+        let img: Vec<u8> = (0..5 * 4).flat_map(|i| [i as u8 * 12, 255 - i as u8 * 12, 90, 255]).collect();
+        for orientation in [HexOrientation::FlatTop, HexOrientation::PointyTop] {
+            for (scale, thickness) in [(6, 1), (12, 2), (16, 3)] {
+                let config = HexConfig {
+                    orientation,
+                    draw_borders: true,
+                    border_thickness: thickness,
+                    ..HexConfig::default()
+                };
+                let out = hex_upscale(&img, 5, 4, scale, &config);
+
+                let geometry = HexGeometry::new(scale as u32, orientation);
+                let (w, h) = geometry.output_dimensions(5, 4);
+                let threshold = 0.5 - thickness as f32 * 0.55 / scale as f32;
+                for y in 0..h {
+                    for x in 0..w {
+                        let (q, r) = geometry.pixel_to_hex_fractional(x as f32, y as f32);
+                        let (col, row) = geometry.fractional_to_grid(q, r);
+                        let expected = if col < 0 || row < 0 || col >= 5 || row >= 4 {
+                            [0, 0, 0, 0]
+                        } else if geometry.cube_distance(q, r) > threshold {
+                            [0x28, 0x28, 0x28, 0xFF]
+                        } else {
+                            let i = (row * 5 + col) as usize * 4;
+                            [img[i], img[i + 1], img[i + 2], img[i + 3]]
+                        };
+                        let i = ((y * w + x) * 4) as usize;
+                        assert_eq!(out[i..i + 4], expected, "{orientation:?} scale {scale} at ({x}, {y})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_style_gap_bevel_and_shadow() {
+        let img = vec![200u8; 3 * 3 * 4];
+        let geometry = HexGeometry::new(12, HexOrientation::FlatTop);
+        let (cx, cy) = geometry.cell_center(1, 1);
+        let apothem = 12.0 * 3.0_f32.sqrt() / 2.0;
+        let pixel = |out: &[u8], x: f32, y: f32| {
+            let (w, _) = get_output_dimensions(3, 3, 12, &HexOrientation::FlatTop);
+            let idx = (y.round() as usize * w + x.round() as usize) * 4;
+            [out[idx], out[idx + 1], out[idx + 2], out[idx + 3]]
+        };
+
+        // A gap exposes the background between neighbouring cells
+        let gapped = HexConfig {
+            style: HexStyle { gap: 4.0, ..HexStyle::default() },
+            ..HexConfig::default()
+        };
+        let out = hex_upscale(&img, 3, 3, 12, &gapped);
+        assert_eq!(pixel(&out, cx, cy - apothem), [0, 0, 0, 0]);
+        assert_eq!(pixel(&out, cx, cy), [200, 200, 200, 200]);
+
+        // The bevel lights the top edge and shades the bottom one
+        let bevelled = HexConfig {
+            style: HexStyle { bevel_width: 3.0, bevel_strength: 0.5, ..HexStyle::default() },
+            ..HexConfig::default()
+        };
+        let out = hex_upscale(&img, 3, 3, 12, &bevelled);
+        assert!(pixel(&out, cx, cy - apothem + 1.5)[0] > 200);
+        assert!(pixel(&out, cx, cy + apothem - 1.5)[0] < 200);
+        assert_eq!(pixel(&out, cx, cy), [200, 200, 200, 200]);
+
+        // The drop shadow falls below cells, never above the top row
+        let shadowed = HexConfig {
+            background_color: 0xFFFFFFFF,
+            style: HexStyle {
+                gap: 4.0,
+                shadow_offset_y: 3.0,
+                shadow_color: 0x000000FF,
+                ..HexStyle::default()
+            },
+            ..HexConfig::default()
+        };
+        let out = hex_upscale(&img, 3, 3, 12, &shadowed);
+        assert_eq!(pixel(&out, cx, cy + apothem)[0], 0);
+        let (top_x, top_y) = geometry.cell_center(1, 0);
+        assert_eq!(pixel(&out, top_x, top_y - apothem - 1.0)[0], 255);
+    }
+
+    #[test]
+    fn test_resample_keeps_aspect_and_colours() {
+        // 40x20 image, left half red, right half blue
+        let (w, h) = (40, 20);
+        let mut img = vec![0u8; w * h * 4];
+        for (i, px) in img.chunks_mut(4).enumerate() {
+            let color = if i % w < w / 2 { [255, 0, 0, 255] } else { [0, 0, 255, 255] };
+            px.copy_from_slice(&color);
+        }
+
+        for orientation in [HexOrientation::FlatTop, HexOrientation::PointyTop] {
+            let (cells, cols, rows) = resample_grid(&img, w, h, 10, orientation, HexSampling::Average);
+            assert_eq!(cols, 10);
+            assert_eq!(cells.len(), cols * rows * 4);
+
+            // Aspect of the rendered grid stays close to the source's 2:1
+            let (out_w, out_h) = get_output_dimensions(cols, rows, 16, &orientation);
+            let aspect = out_w as f32 / out_h as f32;
+            assert!((aspect - 2.0).abs() < 0.35, "{:?} aspect {}", orientation, aspect);
+
+            let cell = |col: usize, row: usize| &cells[(row * cols + col) * 4..(row * cols + col) * 4 + 4];
+            assert_eq!(cell(0, rows / 2), [255, 0, 0, 255]);
+            assert_eq!(cell(cols - 1, rows / 2), [0, 0, 255, 255]);
+        }
+
+        // Averaging mixes cells on the seam, centre sampling never does
+        let (avg, cols, rows) = resample_grid(&img, w, h, 9, HexOrientation::FlatTop, HexSampling::Average);
+        let (center, _, _) = resample_grid(&img, w, h, 9, HexOrientation::FlatTop, HexSampling::Center);
+        let is_mixed = |p: &[u8]| p[0] > 0 && p[2] > 0;
+        assert!(avg.chunks(4).any(is_mixed));
+        assert!(!center.chunks(4).any(is_mixed));
+        assert_eq!(center.len(), cols * rows * 4);
+    }
+
+    #[test]
+    fn test_alpha_options() {
+        // Opaque red, half-transparent green, fully transparent
+        let img = [255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 0, 0];
+        let geometry = HexGeometry::new(10, HexOrientation::FlatTop);
+        let (w, _) = get_output_dimensions(3, 1, 10, &HexOrientation::FlatTop);
+        let at = |out: &[u8], col: i32| {
+            let (x, y) = geometry.cell_center(col, 0);
+            let idx = (y.round() as usize * w + x.round() as usize) * 4;
+            [out[idx], out[idx + 1], out[idx + 2], out[idx + 3]]
+        };
+        let edge_of = |out: &[u8], col: i32| {
+            let (x, y) = geometry.cell_center(col, 0);
+            let idx = ((y + 10.0 * 3.0_f32.sqrt() / 2.0 - 0.3).floor() as usize * w + x.round() as usize) * 4;
+            [out[idx], out[idx + 1], out[idx + 2], out[idx + 3]]
+        };
+        let base = HexConfig {
+            draw_borders: true,
+            border_color: 0x000000FF,
+            background_color: 0x0000FFFF,
+            ..HexConfig::default()
+        };
+
+        // Default: verbatim copy, borders everywhere
+        let out = hex_upscale(&img, 3, 1, 10, &base);
+        assert_eq!(at(&out, 1), [0, 255, 0, 128]);
+        assert_eq!(edge_of(&out, 2), [0, 0, 0, 255]);
+
+        let out = hex_upscale(&img, 3, 1, 10, &HexConfig {
+            alpha: HexAlpha { composite: true, skip_transparent_borders: true },
+            ..base.clone()
+        });
+        assert_eq!(at(&out, 0), [255, 0, 0, 255]);
+        assert_eq!(at(&out, 1), [0, 128, 127, 255]);
+        assert_eq!(at(&out, 2), [0, 0, 255, 255]);
+        assert_eq!(edge_of(&out, 2), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_geometry_round_trip() {
+        for orientation in [HexOrientation::FlatTop, HexOrientation::PointyTop] {
+            let geometry = HexGeometry::new(12, orientation);
+            for row in 0..6 {
+                for col in 0..6 {
+                    let (cx, cy) = geometry.cell_center(col, row);
+                    assert_eq!(geometry.pixel_to_cell(cx, cy), (col, row));
+
+                    let (q, r) = geometry.grid_to_axial(col, row);
+                    assert_eq!(geometry.axial_to_grid(q, r), (col, row));
+
+                    // Neighbour centres are one cell width away and map back to themselves
+                    for (ncol, nrow) in geometry.neighbours(col, row) {
+                        let (nx, ny) = geometry.cell_center(ncol, nrow);
+                        let d = ((nx - cx).powi(2) + (ny - cy).powi(2)).sqrt();
+                        assert!((d - 12.0 * 3.0_f32.sqrt()).abs() < 1e-3);
+                        assert_eq!(geometry.pixel_to_cell(nx, ny), (ncol, nrow));
+                    }
+
+                    // Points just inside each corner belong to the cell
+                    for (x, y) in geometry.cell_corners(col, row) {
+                        let (ix, iy) = (x * 0.95 + cx * 0.05, y * 0.95 + cy * 0.05);
+                        assert_eq!(geometry.pixel_to_cell(ix, iy), (col, row));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_exports() {
+        let svg = grid_svg(3, 2, 8, HexOrientation::FlatTop);
+        let (w, h) = get_output_dimensions(3, 2, 8, &HexOrientation::FlatTop);
+        assert!(svg.starts_with(&format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}""#, w, h)));
+        assert_eq!(svg.matches("<path").count(), 6);
+
+        let json = grid_json(3, 2, 8, HexOrientation::PointyTop);
+        assert!(json.contains(r#""orientation":"pointy-top""#));
+        assert_eq!(json.matches(r#""col":"#).count(), 6);
+    }
+
+    #[test]
+    fn test_svg_merges_same_colour_cells() {
+        // Uniform 3x3 image merges into a single outline
+        let img = [10u8, 20, 30, 255].repeat(9);
+        let svg = hex_svg(&img, 3, 3, 8, &HexConfig::default());
+        let (w, h) = get_output_dimensions(3, 3, 8, &HexOrientation::FlatTop);
+        assert!(svg.contains(&format!(r#"width="{}" height="{}""#, w, h)));
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert_eq!(svg.matches('M').count(), 1);
+        assert!(svg.contains("#0a141e"));
+
+        // Two colours: two paths; borders add a ring pair per cell
+        let img = two_colour_image();
+        let config = HexConfig { draw_borders: true, ..Default::default() };
+        let svg = hex_svg(&img, 2, 2, 8, &config);
+        assert_eq!(svg.matches("<path").count(), 3);
+        assert!(svg.contains("#282828"));
+    }
+}
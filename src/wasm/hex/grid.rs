@@ -0,0 +1,112 @@
+//! Vector and data exports of the hex grid.
+//!
+//! Coordinates are emitted in image space, where output pixel `(x, y)` covers
+//! the square `[x, x + 1) x [y, y + 1)`. That is the sample space used by
+//! [`HexGeometry`] shifted by half a pixel, so the exported shapes line up
+//! with the raster produced by `hex_upscale` at the same scale.
+
+use std::fmt::Write;
+
+use super::{HexGeometry, HexOrientation};
+
+/// Shift from geometry sample space to image space
+pub(crate) const PIXEL_CENTER: f32 = 0.5;
+
+/// Format a coordinate with at most two decimals and no trailing zeros
+pub(crate) fn fmt_coord(v: f32) -> String {
+    let s = format!("{:.2}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+/// SVG path data (`M .. L .. Z`) for one cell outline
+pub(crate) fn cell_path(geometry: &HexGeometry, col: i32, row: i32) -> String {
+    let mut d = String::new();
+    for (i, (x, y)) in geometry.cell_corners(col, row).iter().enumerate() {
+        let _ = write!(
+            d,
+            "{}{} {}",
+            if i == 0 { "M" } else { "L" },
+            fmt_coord(x + PIXEL_CENTER),
+            fmt_coord(y + PIXEL_CENTER)
+        );
+    }
+    d.push('Z');
+    d
+}
+
+/// SVG document with one outline `<path>` per source pixel, sized like the raster output
+pub fn grid_svg(src_w: usize, src_h: usize, scale: usize, orientation: HexOrientation) -> String {
+    let geometry = HexGeometry::new(scale as u32, orientation);
+    let (out_w, out_h) = geometry.output_dimensions(src_w as u32, src_h as u32);
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = out_w,
+        h = out_h
+    );
+    svg.push_str(r##"<g fill="none" stroke="#000" stroke-width="1">"##);
+    for row in 0..src_h as i32 {
+        for col in 0..src_w as i32 {
+            let _ = write!(
+                svg,
+                r#"<path data-col="{}" data-row="{}" d="{}"/>"#,
+                col,
+                row,
+                cell_path(&geometry, col, row)
+            );
+        }
+    }
+    svg.push_str("</g></svg>");
+    svg
+}
+
+/// JSON description of the grid: output size, cell size and, for every source
+/// pixel, its offset/axial coordinates, centre and corner polygon
+pub fn grid_json(src_w: usize, src_h: usize, scale: usize, orientation: HexOrientation) -> String {
+    let geometry = HexGeometry::new(scale as u32, orientation);
+    let (out_w, out_h) = geometry.output_dimensions(src_w as u32, src_h as u32);
+
+    let mut json = String::new();
+    let _ = write!(
+        json,
+        r#"{{"width":{},"height":{},"size":{},"orientation":"{}","cells":["#,
+        out_w,
+        out_h,
+        fmt_coord(geometry.size()),
+        match orientation {
+            HexOrientation::FlatTop => "flat-top",
+            HexOrientation::PointyTop => "pointy-top",
+        }
+    );
+    for row in 0..src_h as i32 {
+        for col in 0..src_w as i32 {
+            if row > 0 || col > 0 {
+                json.push(',');
+            }
+            let (q, r) = geometry.grid_to_axial(col, row);
+            let (cx, cy) = geometry.cell_center(col, row);
+            let _ = write!(
+                json,
+                r#"{{"col":{},"row":{},"q":{},"r":{},"center":[{},{}],"corners":["#,
+                col,
+                row,
+                q,
+                r,
+                fmt_coord(cx + PIXEL_CENTER),
+                fmt_coord(cy + PIXEL_CENTER)
+            );
+            for (i, (x, y)) in geometry.cell_corners(col, row).iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                let _ = write!(json, "[{},{}]", fmt_coord(x + PIXEL_CENTER), fmt_coord(y + PIXEL_CENTER));
+            }
+            json.push_str("]}");
+        }
+    }
+    json.push_str("]}");
+    json
+}
@@ -0,0 +1,153 @@
+//! Resampling of arbitrary images onto a hex grid.
+//!
+//! `hex_upscale` turns every source pixel into one cell, which only suits
+//! small pixel art. Here the cell count is chosen freely: the source is fitted
+//! into the grid's footprint with its aspect ratio preserved, and every cell
+//! takes its colour from the source pixels it covers. The result is a
+//! `columns x rows` cell image that can be fed to `hex_upscale` (or `hex_svg`)
+//! at any scale.
+
+use super::{HexGeometry, HexOrientation};
+
+/// How a cell's colour is taken from the source pixels it covers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HexSampling {
+    /// Alpha-weighted mean of every source pixel whose centre lies in the cell
+    #[default]
+    Average = 0,
+    /// The source pixel under the cell centre
+    Center = 1,
+}
+
+/// Geometry used for the mapping; the layout is scale invariant, so the
+/// largest size just gives the most precision
+const LAYOUT_SCALE: u32 = 32;
+
+/// Width and height in geometry units of a `cols x rows` grid
+fn grid_extent(geometry: &HexGeometry, cols: usize, rows: usize) -> (f32, f32) {
+    let size = geometry.size();
+    let sqrt3 = 3.0_f32.sqrt();
+    let (cols, rows) = (cols as f32, rows as f32);
+    match geometry.orientation {
+        HexOrientation::FlatTop => (
+            (cols - 1.0) * size * 1.5 + size * 2.0,
+            rows * size * sqrt3 + size * sqrt3 * 0.5,
+        ),
+        HexOrientation::PointyTop => (
+            cols * size * sqrt3 + size * sqrt3 * 0.5,
+            (rows - 1.0) * size * 1.5 + size * 2.0,
+        ),
+    }
+}
+
+/// Cell grid `(columns, rows)` for a source image hexified at `columns` cells
+/// across; rows follow from the source aspect ratio
+pub fn resample_dimensions(
+    src_w: usize,
+    src_h: usize,
+    columns: usize,
+    orientation: HexOrientation,
+) -> (usize, usize) {
+    let geometry = HexGeometry::new(LAYOUT_SCALE, orientation);
+    let cols = columns.max(1);
+    let (grid_w, _) = grid_extent(&geometry, cols, 1);
+    let target_h = src_h as f32 * grid_w / src_w.max(1) as f32;
+
+    // Extent grows linearly with rows, so solve for the closest row count
+    let (_, h1) = grid_extent(&geometry, cols, 1);
+    let (_, h2) = grid_extent(&geometry, cols, 2);
+    let rows = ((target_h - h1) / (h2 - h1)).round() + 1.0;
+
+    (cols, rows.max(1.0) as usize)
+}
+
+/// Resample `input` onto a hex grid `columns` cells wide. Returns the cell
+/// image (one RGBA pixel per cell) with its width and height.
+pub fn resample_grid(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    columns: usize,
+    orientation: HexOrientation,
+    sampling: HexSampling,
+) -> (Vec<u8>, usize, usize) {
+    let (cols, rows) = resample_dimensions(src_w, src_h, columns, orientation);
+    let mut cells = vec![0u8; cols * rows * 4];
+    if src_w == 0 || src_h == 0 {
+        return (cells, cols, rows);
+    }
+
+    let geometry = HexGeometry::new(LAYOUT_SCALE, orientation);
+    let (grid_w, grid_h) = grid_extent(&geometry, cols, rows);
+
+    // Uniform fit of the source into the grid footprint, centred
+    let k = (grid_w / src_w as f32).min(grid_h / src_h as f32);
+    let pad_x = (grid_w - src_w as f32 * k) * 0.5;
+    let pad_y = (grid_h - src_h as f32 * k) * 0.5;
+
+    let source = |x: usize, y: usize| -> [u8; 4] {
+        let idx = (y * src_w + x) * 4;
+        [input[idx], input[idx + 1], input[idx + 2], input[idx + 3]]
+    };
+
+    // Source pixel under the centre of a cell (geometry space is shifted by
+    // half a pixel from image space, see `grid::PIXEL_CENTER`)
+    let center_sample = |col: usize, row: usize| -> [u8; 4] {
+        let (cx, cy) = geometry.cell_center(col as i32, row as i32);
+        let sx = ((cx + 0.5 - pad_x) / k).floor().clamp(0.0, (src_w - 1) as f32);
+        let sy = ((cy + 0.5 - pad_y) / k).floor().clamp(0.0, (src_h - 1) as f32);
+        source(sx as usize, sy as usize)
+    };
+
+    match sampling {
+        HexSampling::Center => {
+            for row in 0..rows {
+                for col in 0..cols {
+                    let idx = (row * cols + col) * 4;
+                    cells[idx..idx + 4].copy_from_slice(&center_sample(col, row));
+                }
+            }
+        }
+        HexSampling::Average => {
+            // [r*a, g*a, b*a, a, count] per cell
+            let mut acc = vec![[0u64; 5]; cols * rows];
+            for y in 0..src_h {
+                let gy = pad_y + (y as f32 + 0.5) * k - 0.5;
+                for x in 0..src_w {
+                    let gx = pad_x + (x as f32 + 0.5) * k - 0.5;
+                    let (col, row) = geometry.pixel_to_cell(gx, gy);
+                    if col < 0 || row < 0 || col as usize >= cols || row as usize >= rows {
+                        continue;
+                    }
+                    let c = source(x, y);
+                    let a = c[3] as u64;
+                    let cell = &mut acc[row as usize * cols + col as usize];
+                    cell[0] += c[0] as u64 * a;
+                    cell[1] += c[1] as u64 * a;
+                    cell[2] += c[2] as u64 * a;
+                    cell[3] += a;
+                    cell[4] += 1;
+                }
+            }
+
+            for row in 0..rows {
+                for col in 0..cols {
+                    let i = row * cols + col;
+                    let [r, g, b, a, count] = acc[i];
+                    // Cells smaller than a source pixel may not contain any pixel centre
+                    // (fully transparent cells come out as 0, 0, 0, 0)
+                    let color = match (a + count / 2).checked_div(count) {
+                        None => center_sample(col, row),
+                        Some(alpha) => {
+                            let mean = |sum: u64| ((sum + a / 2) / a.max(1)) as u8;
+                            [mean(r), mean(g), mean(b), alpha as u8]
+                        }
+                    };
+                    cells[i * 4..i * 4 + 4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    (cells, cols, rows)
+}
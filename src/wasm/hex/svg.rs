@@ -0,0 +1,161 @@
+//! SVG writer for the HEX engine.
+//!
+//! Emits the same picture as `hex_upscale` as vector shapes: adjacent cells
+//! of identical colour are merged into one outline (shared edges cancel),
+//! borders become rings inset by `border_thickness`, and the document size
+//! matches `get_output_dimensions`.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::grid::{fmt_coord, PIXEL_CENTER};
+use super::{HexConfig, HexGeometry};
+use crate::canvas::rgba_parts;
+
+/// Vertex key resolution (1/64 px) used to match corners of neighbouring cells
+const VERTEX_GRID: f32 = 64.0;
+
+type VertexKey = (i64, i64);
+
+fn vertex_key(x: f32, y: f32) -> VertexKey {
+    ((x * VERTEX_GRID).round() as i64, (y * VERTEX_GRID).round() as i64)
+}
+
+/// `fill="#rrggbb"` plus `fill-opacity` for translucent colours
+pub(crate) fn fill_attrs(rgba: [u8; 4]) -> String {
+    let mut attrs = format!(r##"fill="#{:02x}{:02x}{:02x}""##, rgba[0], rgba[1], rgba[2]);
+    if rgba[3] < 255 {
+        let _ = write!(attrs, r#" fill-opacity="{}""#, fmt_coord(rgba[3] as f32 / 255.0));
+    }
+    attrs
+}
+
+/// Append closed subpaths for the boundary of the union of `cells`
+fn merged_outline(geometry: &HexGeometry, cells: &[(i32, i32)], d: &mut String) {
+    // Directed boundary edges keyed by their start vertex; an edge shared by two
+    // cells of the region appears once in each direction and cancels out
+    let mut edges: HashMap<(VertexKey, VertexKey), (f32, f32)> = HashMap::new();
+    for &(col, row) in cells {
+        let corners = geometry.cell_corners(col, row);
+        for i in 0..6 {
+            let (ax, ay) = corners[i];
+            let (bx, by) = corners[(i + 1) % 6];
+            let a = vertex_key(ax, ay);
+            let b = vertex_key(bx, by);
+            if edges.remove(&(b, a)).is_none() {
+                edges.insert((a, b), (ax, ay));
+            }
+        }
+    }
+
+    let mut next: HashMap<VertexKey, Vec<VertexKey>> = HashMap::new();
+    let mut points: HashMap<VertexKey, (f32, f32)> = HashMap::new();
+    let mut starts: Vec<VertexKey> = Vec::with_capacity(edges.len());
+    for (&(a, b), &p) in &edges {
+        next.entry(a).or_default().push(b);
+        points.insert(a, p);
+        starts.push(a);
+    }
+    // Deterministic output regardless of hash order
+    starts.sort_unstable();
+
+    for start in starts {
+        let Some(first) = next.get_mut(&start).and_then(|v| v.pop()) else {
+            continue;
+        };
+        let (x, y) = points[&start];
+        let _ = write!(d, "M{} {}", fmt_coord(x + PIXEL_CENTER), fmt_coord(y + PIXEL_CENTER));
+
+        let mut current = first;
+        while current != start {
+            let (x, y) = points[&current];
+            let _ = write!(d, "L{} {}", fmt_coord(x + PIXEL_CENTER), fmt_coord(y + PIXEL_CENTER));
+            match next.get_mut(&current).and_then(|v| v.pop()) {
+                Some(n) => current = n,
+                None => break,
+            }
+        }
+        d.push('Z');
+    }
+}
+
+/// Render the hexagonal version of `input` as an SVG document
+pub fn hex_svg(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &HexConfig,
+) -> String {
+    let geometry = HexGeometry::new(scale as u32, config.orientation);
+    let (out_w, out_h) = geometry.output_dimensions(src_w as u32, src_h as u32);
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" shape-rendering="geometricPrecision">"#,
+        w = out_w,
+        h = out_h
+    );
+
+    let bg = rgba_parts(config.background_color);
+    if bg[3] > 0 {
+        let _ = write!(svg, r#"<rect width="{}" height="{}" {}/>"#, out_w, out_h, fill_attrs(bg));
+    }
+
+    // Group cells by colour, in order of first appearance
+    let mut order: Vec<[u8; 4]> = Vec::new();
+    let mut groups: HashMap<[u8; 4], Vec<(i32, i32)>> = HashMap::new();
+    for row in 0..src_h {
+        for col in 0..src_w {
+            let i = (row * src_w + col) * 4;
+            let rgba = [input[i], input[i + 1], input[i + 2], input[i + 3]];
+            if rgba[3] == 0 {
+                continue;
+            }
+            groups
+                .entry(rgba)
+                .or_insert_with(|| {
+                    order.push(rgba);
+                    Vec::new()
+                })
+                .push((col as i32, row as i32));
+        }
+    }
+
+    for rgba in order {
+        let mut d = String::new();
+        merged_outline(&geometry, &groups[&rgba], &mut d);
+        let _ = write!(svg, r#"<path {} fill-rule="evenodd" d="{}"/>"#, fill_attrs(rgba), d);
+    }
+
+    if config.draw_borders && config.border_thickness > 0 {
+        // Each cell gets a ring from its outline to an outline inset by the thickness
+        let inset = (config.border_thickness as f32 * 2.0 / 3.0_f32.sqrt()).min(geometry.size());
+        let ratio = 1.0 - inset / geometry.size();
+        let mut d = String::new();
+        for row in 0..src_h as i32 {
+            for col in 0..src_w as i32 {
+                let (cx, cy) = geometry.cell_center(col, row);
+                let corners = geometry.cell_corners(col, row);
+                for ring in [1.0, ratio] {
+                    for (i, (x, y)) in corners.iter().enumerate() {
+                        let x = cx + (x - cx) * ring + PIXEL_CENTER;
+                        let y = cy + (y - cy) * ring + PIXEL_CENTER;
+                        let _ = write!(d, "{}{} {}", if i == 0 { "M" } else { "L" }, fmt_coord(x), fmt_coord(y));
+                    }
+                    d.push('Z');
+                }
+            }
+        }
+        let _ = write!(
+            svg,
+            r#"<path {} fill-rule="evenodd" d="{}"/>"#,
+            fill_attrs(rgba_parts(config.border_color)),
+            d
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
@@ -0,0 +1,668 @@
+//! HQ2x / HQ3x / HQ4x Upscaling Engine
+//! Pattern-based magnification after Maxim Stepin's hqx. Each of the eight
+//! neighbours is compared with the centre pixel in Rec.601 YUV (the shared
+//! conversion in `xbrz::ycbcr_lookup`); the resulting
+//! 8-bit pattern (bit order `w1 w2 w3 w4 w6 w7 w8 w9`, as in the reference)
+//! selects a case that gives every output sub-pixel as one of the reference
+//! interpolations `Interp1`..`Interp10` of the centre and its neighbours.
+//! Cases with two differing edge neighbours additionally test whether those
+//! two neighbours match each other (`Diff(w4, w2)` in the reference).
+//!
+//! The case tables are symmetric under rotation, so they are generated once
+//! per scale for the top-left corner of the output block from the rules in
+//! [`hq2x_rule`], [`hq3x_rule`] and [`hq4x_rule`] and rotated onto the other
+//! three corners. The rules list the reference's case groups as pattern
+//! masks, the same reduction FFmpeg's `hqx` filter makes of the tables.
+//! Colours mix with the reference's truncating integer formulas; alpha
+//! (absent from the RGB-only reference) is interpolated alongside and weights
+//! the colour channels, so transparent neighbours do not darken the result.
+
+use std::sync::OnceLock;
+
+use crate::xbrz::kernel::Kernel4x4;
+use crate::xbrz::oob_reader::{OobReader, OobReaderDuplicate};
+use crate::xbrz::pixel::{Pixel, Rgba8};
+use crate::xbrz::ycbcr_lookup::{ycbcr_diff, REC_601};
+
+/// HQx configuration
+#[derive(Clone, Copy)]
+pub struct HqxConfig {
+    /// Luma difference above which two pixels count as different
+    pub y_threshold: f32,
+    /// Blue-difference chroma threshold
+    pub u_threshold: f32,
+    /// Red-difference chroma threshold
+    pub v_threshold: f32,
+    /// Alpha difference above which two pixels count as different
+    pub alpha_threshold: u8,
+}
+
+impl Default for HqxConfig {
+    fn default() -> Self {
+        Self {
+            y_threshold: 48.0,
+            u_threshold: 7.0,
+            v_threshold: 6.0,
+            alpha_threshold: 16,
+        }
+    }
+}
+
+impl HqxConfig {
+    /// The hqx `Diff` test: true when `p` and `q` are visibly different
+    fn differs(&self, p: Rgba8, q: Rgba8) -> bool {
+        if p == q {
+            return false;
+        }
+        let (pa, qa) = (p.alpha(), q.alpha());
+        if pa == 0 && qa == 0 {
+            return false;
+        }
+        if pa.abs_diff(qa) > self.alpha_threshold {
+            return true;
+        }
+
+        let ([r1, g1, b1], [r2, g2, b2]) = (p.to_rgb(), q.to_rgb());
+        let diff = |a: u8, b: u8| a as i16 - b as i16;
+        let [y, u, v] = ycbcr_diff(diff(r1, r2), diff(g1, g2), diff(b1, b2), REC_601);
+        y.abs() as f32 > self.y_threshold
+            || u.abs() as f32 > self.u_threshold
+            || v.abs() as f32 > self.v_threshold
+    }
+}
+
+/// Supported factors: 2, 3 and 4 (anything else is clamped into that range)
+fn clamp_scale(scale: usize) -> usize {
+    scale.clamp(2, 4)
+}
+
+pub fn get_output_dimensions(src_w: usize, src_h: usize, scale: usize) -> (usize, usize) {
+    let scale = clamp_scale(scale);
+    (src_w * scale, src_h * scale)
+}
+
+/// One output sub-pixel: up to three of the neighbours `w1..=w9` (`w5` is
+/// the centre) with integer weights summing to `1 << shift`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Interp {
+    terms: [(u8, u8); 3],
+    shift: u8,
+}
+
+impl Interp {
+    const fn new(terms: [(u8, u8); 3], shift: u8) -> Self {
+        Self { terms, shift }
+    }
+}
+
+/// `w5` unchanged
+const fn keep() -> Interp { Interp::new([(5, 1), (5, 0), (5, 0)], 0) }
+/// `(3a + b) / 4`
+const fn interp1(a: u8, b: u8) -> Interp { Interp::new([(a, 3), (b, 1), (5, 0)], 2) }
+/// `(2a + b + c) / 4`
+const fn interp2(a: u8, b: u8, c: u8) -> Interp { Interp::new([(a, 2), (b, 1), (c, 1)], 2) }
+/// `(7a + b) / 8`
+const fn interp3(a: u8, b: u8) -> Interp { Interp::new([(a, 7), (b, 1), (5, 0)], 3) }
+/// `(2a + 7b + 7c) / 16`
+const fn interp4(a: u8, b: u8, c: u8) -> Interp { Interp::new([(a, 2), (b, 7), (c, 7)], 4) }
+/// `(a + b) / 2`
+const fn interp5(a: u8, b: u8) -> Interp { Interp::new([(a, 1), (b, 1), (5, 0)], 1) }
+/// `(5a + 2b + c) / 8`
+const fn interp6(a: u8, b: u8, c: u8) -> Interp { Interp::new([(a, 5), (b, 2), (c, 1)], 3) }
+/// `(6a + b + c) / 8`
+const fn interp7(a: u8, b: u8, c: u8) -> Interp { Interp::new([(a, 6), (b, 1), (c, 1)], 3) }
+/// `(5a + 3b) / 8`
+const fn interp8(a: u8, b: u8) -> Interp { Interp::new([(a, 5), (b, 3), (5, 0)], 3) }
+/// `(2a + 3b + 3c) / 8`
+const fn interp9(a: u8, b: u8, c: u8) -> Interp { Interp::new([(a, 2), (b, 3), (c, 3)], 3) }
+/// `(14a + b + c) / 16`
+const fn interp10(a: u8, b: u8, c: u8) -> Interp { Interp::new([(a, 14), (b, 1), (c, 1)], 4) }
+
+/// Neighbourhood of the top-left output corner: the pattern bits and the
+/// three cross tests the cases of that corner use
+#[derive(Clone, Copy)]
+struct Corner {
+    pattern: u8,
+    /// `Diff(w4, w2)`
+    diff_42: bool,
+    /// `Diff(w2, w6)`
+    diff_26: bool,
+    /// `Diff(w8, w4)`
+    diff_84: bool,
+}
+
+/// Pattern bit each bit moves to when mirroring across the w1-w9 diagonal
+const TRANSPOSED_BIT: [u8; 8] = [0, 3, 5, 1, 6, 2, 4, 7];
+
+/// Neighbour `w` mirrored across the w1-w9 diagonal
+const TRANSPOSED: [u8; 10] = [0, 1, 4, 7, 2, 5, 8, 3, 6, 9];
+
+impl Corner {
+    fn from_index(index: usize) -> Self {
+        Self {
+            pattern: index as u8,
+            diff_42: index & 0x100 != 0,
+            diff_26: index & 0x200 != 0,
+            diff_84: index & 0x400 != 0,
+        }
+    }
+
+    /// Whether the pattern bits under `mask` are exactly `bits`
+    fn is(&self, mask: u8, bits: u8) -> bool {
+        self.pattern & mask == bits
+    }
+
+    /// Whether the pattern is in `group`
+    fn any(&self, group: &[(u8, u8)]) -> bool {
+        group.iter().any(|&(mask, bits)| self.is(mask, bits))
+    }
+
+    /// The neighbourhood mirrored across the w1-w9 diagonal
+    fn transposed(&self) -> Self {
+        let pattern = (0..8)
+            .filter(|&bit| self.pattern & 1 << bit != 0)
+            .fold(0u8, |acc, bit| acc | 1 << TRANSPOSED_BIT[bit]);
+        Self { pattern, diff_42: self.diff_42, diff_26: self.diff_84, diff_84: self.diff_26 }
+    }
+}
+
+impl Interp {
+    /// The same mix mirrored across the w1-w9 diagonal
+    fn transposed(self) -> Self {
+        Self { terms: self.terms.map(|(n, weight)| (TRANSPOSED[n as usize], weight)), shift: self.shift }
+    }
+}
+
+// Groups of reference cases as (mask, bits) pairs over the pattern. Each
+// group shares the sub-pixels of the top-left corner in all three scales.
+
+/// An edge through w2 and w6 that `Diff(w2, w6)` decides
+const EDGE_26: &[(u8, u8)] = &[(0xbf, 0x37), (0xdb, 0x13)];
+/// An edge through w8 and w4 that `Diff(w8, w4)` decides
+const EDGE_84: &[(u8, u8)] = &[(0xdb, 0x49), (0xef, 0x6d)];
+/// Corners kept whole when w4 and w2 differ
+const CORNER_42: &[(u8, u8)] = &[(0x0b, 0x0b), (0xfe, 0x4a), (0xfe, 0x1a)];
+/// Corners cut towards w1 when w4 and w2 differ
+const CUT_42: &[(u8, u8)] = &[
+    (0x6f, 0x2a), (0x5b, 0x0a), (0xbf, 0x3a), (0xdf, 0x5a), (0x9f, 0x8a), (0xcf, 0x8a), (0xef, 0x4e),
+    (0x3f, 0x0e), (0xfb, 0x5a), (0xbb, 0x8a), (0x7f, 0x5a), (0xaf, 0x8a), (0xeb, 0x8a),
+];
+/// w2 differs on its own: the sub-pixel leans to w4
+const SIDE_2: &[(u8, u8)] = &[(0x1b, 0x03), (0x4f, 0x43), (0x8b, 0x83), (0x6b, 0x43)];
+/// w4 differs on its own: the sub-pixel leans to w2
+const SIDE_4: &[(u8, u8)] = &[(0x4b, 0x09), (0x8b, 0x89), (0x1f, 0x19), (0x3b, 0x19)];
+/// A steep diagonal edge from w4 to w2
+const STEEP: &[(u8, u8)] = &[(0x7e, 0x2a), (0xef, 0xab), (0xbf, 0x8f), (0x7e, 0x0e)];
+/// A 45° diagonal edge from w4 to w2
+const DIAGONAL: &[(u8, u8)] = &[
+    (0x4f, 0x4b), (0x9f, 0x1b), (0x2f, 0x0b), (0xbe, 0x0a), (0xee, 0x0a), (0x7e, 0x0a), (0xeb, 0x4b),
+    (0x3b, 0x1b),
+];
+/// One straight edge: the sub-pixel leans to w1
+const STRAIGHT: &[(u8, u8)] = &[
+    (0x0b, 0x08), (0xf9, 0x68), (0xf3, 0x62), (0x6d, 0x6c), (0x67, 0x66), (0x3d, 0x3c), (0x37, 0x36),
+    (0xf9, 0xf8), (0xdd, 0xdc), (0xf3, 0xf2), (0xd7, 0xd6), (0xdd, 0x1c), (0xd7, 0x16), (0x0b, 0x02),
+];
+
+/// HQ2x top-left sub-pixel
+fn hq2x_rule(c: &Corner) -> [Interp; 1] {
+    let p = if c.any(EDGE_26) && c.diff_26 {
+        interp1(5, 4)
+    } else if c.any(EDGE_84) && c.diff_84 {
+        interp1(5, 2)
+    } else if c.any(CORNER_42) && c.diff_42 {
+        keep()
+    } else if c.any(CUT_42) && c.diff_42 {
+        interp1(5, 1)
+    } else if c.is(0x0b, 0x08) {
+        interp2(5, 1, 2)
+    } else if c.is(0x0b, 0x02) {
+        interp2(5, 1, 4)
+    } else if c.is(0x2f, 0x2f) {
+        interp10(5, 4, 2)
+    } else if c.any(EDGE_26) {
+        interp6(5, 2, 4)
+    } else if c.any(EDGE_84) {
+        interp6(5, 4, 2)
+    } else if c.any(SIDE_2) {
+        interp1(5, 4)
+    } else if c.any(SIDE_4) {
+        interp1(5, 2)
+    } else if c.any(STEEP) {
+        interp9(5, 4, 2)
+    } else if c.any(&[(0xfb, 0x6a), (0x6f, 0x6e), (0x3f, 0x3e), (0xfb, 0xfa), (0xdf, 0xde), (0xdf, 0x1e)]) {
+        interp1(5, 1)
+    } else if c.is(0x0a, 0x00) || c.any(DIAGONAL) {
+        interp2(5, 4, 2)
+    } else {
+        interp7(5, 4, 2)
+    };
+    [p]
+}
+
+/// HQ3x top-left corner and top-middle sub-pixels
+fn hq3x_rule(c: &Corner) -> [Interp; 2] {
+    let corner = if c.any(EDGE_26) && c.diff_26 {
+        interp1(5, 4)
+    } else if c.any(EDGE_84) && c.diff_84 {
+        interp1(5, 2)
+    } else if c.any(CORNER_42) && c.diff_42 {
+        keep()
+    } else if c.any(CUT_42) && c.diff_42 {
+        interp1(5, 1)
+    } else if c.any(SIDE_4) {
+        interp1(5, 2)
+    } else if c.any(SIDE_2) {
+        interp1(5, 4)
+    } else if c.any(STEEP) {
+        interp5(4, 2)
+    } else if c.any(DIAGONAL) {
+        interp4(5, 4, 2)
+    } else if c.any(STRAIGHT) {
+        interp1(5, 1)
+    } else {
+        interp2(5, 4, 2)
+    };
+
+    // The top side continues an edge through w2 and w6, or its mirror image
+    let continues_26 = c.any(&[(0xfe, 0xde), (0x9e, 0x16), (0xda, 0x12), (0x17, 0x16), (0x5b, 0x12), (0xbb, 0x12)]);
+    let continues_42 = c.any(&[(0x0f, 0x0b), (0x5e, 0x0a), (0xfb, 0x7b), (0x3b, 0x0b), (0xbe, 0x0a), (0x7a, 0x0a)]);
+    let middle = if (continues_26 && c.diff_26) || (continues_42 && c.diff_42) {
+        keep()
+    } else if c.any(&[(0xbf, 0x8f), (0x7e, 0x0e), (0xbf, 0x37), (0xdb, 0x13)]) {
+        interp1(2, 5)
+    } else if c.any(&[(0x02, 0x00), (0x7c, 0x28), (0xed, 0xa9), (0xf5, 0xb4), (0xd9, 0x90)]) {
+        interp1(5, 2)
+    } else if c.any(&[
+        (0x4f, 0x4b), (0xfb, 0x7b), (0xfe, 0x7e), (0x9f, 0x1b), (0x2f, 0x0b), (0xbe, 0x0a), (0x7e, 0x0a),
+        (0xfb, 0x4b), (0xfb, 0xdb), (0xfe, 0xde), (0xfe, 0x56), (0x57, 0x56), (0x97, 0x16), (0x3f, 0x1e),
+        (0xdb, 0x12), (0xbb, 0x12),
+    ]) {
+        interp3(5, 2)
+    } else {
+        keep()
+    };
+    [corner, middle]
+}
+
+/// HQ4x sub-pixel right of the top-left corner; the one below it is this
+/// rule on the transposed neighbourhood
+fn hq4x_edge(c: &Corner) -> Interp {
+    if c.any(EDGE_26) && c.diff_26 {
+        interp3(5, 4)
+    } else if c.any(&[(0x0f, 0x0b), (0x2b, 0x0b), (0xfe, 0x4a), (0xfe, 0x1a)]) && c.diff_42 {
+        keep()
+    } else if c.any(CUT_42) && c.diff_42 {
+        interp1(5, 1)
+    } else if c.is(0x0a, 0x00) {
+        interp6(5, 2, 4)
+    } else if c.is(0x0b, 0x08) {
+        interp6(5, 2, 1)
+    } else if c.is(0x0b, 0x09) {
+        interp8(5, 2)
+    } else if c.any(EDGE_26) {
+        interp1(2, 5)
+    } else if c.any(&[(0x7e, 0x2a), (0xef, 0xab)]) {
+        interp2(2, 5, 4)
+    } else if c.any(&[(0xbf, 0x8f), (0x7e, 0x0e)]) {
+        interp8(2, 4)
+    } else if c.any(SIDE_2) {
+        interp3(5, 4)
+    } else if c.any(&[
+        (0xf3, 0x62), (0x67, 0x66), (0x37, 0x36), (0xf3, 0xf2), (0xd7, 0xd6), (0xd7, 0x16), (0x0b, 0x02),
+    ]) {
+        interp1(5, 1)
+    } else if c.any(DIAGONAL) {
+        interp5(2, 5)
+    } else if c.is(0x2f, 0x2f) {
+        keep()
+    } else {
+        interp1(5, 2)
+    }
+}
+
+/// HQ4x top-left 2x2 sub-pixels: corner, right of it, below it, diagonal
+fn hq4x_rule(c: &Corner) -> [Interp; 4] {
+    let corner = if c.any(EDGE_26) && c.diff_26 {
+        interp8(5, 4)
+    } else if c.any(EDGE_84) && c.diff_84 {
+        interp8(5, 2)
+    } else if c.any(CORNER_42) && c.diff_42 {
+        keep()
+    } else if c.any(CUT_42) && c.diff_42 {
+        interp8(5, 1)
+    } else if c.any(EDGE_84) {
+        interp1(5, 4)
+    } else if c.any(EDGE_26) {
+        interp1(5, 2)
+    } else if c.any(SIDE_2) {
+        interp8(5, 4)
+    } else if c.any(SIDE_4) {
+        interp8(5, 2)
+    } else if c.any(&[(0x0f, 0x0b), (0x5e, 0x0a), (0x2b, 0x0b), (0xbe, 0x0a), (0x7a, 0x0a), (0xee, 0x0a)]) {
+        interp5(2, 4)
+    } else if c.any(STRAIGHT) {
+        interp8(5, 1)
+    } else {
+        interp2(5, 4, 2)
+    };
+
+    let diagonal = if c.any(&[(0x7f, 0x2b), (0xef, 0xab), (0xbf, 0x8f), (0x7f, 0x0f)]) && c.diff_42 {
+        keep()
+    } else if c.any(CUT_42) && c.diff_42 {
+        interp3(5, 1)
+    } else if c.is(0x0b, 0x03) {
+        interp3(5, 4)
+    } else if c.is(0x0b, 0x09) {
+        interp3(5, 2)
+    } else if c.is(0x0a, 0x00) || c.any(STEEP) {
+        interp7(5, 4, 2)
+    } else if c.any(STRAIGHT) {
+        interp3(5, 1)
+    } else {
+        keep()
+    };
+
+    [corner, hq4x_edge(c), hq4x_edge(&c.transposed()).transposed(), diagonal]
+}
+
+/// Case table for one scale: the top-left sub-pixels of every `Corner`,
+/// indexed by pattern | `diff_42` << 8 | `diff_26` << 9 | `diff_84` << 10
+struct CaseTable {
+    /// Sub-pixel positions in the top-left corner of the block
+    positions: &'static [(usize, usize)],
+    cases: Vec<[Interp; 4]>,
+}
+
+impl CaseTable {
+    fn build<const N: usize>(positions: &'static [(usize, usize)], rule: fn(&Corner) -> [Interp; N]) -> Self {
+        let cases = (0..1 << 11)
+            .map(|index| {
+                let mut entry = [keep(); 4];
+                entry[..N].copy_from_slice(&rule(&Corner::from_index(index)));
+                entry
+            })
+            .collect();
+        Self { positions, cases }
+    }
+
+    fn for_scale(scale: usize) -> &'static CaseTable {
+        static TABLES: OnceLock<[CaseTable; 3]> = OnceLock::new();
+        let tables = TABLES.get_or_init(|| [
+            Self::build(&[(0, 0)], hq2x_rule),
+            Self::build(&[(0, 0), (1, 0)], hq3x_rule),
+            Self::build(&[(0, 0), (1, 0), (0, 1), (1, 1)], hq4x_rule),
+        ]);
+        &tables[scale - 2]
+    }
+}
+
+/// Neighbour `w` of the top-left corner seen from each of the four corners
+/// (clockwise from top-left): a quarter turn per step
+const ROTATED: [[u8; 10]; 4] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [0, 3, 6, 9, 2, 5, 8, 1, 4, 7],
+    [0, 9, 8, 7, 6, 5, 4, 3, 2, 1],
+    [0, 7, 4, 1, 8, 5, 2, 9, 6, 3],
+];
+
+/// Pattern bit of each neighbour `w1..=w9` in the reference order
+const PATTERN_BIT: [u8; 10] = [0, 0, 1, 2, 3, 0, 4, 5, 6, 7];
+
+/// Mix the neighbours of `interp` with the reference's truncating integer
+/// arithmetic, weighting colours by alpha
+fn blend(w: &[Rgba8; 10], interp: &Interp) -> [u8; 4] {
+    let mut acc = [0u32; 4];
+    for &(n, weight) in &interp.terms {
+        let p = w[n as usize];
+        let [r, g, b] = p.to_rgb();
+        let a = p.alpha() as u32;
+        let weight = weight as u32;
+        acc[0] += r as u32 * a * weight;
+        acc[1] += g as u32 * a * weight;
+        acc[2] += b as u32 * a * weight;
+        acc[3] += a * weight;
+    }
+
+    if acc[3] == 0 {
+        return [0, 0, 0, 0];
+    }
+    [
+        (acc[0] / acc[3]) as u8,
+        (acc[1] / acc[3]) as u8,
+        (acc[2] / acc[3]) as u8,
+        (acc[3] >> interp.shift) as u8,
+    ]
+}
+
+pub fn hqx_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &HqxConfig,
+) -> Vec<u8> {
+    if src_w == 0 || src_h == 0 {
+        return vec![];
+    }
+    assert_eq!(input.len(), src_w * src_h * 4);
+    let (_, src, _) = unsafe { input.align_to::<Rgba8>() };
+
+    let scale = clamp_scale(scale);
+    let table = CaseTable::for_scale(scale);
+    let dst_w = src_w * scale;
+    let mut output = vec![0u8; dst_w * src_h * scale * 4];
+
+    for y in 0..src_h {
+        let oob_reader = OobReaderDuplicate::new(src, src_w, src_h, y as isize);
+        let mut kernel = Kernel4x4::init_row(&oob_reader);
+
+        for x in 0..src_w {
+            kernel.next_column(&oob_reader, x as isize);
+            let k = &kernel;
+            // Reference numbering: w1 w2 w3 / w4 w5 w6 / w7 w8 w9
+            let w = [k.f, k.a, k.b, k.c, k.e, k.f, k.g, k.i, k.j, k.k];
+
+            let mut differs = [false; 10];
+            for n in [1, 2, 3, 4, 6, 7, 8, 9] {
+                differs[n] = config.differs(w[5], w[n]);
+            }
+            // Diff(w4, w2), Diff(w2, w6), Diff(w6, w8), Diff(w8, w4): the
+            // cross test of each corner, clockwise from top-left
+            let cross = [(4, 2), (2, 6), (6, 8), (8, 4)]
+                .map(|(a, b)| differs[a] && differs[b] && config.differs(w[a], w[b]));
+
+            for (turn, rotated) in ROTATED.iter().enumerate() {
+                let pattern = (1..=9)
+                    .filter(|&n| n != 5 && differs[rotated[n] as usize])
+                    .fold(0u8, |acc, n| acc | 1 << PATTERN_BIT[n]);
+                let index = pattern as usize
+                    | (cross[turn] as usize) << 8
+                    | (cross[(turn + 1) % 4] as usize) << 9
+                    | (cross[(turn + 3) % 4] as usize) << 10;
+                let entry = &table.cases[index];
+
+                for (&(sx, sy), interp) in table.positions.iter().zip(entry) {
+                    let interp = Interp {
+                        terms: interp.terms.map(|(n, weight)| (rotated[n as usize], weight)),
+                        shift: interp.shift,
+                    };
+                    // Rotate the sub-pixel position a quarter turn per step
+                    let (mut ox, mut oy) = (sx, sy);
+                    for _ in 0..turn {
+                        (ox, oy) = (scale - 1 - oy, ox);
+                    }
+                    let idx = ((y * scale + oy) * dst_w + x * scale + ox) * 4;
+                    output[idx..idx + 4].copy_from_slice(&blend(&w, &interp));
+                }
+            }
+
+            // HQ3x centre
+            if scale == 3 {
+                let idx = ((y * 3 + 1) * dst_w + x * 3 + 1) * 4;
+                output[idx..idx + 4].copy_from_slice(&blend(&w, &keep()));
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(w: usize, h: usize, rgba: [u8; 4]) -> Vec<u8> {
+        rgba.repeat(w * h)
+    }
+
+    /// 3x3 image from the reference neighbourhood `w1..=w9` (opaque greys)
+    fn neighbourhood(greys: [u8; 9]) -> Vec<u8> {
+        greys.iter().flat_map(|&g| [g, g, g, 255]).collect()
+    }
+
+    /// The scale x scale block of the centre pixel as greys
+    fn centre_block(img: &[u8], scale: usize) -> Vec<Vec<u8>> {
+        let out = hqx_upscale(img, 3, 3, scale, &HqxConfig::default());
+        (0..scale)
+            .map(|sy| (0..scale).map(|sx| out[((scale + sy) * 3 * scale + scale + sx) * 4]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_flat_image_stays_flat() {
+        let img = solid(3, 2, [40, 90, 200, 255]);
+        for scale in 2..=4 {
+            let out = hqx_upscale(&img, 3, 2, scale, &HqxConfig::default());
+            assert_eq!(out, solid(3 * scale, 2 * scale, [40, 90, 200, 255]));
+        }
+    }
+
+    #[test]
+    fn test_reference_cases() {
+        // Similar but distinct greys around a centre of 120
+        let similar = [100, 104, 108, 112, 120, 116, 124, 128, 132];
+
+        // Pattern 0: HQ2x 20 20 / 20 20
+        assert_eq!(centre_block(&neighbourhood(similar), 2), [[114, 115], [120, 121]]);
+        // Pattern 0: HQ3x 2 1 2 / 1 C 1 / 2 1 2
+        assert_eq!(
+            centre_block(&neighbourhood(similar), 3),
+            [[114, 116, 115], [118, 120, 119], [120, 122, 121]]
+        );
+        // Pattern 0: HQ4x 20 60 61 20 / 60 70 70 61 / 61 70 70 60 / 20 61 60 20
+        assert_eq!(
+            centre_block(&neighbourhood(similar), 4),
+            [
+                [114, 115, 115, 115],
+                [116, 117, 117, 117],
+                [119, 120, 120, 120],
+                [120, 121, 121, 121],
+            ]
+        );
+
+        // Pattern 2 (w2 differs): HQ2x 22 21 / 20 20
+        let mut top = similar;
+        top[1] = 0;
+        assert_eq!(centre_block(&neighbourhood(top), 2), [[113, 116], [120, 121]]);
+        // Pattern 3 (w1 and w2 differ): HQ2x 11 21 / 20 20
+        top[0] = 0;
+        assert_eq!(centre_block(&neighbourhood(top), 2), [[118, 116], [120, 121]]);
+        // Pattern 3: HQ3x 1L C 1M / 1 C 1 / 2 1 2
+        assert_eq!(
+            centre_block(&neighbourhood(top), 3),
+            [[118, 120, 117], [118, 120, 119], [120, 122, 121]]
+        );
+    }
+
+    #[test]
+    fn test_cross_tests_pick_the_reference_case() {
+        let similar = [100, 104, 108, 112, 120, 116, 124, 128, 132];
+
+        // Pattern 19 (w1, w2 and w6 differ), Diff(w2, w6): HQ2x 11 10
+        let mut edge = similar;
+        (edge[0], edge[1], edge[5]) = (0, 0, 250);
+        assert_eq!(centre_block(&neighbourhood(edge), 2), [[118, 117], [120, 125]]);
+        // Pattern 19 with w2 matching w6: HQ2x 60 90, HQ3x 2 6 5 / 1 C 1,
+        // HQ4x 12 14 83 50 / 81 31 70 21
+        edge[5] = 10;
+        assert_eq!(centre_block(&neighbourhood(edge), 2), [[89, 33], [120, 125]]);
+        assert_eq!(
+            centre_block(&neighbourhood(edge), 3),
+            [[88, 30, 5], [118, 120, 92], [120, 122, 123]]
+        );
+        assert_eq!(
+            centre_block(&neighbourhood(edge), 4),
+            [
+                [90, 30, 3, 5],
+                [117, 119, 91, 35],
+                [119, 120, 121, 123],
+                [120, 121, 123, 124],
+            ]
+        );
+
+        // Pattern 10 (w2 and w4 differ), Diff(w4, w2): HQ4x 80 10 / 10 30
+        let mut corner = similar;
+        (corner[1], corner[3]) = (0, 250);
+        assert_eq!(centre_block(&neighbourhood(corner), 4)[..2], [[112, 115, 117, 115], [115, 117, 118, 117]]);
+        // Pattern 10 with w4 matching w2: HQ2x 20, HQ3x 4 3 / 3 C, HQ4x 50 50 / 50 0
+        corner[3] = 10;
+        assert_eq!(centre_block(&neighbourhood(corner), 2)[0][0], 62);
+        assert_eq!(centre_block(&neighbourhood(corner), 3)[..2], [[19, 105, 117], [106, 120, 119]]);
+        assert_eq!(centre_block(&neighbourhood(corner), 4)[..2], [[5, 60, 117, 115], [65, 120, 118, 117]]);
+
+        // Pattern 255: HQ2x 0 / 100 and HQ4x 0 / 20 at the w4-w2 corner
+        let mut ring = [0; 9];
+        (ring[3], ring[4]) = (250, 120);
+        assert_eq!(centre_block(&neighbourhood(ring), 2), [[120, 105], [120, 105]]);
+        assert_eq!(centre_block(&neighbourhood(ring), 4)[0], [120, 120, 120, 60]);
+        ring[3] = 0;
+        assert_eq!(centre_block(&neighbourhood(ring), 2), [[105, 105], [105, 105]]);
+        assert_eq!(centre_block(&neighbourhood(ring), 4)[0], [60, 120, 120, 60]);
+    }
+
+    #[test]
+    fn test_thresholds_decide_blending() {
+        // Two greys 30 apart in luma: similar by default, different with a tight threshold
+        let img = [100, 100, 100, 255, 130, 130, 130, 255];
+        let out = hqx_upscale(&img, 2, 1, 2, &HqxConfig::default());
+        assert_eq!(&out[4..8], &[107, 107, 107, 255]);
+
+        let strict = HqxConfig { y_threshold: 20.0, ..HqxConfig::default() };
+        let out = hqx_upscale(&img, 2, 1, 2, &strict);
+        assert_eq!(&out[4..8], &[100, 100, 100, 255]);
+    }
+
+    #[test]
+    fn test_case_tables_are_symmetric() {
+        // Mirroring a case across the w1-w9 diagonal mirrors its sub-pixels
+        let sorted = |i: Interp| {
+            let mut terms = i.terms;
+            terms.sort();
+            terms
+        };
+        for index in 0..1 << 11 {
+            let c = Corner::from_index(index);
+            let mirrored = c.transposed();
+            let flip = |i: Interp| sorted(i.transposed());
+
+            assert_eq!(flip(hq2x_rule(&c)[0]), sorted(hq2x_rule(&mirrored)[0]));
+            assert_eq!(flip(hq3x_rule(&c)[0]), sorted(hq3x_rule(&mirrored)[0]));
+            let (a, b) = (hq4x_rule(&c), hq4x_rule(&mirrored));
+            for (p, q) in [(0, 0), (1, 2), (2, 1), (3, 3)] {
+                assert_eq!(flip(a[p]), sorted(b[q]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_transparent_neighbours_do_not_darken() {
+        let mut img = solid(3, 3, [0, 0, 0, 0]);
+        img[16..20].copy_from_slice(&[255, 0, 0, 255]);
+        for scale in 2..=4 {
+            let out = hqx_upscale(&img, 3, 3, scale, &HqxConfig::default());
+            assert!(out.chunks(4).any(|p| p[3] > 0));
+            assert!(out.chunks(4).filter(|p| p[3] > 0).all(|p| p[..3] == [255, 0, 0]));
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! LCD Display Rendering Engine
+//! Handheld and dot-matrix LCD emulation: every source pixel becomes one LCD
+//! cell of `scale` x `scale` output pixels, separated by a thin gap.
+//!
+//! The model decides how source colours reach the panel: the Game Boy (DMG)
+//! maps luminance onto four shades of green, Game Boy Color / Advance screens
+//! pass colours through a correction matrix that imitates their washed-out
+//! panels, and the generic dot matrix shows colours as they are. Subpixel
+//! stripes, the drop shadow cells cast on a reflective backing and backlight
+//! bleeding into the gaps are shared by all models. Ghosting, the slow pixel
+//! response of the DMG, blends each frame with the previous output frame the
+//! caller passes back in.
+
+use crate::canvas::rgba_parts;
+
+/// Panel type, selecting the colour transform
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LcdModel {
+    /// Original Game Boy: four shades from `shades`
+    Dmg = 0,
+    /// Game Boy Color / Advance: `color_matrix` in linear light
+    Color = 1,
+    /// Generic dot-matrix panel showing the source colours
+    DotMatrix = 2,
+}
+
+/// Gamma the colour matrices were tuned for
+const PANEL_GAMMA: f32 = 2.2;
+
+/// Game Boy Color panel response (rows produce output R, G, B)
+pub const GBC_COLOR_MATRIX: [[f32; 3]; 3] = [
+    [0.82, 0.24, -0.06],
+    [0.125, 0.665, 0.21],
+    [0.195, 0.075, 0.73],
+];
+
+/// Game Boy Advance panel response (rows produce output R, G, B)
+pub const GBA_COLOR_MATRIX: [[f32; 3]; 3] = [
+    [0.80, 0.275, -0.075],
+    [0.135, 0.64, 0.225],
+    [0.195, 0.155, 0.65],
+];
+
+/// LCD configuration
+#[derive(Clone, Copy)]
+pub struct LcdConfig {
+    pub model: LcdModel,
+    /// DMG shades from darkest to lightest as `0xRRGGBBAA`
+    pub shades: [u32; 4],
+    /// Colour correction of the `Color` model
+    pub color_matrix: [[f32; 3]; 3],
+    /// Brightness multiplier applied with the colour matrix
+    pub luminance: f32,
+    /// Width of the gap between cells as a fraction of a cell
+    pub grid_width: f32,
+    /// How strongly the gaps show what lies behind the panel
+    pub grid_opacity: f32,
+    /// Strength of the vertical R/G/B stripes inside each cell
+    pub subpixel_opacity: f32,
+    /// Strength of the drop shadow dark cells cast down-right onto the
+    /// reflective backing
+    pub shadow: f32,
+    /// How much of the lit picture shows through the gaps
+    pub backlight_bleed: f32,
+    /// Share of the previous output frame still visible in the new one
+    pub ghosting: f32,
+    /// Colour behind the gaps of backlit panels as `0xRRGGBBAA`
+    pub background_color: u32,
+}
+
+impl Default for LcdConfig {
+    fn default() -> Self {
+        Self {
+            model: LcdModel::Dmg,
+            shades: [0x0F380FFF, 0x306230FF, 0x8BAC0FFF, 0x9BBC0FFF],
+            color_matrix: GBC_COLOR_MATRIX,
+            luminance: 1.0,
+            grid_width: 0.12,
+            grid_opacity: 0.35,
+            subpixel_opacity: 0.0,
+            shadow: 0.3,
+            backlight_bleed: 0.0,
+            ghosting: 0.4,
+            background_color: 0x000000FF,
+        }
+    }
+}
+
+impl LcdConfig {
+    /// Names accepted by [`LcdConfig::preset`], in display order
+    pub const PRESET_NAMES: &'static [&'static str] = &["default", "pocket", "gbc", "gba", "dot-matrix"];
+
+    /// Look up a named preset
+    pub fn preset(name: &str) -> Option<Self> {
+        let base = Self::default();
+        Some(match name {
+            "default" => base,
+            "pocket" => Self {
+                shades: [0x181818FF, 0x4A5138FF, 0x8C926BFF, 0xC5CAA4FF],
+                grid_opacity: 0.25,
+                shadow: 0.2,
+                ghosting: 0.25,
+                ..base
+            },
+            "gbc" => Self {
+                model: LcdModel::Color,
+                color_matrix: GBC_COLOR_MATRIX,
+                luminance: 0.94,
+                grid_opacity: 0.3,
+                subpixel_opacity: 0.25,
+                shadow: 0.0,
+                ghosting: 0.15,
+                ..base
+            },
+            "gba" => Self {
+                model: LcdModel::Color,
+                color_matrix: GBA_COLOR_MATRIX,
+                luminance: 0.93,
+                grid_width: 0.1,
+                grid_opacity: 0.3,
+                subpixel_opacity: 0.3,
+                shadow: 0.0,
+                backlight_bleed: 0.15,
+                ghosting: 0.0,
+                ..base
+            },
+            "dot-matrix" => Self {
+                model: LcdModel::DotMatrix,
+                grid_width: 0.2,
+                grid_opacity: 0.85,
+                shadow: 0.0,
+                backlight_bleed: 0.35,
+                ghosting: 0.0,
+                background_color: 0x101010FF,
+                ..base
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Compute the output dimensions `lcd_upscale` will produce
+pub fn get_output_dimensions(src_w: usize, src_h: usize, scale: usize) -> (usize, usize) {
+    let scale = scale.clamp(2, 32);
+    (src_w * scale, src_h * scale)
+}
+
+#[inline]
+fn to_rgb(color: u32) -> [f32; 3] {
+    let [r, g, b, _] = rgba_parts(color);
+    [r, g, b].map(|c| c as f32 / 255.0)
+}
+
+#[inline]
+fn luma(c: [f32; 3]) -> f32 {
+    0.299 * c[0] + 0.587 * c[1] + 0.114 * c[2]
+}
+
+#[inline]
+fn lerp(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|k| a[k] + (b[k] - a[k]) * t)
+}
+
+/// Overlap of output pixel `i` (of `scale` per cell) with `[lo, hi]` in cell units,
+/// as a fraction of the pixel
+fn overlap(i: usize, scale: usize, lo: f32, hi: f32) -> f32 {
+    let (a, b) = (i as f32 / scale as f32, (i + 1) as f32 / scale as f32);
+    ((b.min(hi) - a.max(lo)).max(0.0) * scale as f32).min(1.0)
+}
+
+/// Panel colour of every source pixel; transparent pixels show the blank screen
+fn panel_colors(input: &[u8], config: &LcdConfig, blank: [f32; 3]) -> Vec<[f32; 3]> {
+    let shades = config.shades.map(to_rgb);
+    input
+        .chunks_exact(4)
+        .map(|px| {
+            let rgb = [px[0], px[1], px[2]].map(|c| c as f32 / 255.0);
+            let lit = match config.model {
+                LcdModel::Dmg => shades[((luma(rgb) * 4.0) as usize).min(3)],
+                LcdModel::Color => {
+                    let linear = rgb.map(|c| c.powf(PANEL_GAMMA));
+                    config.color_matrix.map(|row| {
+                        let v = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+                        (v * config.luminance).clamp(0.0, 1.0).powf(1.0 / PANEL_GAMMA)
+                    })
+                }
+                LcdModel::DotMatrix => rgb,
+            };
+            lerp(blank, lit, px[3] as f32 / 255.0)
+        })
+        .collect()
+}
+
+/// Render `input` as an LCD panel; the output is opaque. `previous` is the
+/// output of the last frame for ghosting and is ignored unless it has the
+/// output's size.
+pub fn lcd_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &LcdConfig,
+    previous: Option<&[u8]>,
+) -> Vec<u8> {
+    let scale = scale.clamp(2, 32);
+    let (out_w, out_h) = get_output_dimensions(src_w, src_h, scale);
+    let mut output = vec![0u8; out_w * out_h * 4];
+    if out_w == 0 || out_h == 0 {
+        return output;
+    }
+    assert_eq!(input.len(), src_w * src_h * 4);
+
+    // The DMG is reflective: its blank screen is the lightest shade
+    let background = to_rgb(config.background_color);
+    let blank = match config.model {
+        LcdModel::Dmg => to_rgb(config.shades[3]),
+        _ => background,
+    };
+    let cells = panel_colors(input, config, blank);
+
+    // Backlight seen through the gaps: 3x3 average around each cell
+    let behind: Vec<[f32; 3]> = if config.backlight_bleed > 0.0 {
+        (0..src_w * src_h)
+            .map(|i| {
+                let (x, y) = (i % src_w, i / src_w);
+                let mut sum = [0.0; 3];
+                let mut n = 0.0;
+                for ny in y.saturating_sub(1)..(y + 2).min(src_h) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(src_w) {
+                        let c = cells[ny * src_w + nx];
+                        sum = [0, 1, 2].map(|k| sum[k] + c[k]);
+                        n += 1.0;
+                    }
+                }
+                lerp(background, sum.map(|s| s / n), config.backlight_bleed)
+            })
+            .collect()
+    } else {
+        vec![background; src_w * src_h]
+    };
+
+    // Per sub-position within a cell: gap coverage and stripe weights
+    let lit = 1.0 - config.grid_width.clamp(0.0, 1.0);
+    let gap: Vec<f32> = (0..scale).map(|i| overlap(i, scale, lit, 1.0)).collect();
+    let stripes: Vec<[f32; 3]> = (0..scale)
+        .map(|i| {
+            let w = [0, 1, 2].map(|k| overlap(i, scale, lit * k as f32 / 3.0, lit * (k + 1) as f32 / 3.0));
+            let total: f32 = w.iter().sum();
+            if total > 0.0 { w.map(|v| v / total) } else { [1.0; 3] }
+        })
+        .collect();
+
+    // Shadow cast a quarter cell down and right
+    let shadow_offset = (scale / 4).max(1);
+    let blank_luma = luma(blank);
+
+    let ghosting = config.ghosting.clamp(0.0, 1.0);
+    let previous = previous.filter(|p| ghosting > 0.0 && p.len() == output.len());
+
+    for oy in 0..out_h {
+        let (cy, fy) = (oy / scale, oy % scale);
+        for ox in 0..out_w {
+            let (cx, fx) = (ox / scale, ox % scale);
+            let idx = cy * src_w + cx;
+            let mut c = cells[idx];
+
+            if config.subpixel_opacity > 0.0 {
+                let w = stripes[fx];
+                c = [0, 1, 2].map(|k| c[k] * (1.0 - config.subpixel_opacity * (1.0 - w[k])));
+            }
+
+            let gap_coverage = 1.0 - (1.0 - gap[fx]) * (1.0 - gap[fy]);
+            c = lerp(c, behind[idx], config.grid_opacity * gap_coverage);
+
+            if config.shadow > 0.0 && blank_luma > 0.0 {
+                let caster = match (ox.checked_sub(shadow_offset), oy.checked_sub(shadow_offset)) {
+                    (Some(sx), Some(sy)) => cells[(sy / scale) * src_w + sx / scale],
+                    _ => blank,
+                };
+                let factor = (luma(caster) / blank_luma).min(1.0);
+                c = c.map(|v| v * (1.0 - config.shadow * (1.0 - factor)));
+            }
+
+            let out = (oy * out_w + ox) * 4;
+            if let Some(previous) = previous {
+                let before = [0, 1, 2].map(|k| previous[out + k] as f32 / 255.0);
+                c = lerp(c, before, ghosting);
+            }
+            for k in 0..3 {
+                output[out + k] = (c[k] * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+            }
+            output[out + 3] = 255;
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(w: usize) -> Vec<u8> {
+        (0..w).flat_map(|x| { let v = (x * 255 / (w - 1)) as u8; [v, v, v, 255] }).collect()
+    }
+
+    #[test]
+    fn test_dmg_maps_to_four_shades() {
+        let config = LcdConfig { grid_opacity: 0.0, shadow: 0.0, ..LcdConfig::default() };
+        let out = lcd_upscale(&gradient(8), 8, 1, 2, &config, None);
+        assert_eq!(out.len(), 16 * 2 * 4);
+
+        let shades = config.shades.map(|c| {
+            let [r, g, b, a] = rgba_parts(c);
+            [r, g, b, a]
+        });
+        assert!(out.chunks(4).all(|px| shades.iter().any(|s| s == px)));
+        assert_eq!(out[..4], shades[0]);
+        assert_eq!(out[out.len() - 4..], shades[3]);
+    }
+
+    #[test]
+    fn test_gaps_and_shadow_darken() {
+        // One black pixel on white: its gap and shadow are darker than its neighbours' cells
+        let mut img = vec![255u8; 3 * 3 * 4];
+        img[16..19].copy_from_slice(&[0, 0, 0]);
+        let config = LcdConfig::preset("dot-matrix").unwrap();
+        let out = lcd_upscale(&img, 3, 3, 8, &config, None);
+        let at = |x: usize, y: usize| out[(y * 24 + x) * 4] as i32;
+
+        assert!(at(1, 1) > 200);
+        assert!(at(7, 1) < at(1, 1), "gap must show the background");
+
+        let dmg = lcd_upscale(&img, 3, 3, 8, &LcdConfig::default(), None);
+        let at = |x: usize, y: usize| dmg[(y * 24 + x) * 4 + 1] as i32;
+        assert!(at(17, 17) < at(1, 17), "shadow falls down-right of the dark cell");
+    }
+
+    #[test]
+    fn test_color_correction_desaturates() {
+        let img = [255, 0, 0, 255];
+        let config = LcdConfig { grid_opacity: 0.0, ..LcdConfig::preset("gbc").unwrap() };
+        let out = lcd_upscale(&img, 1, 1, 3, &config, None);
+        // Pure red bleeds into green and blue and loses brightness
+        let px = &out[4 * 4..4 * 4 + 4];
+        assert!(px[0] < 255 && px[1] > 0 && px[2] > 0);
+        assert!(px[0] > px[1] && px[0] > px[2]);
+    }
+
+    #[test]
+    fn test_ghosting_blends_previous_frame() {
+        let config = LcdConfig { grid_opacity: 0.0, shadow: 0.0, ghosting: 0.5, ..LcdConfig::default() };
+        let (black, white) = ([0, 0, 0, 255], [255, 255, 255, 255]);
+        let dark = lcd_upscale(&black, 1, 1, 2, &config, None);
+        let light = lcd_upscale(&white, 1, 1, 2, &config, None);
+
+        // Half of the dark frame lingers on the light one
+        let ghost = lcd_upscale(&white, 1, 1, 2, &config, Some(&dark));
+        let mid = |a: u8, b: u8| ((a as f32 + b as f32) / 2.0).round() as i32;
+        for (k, &v) in ghost.iter().enumerate().filter(|(k, _)| k % 4 != 3) {
+            assert!((v as i32 - mid(dark[k], light[k])).abs() <= 1);
+        }
+
+        // No weight, or a previous frame of another size, leaves the frame alone
+        let off = LcdConfig { ghosting: 0.0, ..config };
+        assert_eq!(lcd_upscale(&white, 1, 1, 2, &off, Some(&dark)), lcd_upscale(&white, 1, 1, 2, &off, None));
+        assert_eq!(lcd_upscale(&white, 1, 1, 2, &config, Some(&dark[..8])), light);
+    }
+}
@@ -2,10 +2,30 @@
 //! 
 //! High-performance pixel art rendering engines for WebAssembly.
 
+use std::borrow::Cow;
+
 use wasm_bindgen::prelude::*;
 
+mod alpha;
+mod canvas;
+mod color_space;
 mod crt;
+mod depixel;
+mod dither;
 mod hex;
+mod hqx;
+mod lcd;
+mod mmpx;
+mod mosaic;
+mod nearest;
+mod palette;
+mod pixel_grid;
+mod scale2x;
+mod sharp_bilinear;
+mod sprite_fx;
+mod super_xbr;
+mod tess;
+mod vectorize;
 mod xbrz;
 
 // Optimization: Single shared output buffer for all renderers.
@@ -20,6 +40,9 @@ pub struct UpscaleResult {
     pub len: u32,
     pub width: u32,
     pub height: u32,
+    /// Colour space of the output pixels (0 = sRGB, 1 = Display-P3,
+    /// 2 = linear sRGB)
+    pub color_space: u32,
 }
 
 /// Get WASM memory for reading output buffers
@@ -35,37 +58,119 @@ pub fn get_memory() -> JsValue {
 /// Updates the shared buffer with new data and returns the WASM pointer result.
 /// This consolidates the unsafe static mut access into one location.
 #[inline(always)]
-fn update_buffer(output: Vec<u8>, width: u32, height: u32) -> UpscaleResult {
+fn update_buffer(output: Vec<u8>, width: u32, height: u32, format: PixelFormat) -> UpscaleResult {
     unsafe {
+        let buffer = &mut *std::ptr::addr_of_mut!(SHARED_BUFFER);
         // This drops the previous Vec (freeing its memory) and takes ownership of the new one.
-        SHARED_BUFFER = output;
+        *buffer = output_pixels(output, format);
         
         UpscaleResult {
-            ptr: SHARED_BUFFER.as_ptr() as u32,
-            len: SHARED_BUFFER.len() as u32,
+            ptr: buffer.as_ptr() as u32,
+            len: buffer.len() as u32,
             width,
             height,
+            color_space: format.space as u32,
+        }
+    }
+}
+
+/// Pixel format of the data passed to and returned from one call. Engines
+/// always work on straight alpha in the output colour space, config colours
+/// included; conversion happens on the way in (`input_pixels`) and on the
+/// way out (`output_pixels`).
+#[derive(Clone, Copy, Debug, Default)]
+struct PixelFormat {
+    alpha: alpha::AlphaMode,
+    /// Colour space of the input pixels
+    input_space: color_space::ColorSpace,
+    /// Colour space of the output pixels and the engines' working space
+    space: color_space::ColorSpace,
+}
+
+impl PixelFormat {
+    /// Format from the trailing arguments of the pixel exports: `alpha_mode`
+    /// (omitted or 0 = straight, 1 = premultiplied, for input and output
+    /// alike), `color_space` of the input (omitted or 0 = sRGB, 1 = Display-P3,
+    /// 2 = linear sRGB) and `output_space` (omitted = same as the input)
+    fn new(alpha_mode: Option<u32>, color_space: Option<u32>, output_space: Option<u32>) -> Self {
+        let input_space = color_space.map_or(color_space::ColorSpace::Srgb, self::color_space);
+        Self {
+            alpha: alpha_mode.map_or(alpha::AlphaMode::Straight, self::alpha_mode),
+            input_space,
+            space: output_space.map_or(input_space, self::color_space),
         }
     }
 }
 
+fn alpha_mode(mode: u32) -> alpha::AlphaMode {
+    match mode {
+        1 => alpha::AlphaMode::Premultiplied,
+        _ => alpha::AlphaMode::Straight,
+    }
+}
+
+/// Straight-alpha view of pixel data passed in by the caller, in the
+/// working colour space
+fn input_pixels(data: &[u8], format: PixelFormat) -> Cow<'_, [u8]> {
+    let mut data = alpha::to_straight(data, format.alpha);
+    if format.input_space != format.space {
+        color_space::convert_in_place(data.to_mut(), format.input_space, format.space);
+    }
+    data
+}
+
+/// `input_pixels` for an image export, or `None` unless `data` holds exactly
+/// `width` x `height` RGBA pixels
+fn image_pixels(data: &[u8], width: u32, height: u32, format: PixelFormat) -> Option<Cow<'_, [u8]>> {
+    let len = canvas::rgba_len(width as usize, height as usize)?;
+    (data.len() == len).then(|| input_pixels(data, format))
+}
+
+/// Result of an image export whose input did not match its dimensions
+fn empty_result(format: PixelFormat) -> UpscaleResult {
+    update_buffer(Vec::new(), 0, 0, format)
+}
+
+/// Engine output converted to the caller's format
+fn output_pixels(output: Vec<u8>, format: PixelFormat) -> Vec<u8> {
+    alpha::from_straight(output, format.alpha)
+}
+
+fn color_space(space: u32) -> color_space::ColorSpace {
+    match space {
+        1 => color_space::ColorSpace::DisplayP3,
+        2 => color_space::ColorSpace::LinearSrgb,
+        _ => color_space::ColorSpace::Srgb,
+    }
+}
+
 // ============================================================================
 // CRT Functions
 // ============================================================================
 
 /// CRT upscale with default config
 #[wasm_bindgen]
-pub fn crt_upscale(data: &[u8], width: u32, height: u32, scale: u32) -> UpscaleResult {
+pub fn crt_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
     crt_upscale_config(
         data, width, height, scale,
         0.015, 0.02,      // warp_x, warp_y
         -4.0, 0.5, 0.3,   // scan_hardness, scan_opacity, mask_opacity
-        true, true, true  // enable_warp, enable_scanlines, enable_mask
+        true, true, true, // enable_warp, enable_scanlines, enable_mask
+        alpha_mode, color_space, output_space,
     )
 }
 
 /// CRT upscale with full config
 #[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
 pub fn crt_upscale_config(
     data: &[u8],
     width: u32,
@@ -79,6 +184,95 @@ pub fn crt_upscale_config(
     enable_warp: bool,
     enable_scanlines: bool,
     enable_mask: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = crt::CrtConfig {
+        warp_x,
+        warp_y,
+        scan_hardness,
+        scan_opacity,
+        mask_opacity,
+        enable_warp,
+        enable_scanlines,
+        enable_mask,
+        ..Default::default()
+    };
+    
+    crt_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// CRT upscale to an explicit output size with pixel aspect correction.
+/// `output_width` / `output_height` of 0 derive that side from `scale`;
+/// the picture is letterboxed to fit the canvas.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn crt_upscale_sized(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    output_width: u32,
+    output_height: u32,
+    pixel_aspect: f32,
+    warp_x: f32,
+    warp_y: f32,
+    scan_hardness: f32,
+    scan_opacity: f32,
+    mask_opacity: f32,
+    enable_warp: bool,
+    enable_scanlines: bool,
+    enable_mask: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = crt::CrtConfig {
+        warp_x,
+        warp_y,
+        scan_hardness,
+        scan_opacity,
+        mask_opacity,
+        enable_warp,
+        enable_scanlines,
+        enable_mask,
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        pixel_aspect,
+        ..Default::default()
+    };
+    
+    crt_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// CRT upscale with explicit screen geometry.
+/// `curvature`: 0 = quadratic (uses warp_x / warp_y), 1 = spherical, 2 = cylindrical (Trinitron).
+/// Radius and viewing distance are in screen widths, tilt in radians.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn crt_upscale_geometry(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    curvature: u32,
+    curvature_radius: f32,
+    viewing_distance: f32,
+    overscan: f32,
+    tilt_x: f32,
+    tilt_y: f32,
+    warp_x: f32,
+    warp_y: f32,
+    scan_hardness: f32,
+    scan_opacity: f32,
+    mask_opacity: f32,
+    enable_warp: bool,
+    enable_scanlines: bool,
+    enable_mask: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
 ) -> UpscaleResult {
     let config = crt::CrtConfig {
         warp_x,
@@ -89,128 +283,2205 @@ pub fn crt_upscale_config(
         enable_warp,
         enable_scanlines,
         enable_mask,
+        curvature: crt_curvature(curvature),
+        curvature_radius,
+        viewing_distance,
+        overscan,
+        tilt_x,
+        tilt_y,
+        ..Default::default()
+    };
+    
+    crt_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Complete CRT configuration for `crt_upscale_options`. Shader settings,
+/// output sizing and screen geometry are all fields, so presets carry over
+/// whole; `new` starts from the `crt_upscale` defaults.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct CrtRenderOptions {
+    pub warp_x: f32,
+    pub warp_y: f32,
+    pub scan_hardness: f32,
+    pub scan_opacity: f32,
+    pub mask_opacity: f32,
+    pub enable_warp: bool,
+    pub enable_scanlines: bool,
+    pub enable_mask: bool,
+    /// Explicit output width in pixels (0 = derive from scale)
+    pub output_width: u32,
+    /// Explicit output height in pixels (0 = derive from scale)
+    pub output_height: u32,
+    /// Width / height ratio of a source pixel
+    pub pixel_aspect: f32,
+    /// 0 = quadratic (uses warp_x / warp_y), 1 = spherical, 2 = cylindrical
+    pub curvature: u32,
+    /// Tube radius in screen widths
+    pub curvature_radius: f32,
+    /// Eye distance from the screen face in screen widths
+    pub viewing_distance: f32,
+    pub overscan: f32,
+    /// Rotation about the vertical axis in radians
+    pub tilt_x: f32,
+    /// Rotation about the horizontal axis in radians
+    pub tilt_y: f32,
+}
+
+#[wasm_bindgen]
+impl CrtRenderOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CrtRenderOptions {
+        let config = crt::CrtConfig::default();
+        CrtRenderOptions {
+            warp_x: config.warp_x,
+            warp_y: config.warp_y,
+            scan_hardness: config.scan_hardness,
+            scan_opacity: config.scan_opacity,
+            mask_opacity: config.mask_opacity,
+            enable_warp: config.enable_warp,
+            enable_scanlines: config.enable_scanlines,
+            enable_mask: config.enable_mask,
+            output_width: 0,
+            output_height: 0,
+            pixel_aspect: config.pixel_aspect,
+            curvature: config.curvature as u32,
+            curvature_radius: config.curvature_radius,
+            viewing_distance: config.viewing_distance,
+            overscan: config.overscan,
+            tilt_x: config.tilt_x,
+            tilt_y: config.tilt_y,
+        }
+    }
+}
+
+impl Default for CrtRenderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrtRenderOptions {
+    fn config(&self) -> crt::CrtConfig {
+        crt::CrtConfig {
+            warp_x: self.warp_x,
+            warp_y: self.warp_y,
+            scan_hardness: self.scan_hardness,
+            scan_opacity: self.scan_opacity,
+            mask_opacity: self.mask_opacity,
+            enable_warp: self.enable_warp,
+            enable_scanlines: self.enable_scanlines,
+            enable_mask: self.enable_mask,
+            output_width: (self.output_width > 0).then_some(self.output_width as usize),
+            output_height: (self.output_height > 0).then_some(self.output_height as usize),
+            pixel_aspect: self.pixel_aspect,
+            curvature: crt_curvature(self.curvature),
+            curvature_radius: self.curvature_radius,
+            viewing_distance: self.viewing_distance,
+            overscan: self.overscan,
+            tilt_x: self.tilt_x,
+            tilt_y: self.tilt_y,
+            ..Default::default()
+        }
+    }
+}
+
+/// CRT upscale with every option from `CrtRenderOptions`
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn crt_upscale_options(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    options: &CrtRenderOptions,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    crt_render(data, width, height, scale, &options.config(), PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Output dimensions of `crt_upscale_options`
+#[wasm_bindgen]
+pub fn crt_options_get_dimensions(width: u32, height: u32, scale: u32, options: &CrtRenderOptions) -> Vec<u32> {
+    let (w, h) = crt::get_output_dimensions(width as usize, height as usize, scale as usize, &options.config());
+    vec![w as u32, h as u32]
+}
+
+fn crt_curvature(curvature: u32) -> crt::CrtCurvature {
+    match curvature {
+        1 => crt::CrtCurvature::Spherical,
+        2 => crt::CrtCurvature::Cylindrical,
+        _ => crt::CrtCurvature::Quadratic,
+    }
+}
+
+/// Get CRT output dimensions
+#[wasm_bindgen]
+pub fn crt_get_dimensions(
+    width: u32,
+    height: u32,
+    scale: u32,
+    output_width: u32,
+    output_height: u32,
+    pixel_aspect: f32,
+) -> Vec<u32> {
+    let config = crt::CrtConfig {
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        pixel_aspect,
+        ..Default::default()
     };
     
-    let output = crt::crt_upscale(data, width as usize, height as usize, scale as usize, &config);
-    update_buffer(output, width * scale, height * scale)
+    let (out_w, out_h) = crt::get_output_dimensions(
+        width as usize,
+        height as usize,
+        scale as usize,
+        &config
+    );
+    
+    vec![out_w as u32, out_h as u32]
+}
+
+fn crt_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &crt::CrtConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (out_width, out_height) = crt::get_output_dimensions(
+        width as usize,
+        height as usize,
+        scale as usize,
+        config
+    );
+    
+    let config = crt::CrtConfig { color_space: format.space, ..*config };
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = crt::crt_upscale(&data, width as usize, height as usize, scale as usize, &config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
 }
 
 // ============================================================================
-// HEX Functions  
+// Nearest / Sharp Bilinear Functions
 // ============================================================================
 
-/// HEX upscale with default config
+/// Nearest-neighbour upscale with default config (integer `scale`)
 #[wasm_bindgen]
-pub fn hex_upscale(data: &[u8], width: u32, height: u32, scale: u32) -> UpscaleResult {
-    hex_upscale_config(
-        data, width, height, scale,
-        0,           // orientation (flat-top)
-        false,       // draw_borders
-        0x282828FF,  // border_color
-        1,           // border_thickness
-        0x00000000   // background_color
-    )
+pub fn nearest_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    nearest_render(data, width, height, scale, &nearest::NearestConfig::default(), PixelFormat::new(alpha_mode, color_space, output_space))
 }
 
-/// HEX upscale with full config
+/// Nearest-neighbour upscale with full config.
+/// `output_width` / `output_height` of 0 derive that side from `scale`; the picture
+/// is letterboxed in `background_color`. `integer` snaps it to whole multiples.
 #[wasm_bindgen]
-pub fn hex_upscale_config(
+#[allow(clippy::too_many_arguments)]
+pub fn nearest_upscale_config(
     data: &[u8],
     width: u32,
     height: u32,
     scale: u32,
-    orientation: u32,
-    draw_borders: bool,
-    border_color: u32,
-    border_thickness: u32,
+    output_width: u32,
+    output_height: u32,
+    pixel_aspect: f32,
+    integer: bool,
     background_color: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
 ) -> UpscaleResult {
-    let config = hex::HexConfig {
-        orientation: if orientation == 0 {
-            hex::HexOrientation::FlatTop
-        } else {
-            hex::HexOrientation::PointyTop
-        },
-        draw_borders,
-        border_color,
-        border_thickness: border_thickness as usize,
+    let config = nearest::NearestConfig {
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        pixel_aspect,
+        integer,
         background_color,
     };
     
-    let (out_width, out_height) = hex::get_output_dimensions(
+    nearest_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get nearest-neighbour output dimensions
+#[wasm_bindgen]
+pub fn nearest_get_dimensions(
+    width: u32,
+    height: u32,
+    scale: u32,
+    output_width: u32,
+    output_height: u32,
+    pixel_aspect: f32,
+    integer: bool,
+) -> Vec<u32> {
+    let config = nearest::NearestConfig {
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        pixel_aspect,
+        integer,
+        ..Default::default()
+    };
+    
+    let (out_w, out_h) = nearest::get_output_dimensions(width as usize, height as usize, scale as usize, &config);
+    vec![out_w as u32, out_h as u32]
+}
+
+fn nearest_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &nearest::NearestConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (out_width, out_height) = nearest::get_output_dimensions(
         width as usize,
         height as usize,
         scale as usize,
-        &config.orientation
+        config
     );
     
-    let output = hex::hex_upscale(data, width as usize, height as usize, scale as usize, &config);
-    update_buffer(output, out_width as u32, out_height as u32)
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = nearest::nearest_upscale(&data, width as usize, height as usize, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
 }
 
-/// Get HEX output dimensions
+/// Sharp bilinear upscale with default config
 #[wasm_bindgen]
-pub fn hex_get_dimensions(width: u32, height: u32, scale: u32, orientation: u32) -> Vec<u32> {
-    let orient = if orientation == 0 {
-        hex::HexOrientation::FlatTop
-    } else {
-        hex::HexOrientation::PointyTop
+pub fn sharp_bilinear_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = sharp_bilinear::SharpBilinearConfig::default();
+    sharp_bilinear_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Sharp bilinear upscale with full config (same canvas arguments as `nearest_upscale_config`)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn sharp_bilinear_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    output_width: u32,
+    output_height: u32,
+    pixel_aspect: f32,
+    background_color: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = sharp_bilinear::SharpBilinearConfig {
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        pixel_aspect,
+        background_color,
     };
     
-    let (out_w, out_h) = hex::get_output_dimensions(
+    sharp_bilinear_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get sharp bilinear output dimensions
+#[wasm_bindgen]
+pub fn sharp_bilinear_get_dimensions(
+    width: u32,
+    height: u32,
+    scale: u32,
+    output_width: u32,
+    output_height: u32,
+    pixel_aspect: f32,
+) -> Vec<u32> {
+    let config = sharp_bilinear::SharpBilinearConfig {
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        pixel_aspect,
+        ..Default::default()
+    };
+    
+    let (out_w, out_h) = sharp_bilinear::get_output_dimensions(width as usize, height as usize, scale as usize, &config);
+    vec![out_w as u32, out_h as u32]
+}
+
+fn sharp_bilinear_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &sharp_bilinear::SharpBilinearConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (out_width, out_height) = sharp_bilinear::get_output_dimensions(
         width as usize,
         height as usize,
         scale as usize,
-        &orient
+        config
     );
     
-    vec![out_w as u32, out_h as u32]
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = sharp_bilinear::sharp_bilinear_upscale(&data, width as usize, height as usize, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
 }
 
 // ============================================================================
-// XBRZ Functions
+// HEX Functions  
 // ============================================================================
 
-/// XBRZ upscale with default config
+/// HEX upscale with default config
 #[wasm_bindgen]
-pub fn xbrz_upscale(data: &[u8], width: u32, height: u32, scale: u32) -> UpscaleResult {
-    xbrz_upscale_config(
+pub fn hex_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    hex_upscale_config(
         data, width, height, scale,
-        30.0,  // equal_color_tolerance
-        4.0,   // center_direction_bias
-        3.6,   // dominant_direction_threshold
-        2.2    // steep_direction_threshold
+        0,           // orientation (flat-top)
+        false,       // draw_borders
+        0x282828FF,  // border_color
+        1,           // border_thickness
+        0x00000000,  // background_color
+        alpha_mode, color_space, output_space,
     )
 }
 
-/// XBRZ upscale with full config
+/// HEX upscale with full config
 #[wasm_bindgen]
-pub fn xbrz_upscale_config(
+#[allow(clippy::too_many_arguments)]
+pub fn hex_upscale_config(
     data: &[u8],
     width: u32,
     height: u32,
     scale: u32,
-    equal_color_tolerance: f64,
-    center_direction_bias: f64,
-    dominant_direction_threshold: f64,
-    steep_direction_threshold: f64,
+    orientation: u32,
+    draw_borders: bool,
+    border_color: u32,
+    border_thickness: u32,
+    background_color: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
 ) -> UpscaleResult {
-    let clamped_scale = scale.clamp(1, 6) as usize;
-    let output = xbrz::xbrz_upscale(
-        data, 
-        width as usize, 
-        height as usize, 
-        clamped_scale,
+    let config = hex::HexConfig {
+        orientation: hex_orientation(orientation),
+        draw_borders,
+        border_color,
+        border_thickness: border_thickness as usize,
+        background_color,
+        ..Default::default()
+    };
+    
+    hex_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Complete HEX configuration for `hex_upscale_options`. Anti-aliasing,
+/// styling, compositing and resampling are all fields, so they combine
+/// freely; `new` starts from the `hex_upscale` defaults.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct HexRenderOptions {
+    /// 0 = flat-top, 1 = pointy-top
+    pub orientation: u32,
+    pub draw_borders: bool,
+    pub border_color: u32,
+    pub border_thickness: u32,
+    pub background_color: u32,
+    /// Subsamples per axis for anti-aliased cell edges (1 = off, max 8)
+    pub supersample: u32,
+    /// Gap between cells in pixels
+    pub gap: f32,
+    /// Width of the bevel band in pixels (0 = off)
+    pub bevel_width: f32,
+    /// Bevel strength (-1..1, negative for a sunken emboss)
+    pub bevel_strength: f32,
+    /// Radial darkening towards the cell edge (0..1)
+    pub gradient: f32,
+    pub shadow_offset_x: f32,
+    pub shadow_offset_y: f32,
+    /// Drop shadow colour; alpha 0 disables the shadow
+    pub shadow_color: u32,
+    /// Width of the soft shadow edge in pixels
+    pub shadow_softness: f32,
+    /// Composite cells and borders over the background colour
+    pub composite: bool,
+    /// Leave fully transparent cells without borders
+    pub skip_transparent_borders: bool,
+    /// Resample the input onto a grid this many cells wide (0 = one cell per pixel)
+    pub columns: u32,
+    /// Resampling filter: 0 = area average, 1 = centre sample
+    pub sampling: u32,
+}
+
+#[wasm_bindgen]
+impl HexRenderOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> HexRenderOptions {
+        let config = hex::HexConfig::default();
+        HexRenderOptions {
+            orientation: config.orientation as u32,
+            draw_borders: config.draw_borders,
+            border_color: config.border_color,
+            border_thickness: config.border_thickness as u32,
+            background_color: config.background_color,
+            supersample: config.supersample as u32,
+            gap: config.style.gap,
+            bevel_width: config.style.bevel_width,
+            bevel_strength: config.style.bevel_strength,
+            gradient: config.style.gradient,
+            shadow_offset_x: config.style.shadow_offset_x,
+            shadow_offset_y: config.style.shadow_offset_y,
+            shadow_color: config.style.shadow_color,
+            shadow_softness: config.style.shadow_softness,
+            composite: config.alpha.composite,
+            skip_transparent_borders: config.alpha.skip_transparent_borders,
+            columns: 0,
+            sampling: 0,
+        }
+    }
+}
+
+impl Default for HexRenderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HexRenderOptions {
+    fn config(&self) -> hex::HexConfig {
+        hex::HexConfig {
+            orientation: hex_orientation(self.orientation),
+            draw_borders: self.draw_borders,
+            border_color: self.border_color,
+            border_thickness: self.border_thickness as usize,
+            background_color: self.background_color,
+            supersample: self.supersample as usize,
+            style: hex::HexStyle {
+                gap: self.gap,
+                bevel_width: self.bevel_width,
+                bevel_strength: self.bevel_strength,
+                gradient: self.gradient,
+                shadow_offset_x: self.shadow_offset_x,
+                shadow_offset_y: self.shadow_offset_y,
+                shadow_color: self.shadow_color,
+                shadow_softness: self.shadow_softness,
+            },
+            alpha: hex::HexAlpha {
+                composite: self.composite,
+                skip_transparent_borders: self.skip_transparent_borders,
+            },
+        }
+    }
+
+    fn sampling(&self) -> hex::HexSampling {
+        if self.sampling == 0 {
+            hex::HexSampling::Average
+        } else {
+            hex::HexSampling::Center
+        }
+    }
+}
+
+/// HEX upscale with every option from `HexRenderOptions`
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn hex_upscale_options(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    options: &HexRenderOptions,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = options.config();
+    if options.columns == 0 {
+        return hex_render(data, width, height, scale, &config, format);
+    }
+    
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let (cells, cols, rows) = hex::resample_grid(
+        &data,
+        width as usize,
+        height as usize,
+        options.columns as usize,
+        config.orientation,
+        options.sampling(),
+    );
+    hex_render_straight(&cells, cols as u32, rows as u32, scale, &config, format)
+}
+
+/// Output dimensions of `hex_upscale_options`
+#[wasm_bindgen]
+pub fn hex_options_get_dimensions(width: u32, height: u32, scale: u32, options: &HexRenderOptions) -> Vec<u32> {
+    let orientation = hex_orientation(options.orientation);
+    let (cols, rows) = if options.columns == 0 {
+        (width as usize, height as usize)
+    } else {
+        hex::resample_dimensions(width as usize, height as usize, options.columns as usize, orientation)
+    };
+    let (w, h) = hex::get_output_dimensions(cols, rows, scale as usize, &orientation);
+    vec![w as u32, h as u32]
+}
+
+fn hex_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &hex::HexConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    hex_render_straight(&data, width, height, scale, config, format)
+}
+
+fn hex_render_straight(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &hex::HexConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (out_width, out_height) = hex::get_output_dimensions(
+        width as usize,
+        height as usize,
+        scale as usize,
+        &config.orientation
+    );
+    
+    let output = hex::hex_upscale(data, width as usize, height as usize, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+/// HEX rendering as an SVG document (same size and layout as `hex_upscale_config`)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn hex_upscale_svg(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    orientation: u32,
+    draw_borders: bool,
+    border_color: u32,
+    border_thickness: u32,
+    background_color: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> String {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = hex::HexConfig {
+        orientation: hex_orientation(orientation),
+        draw_borders,
+        border_color,
+        border_thickness: border_thickness as usize,
+        background_color,
+        ..Default::default()
+    };
+    
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return String::new();
+    };
+    hex::hex_svg(&data, width as usize, height as usize, scale as usize, &config)
+}
+
+/// Get HEX output dimensions
+#[wasm_bindgen]
+pub fn hex_get_dimensions(width: u32, height: u32, scale: u32, orientation: u32) -> Vec<u32> {
+    let (out_w, out_h) = hex::get_output_dimensions(
+        width as usize,
+        height as usize,
+        scale as usize,
+        &hex_orientation(orientation)
+    );
+    
+    vec![out_w as u32, out_h as u32]
+}
+
+fn hex_orientation(orientation: u32) -> hex::HexOrientation {
+    if orientation == 0 {
+        hex::HexOrientation::FlatTop
+    } else {
+        hex::HexOrientation::PointyTop
+    }
+}
+
+/// Grid cell `[col, row]` under output position (x, y); may be outside the source
+#[wasm_bindgen]
+pub fn hex_pixel_to_cell(x: f32, y: f32, scale: u32, orientation: u32) -> Vec<i32> {
+    let geometry = hex::HexGeometry::new(scale, hex_orientation(orientation));
+    let (col, row) = geometry.pixel_to_cell(x, y);
+    vec![col, row]
+}
+
+/// Centre `[x, y]` of grid cell (col, row) in output pixels
+#[wasm_bindgen]
+pub fn hex_cell_center(col: i32, row: i32, scale: u32, orientation: u32) -> Vec<f32> {
+    let geometry = hex::HexGeometry::new(scale, hex_orientation(orientation));
+    let (x, y) = geometry.cell_center(col, row);
+    vec![x, y]
+}
+
+/// Corner polygon of grid cell (col, row) as `[x0, y0, ..., x5, y5]`
+#[wasm_bindgen]
+pub fn hex_cell_corners(col: i32, row: i32, scale: u32, orientation: u32) -> Vec<f32> {
+    let geometry = hex::HexGeometry::new(scale, hex_orientation(orientation));
+    geometry.cell_corners(col, row).iter().flat_map(|&(x, y)| [x, y]).collect()
+}
+
+/// The six neighbours of grid cell (col, row) as `[col0, row0, ..., col5, row5]`
+#[wasm_bindgen]
+pub fn hex_cell_neighbours(col: i32, row: i32, orientation: u32) -> Vec<i32> {
+    let geometry = hex::HexGeometry::new(2, hex_orientation(orientation));
+    geometry.neighbours(col, row).iter().flat_map(|&(c, r)| [c, r]).collect()
+}
+
+/// SVG outlines of the hex grid for a `width` x `height` source
+#[wasm_bindgen]
+pub fn hex_grid_svg(width: u32, height: u32, scale: u32, orientation: u32) -> String {
+    hex::grid_svg(width as usize, height as usize, scale as usize, hex_orientation(orientation))
+}
+
+/// JSON description of the hex grid for a `width` x `height` source
+#[wasm_bindgen]
+pub fn hex_grid_json(width: u32, height: u32, scale: u32, orientation: u32) -> String {
+    hex::grid_json(width as usize, height as usize, scale as usize, hex_orientation(orientation))
+}
+
+// ============================================================================
+// Tessellation Functions
+// ============================================================================
+//
+// Triangle, brick, diamond and Voronoi grids. Border and background arguments
+// match `hex_upscale_config`; `supersample` anti-aliases cell edges (1 = off).
+
+/// Triangle upscale with default config
+#[wasm_bindgen]
+pub fn triangle_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::triangle::TriangleConfig::default();
+    triangle_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Triangle upscale with full config (mode: 0 = triangle pair per pixel, 1 = resample
+/// with triangles of side `cell_size`, 0 = twice the scale)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn triangle_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    mode: u32,
+    cell_size: u32,
+    draw_borders: bool,
+    border_color: u32,
+    border_thickness: u32,
+    background_color: u32,
+    supersample: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::triangle::TriangleConfig {
+        mode: triangle_mode(mode),
+        cell_size: cell_size as usize,
+        draw_borders,
+        border_color,
+        border_thickness: border_thickness as usize,
+        background_color,
+        supersample: supersample as usize,
+    };
+    triangle_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get triangle output dimensions
+#[wasm_bindgen]
+pub fn triangle_get_dimensions(width: u32, height: u32, scale: u32, mode: u32) -> Vec<u32> {
+    let config = tess::triangle::TriangleConfig {
+        mode: triangle_mode(mode),
+        ..Default::default()
+    };
+    let (w, h) = tess::triangle::get_output_dimensions(width as usize, height as usize, scale as usize, &config);
+    vec![w as u32, h as u32]
+}
+
+fn triangle_mode(mode: u32) -> tess::triangle::TriangleMode {
+    if mode == 0 {
+        tess::triangle::TriangleMode::Pair
+    } else {
+        tess::triangle::TriangleMode::Resample
+    }
+}
+
+fn triangle_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &tess::triangle::TriangleConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (w, h) = (width as usize, height as usize);
+    let (out_width, out_height) = tess::triangle::get_output_dimensions(w, h, scale as usize, config);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = tess::triangle::triangle_upscale(&data, w, h, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+/// Brick upscale with default config
+#[wasm_bindgen]
+pub fn brick_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::brick::BrickConfig::default();
+    brick_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Brick upscale with full config (`offset`: shift of odd rows in cell widths)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn brick_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    offset: f32,
+    draw_borders: bool,
+    border_color: u32,
+    border_thickness: u32,
+    background_color: u32,
+    supersample: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::brick::BrickConfig {
+        offset,
+        draw_borders,
+        border_color,
+        border_thickness: border_thickness as usize,
+        background_color,
+        supersample: supersample as usize,
+    };
+    brick_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get brick output dimensions
+#[wasm_bindgen]
+pub fn brick_get_dimensions(width: u32, height: u32, scale: u32, offset: f32) -> Vec<u32> {
+    let config = tess::brick::BrickConfig { offset, ..Default::default() };
+    let (w, h) = tess::brick::get_output_dimensions(width as usize, height as usize, scale as usize, &config);
+    vec![w as u32, h as u32]
+}
+
+fn brick_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &tess::brick::BrickConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (w, h) = (width as usize, height as usize);
+    let (out_width, out_height) = tess::brick::get_output_dimensions(w, h, scale as usize, config);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = tess::brick::brick_upscale(&data, w, h, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+/// Diamond (isometric) upscale with default config
+#[wasm_bindgen]
+pub fn diamond_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::diamond::DiamondConfig::default();
+    diamond_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Diamond upscale with full config (`aspect`: rhombus height / width, 0.5 = isometric)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn diamond_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    aspect: f32,
+    draw_borders: bool,
+    border_color: u32,
+    border_thickness: u32,
+    background_color: u32,
+    supersample: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::diamond::DiamondConfig {
+        aspect,
+        draw_borders,
+        border_color,
+        border_thickness: border_thickness as usize,
+        background_color,
+        supersample: supersample as usize,
+    };
+    diamond_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get diamond output dimensions
+#[wasm_bindgen]
+pub fn diamond_get_dimensions(width: u32, height: u32, scale: u32, aspect: f32) -> Vec<u32> {
+    let config = tess::diamond::DiamondConfig { aspect, ..Default::default() };
+    let (w, h) = tess::diamond::get_output_dimensions(width as usize, height as usize, scale as usize, &config);
+    vec![w as u32, h as u32]
+}
+
+fn diamond_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &tess::diamond::DiamondConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (w, h) = (width as usize, height as usize);
+    let (out_width, out_height) = tess::diamond::get_output_dimensions(w, h, scale as usize, config);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = tess::diamond::diamond_upscale(&data, w, h, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+/// Voronoi upscale with default config
+#[wasm_bindgen]
+pub fn voronoi_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::voronoi::VoronoiConfig::default();
+    voronoi_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Voronoi upscale with full config (`jitter` 0..1 moves the cell sites, `seed` picks the pattern)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn voronoi_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    jitter: f32,
+    seed: u32,
+    draw_borders: bool,
+    border_color: u32,
+    border_thickness: u32,
+    background_color: u32,
+    supersample: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = tess::voronoi::VoronoiConfig {
+        jitter,
+        seed,
+        draw_borders,
+        border_color,
+        border_thickness: border_thickness as usize,
+        background_color,
+        supersample: supersample as usize,
+    };
+    voronoi_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get Voronoi output dimensions
+#[wasm_bindgen]
+pub fn voronoi_get_dimensions(width: u32, height: u32, scale: u32) -> Vec<u32> {
+    let config = tess::voronoi::VoronoiConfig::default();
+    let (w, h) = tess::voronoi::get_output_dimensions(width as usize, height as usize, scale as usize, &config);
+    vec![w as u32, h as u32]
+}
+
+fn voronoi_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &tess::voronoi::VoronoiConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (w, h) = (width as usize, height as usize);
+    let (out_width, out_height) = tess::voronoi::get_output_dimensions(w, h, scale as usize, config);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = tess::voronoi::voronoi_upscale(&data, w, h, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+// ============================================================================
+// XBRZ Functions
+// ============================================================================
+
+/// XBRZ upscale with default config
+#[wasm_bindgen]
+pub fn xbrz_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    xbrz_upscale_config(
+        data, width, height, scale,
+        30.0,  // equal_color_tolerance
+        4.0,   // center_direction_bias
+        3.6,   // dominant_direction_threshold
+        2.2,   // steep_direction_threshold
+        alpha_mode, color_space, output_space,
+    )
+}
+
+/// XBRZ upscale with full config
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn xbrz_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    equal_color_tolerance: f64,
+    center_direction_bias: f64,
+    dominant_direction_threshold: f64,
+    steep_direction_threshold: f64,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = xbrz::config::ScalerConfig {
         equal_color_tolerance,
         center_direction_bias,
         dominant_direction_threshold,
         steep_direction_threshold,
+        ..Default::default()
+    };
+    
+    xbrz_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+fn xbrz_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &xbrz::config::ScalerConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let clamped_scale = scale.clamp(1, 6) as usize;
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = xbrz::xbrz_upscale(
+        &data, 
+        width as usize, 
+        height as usize, 
+        clamped_scale,
+        config.equal_color_tolerance,
+        config.center_direction_bias,
+        config.dominant_direction_threshold,
+        config.steep_direction_threshold,
+        format.space,
+    );
+    
+    let out_width = width * clamped_scale as u32;
+    let out_height = height * clamped_scale as u32;
+    
+    update_buffer(output, out_width, out_height, format)
+}
+
+// ============================================================================
+// SCALE2X Functions
+// ============================================================================
+
+/// Scale2x family upscale with default config (scale 2, 3 or 4; 4 = Scale2x twice)
+#[wasm_bindgen]
+pub fn scale2x_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    scale2x_upscale_config(data, width, height, scale, false, alpha_mode, color_space, output_space)
+}
+
+/// Scale2x family upscale with full config
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn scale2x_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    transparent_edges: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = scale2x::Scale2xConfig { transparent_edges };
+    let (w, h) = (width as usize, height as usize);
+    
+    let (out_width, out_height) = scale2x::get_output_dimensions(w, h, scale as usize);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = scale2x::scale2x_upscale(&data, w, h, scale as usize, &config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+// ============================================================================
+// HQX Functions
+// ============================================================================
+
+/// HQ2x / HQ3x / HQ4x upscale with default thresholds (scale 2, 3 or 4)
+#[wasm_bindgen]
+pub fn hqx_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = hqx::HqxConfig::default();
+    hqx_upscale_config(
+        data, width, height, scale,
+        config.y_threshold, config.u_threshold, config.v_threshold, config.alpha_threshold as u32,
+        alpha_mode, color_space, output_space,
+    )
+}
+
+/// HQx upscale with custom YUV and alpha difference thresholds
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn hqx_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    y_threshold: f32,
+    u_threshold: f32,
+    v_threshold: f32,
+    alpha_threshold: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = hqx::HqxConfig {
+        y_threshold,
+        u_threshold,
+        v_threshold,
+        alpha_threshold: alpha_threshold.min(255) as u8,
+    };
+    let (w, h) = (width as usize, height as usize);
+    
+    let (out_width, out_height) = hqx::get_output_dimensions(w, h, scale as usize);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = hqx::hqx_upscale(&data, w, h, scale as usize, &config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+// ============================================================================
+// MMPX / Super-xBR Functions
+// ============================================================================
+
+/// MMPX upscale with default config (scale 2 or 4; 4 = MMPX twice)
+#[wasm_bindgen]
+pub fn mmpx_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    mmpx_upscale_config(data, width, height, scale, false, alpha_mode, color_space, output_space)
+}
+
+/// MMPX upscale with full config
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn mmpx_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    transparent_edges: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = mmpx::MmpxConfig { transparent_edges };
+    let (w, h) = (width as usize, height as usize);
+    
+    let (out_width, out_height) = mmpx::get_output_dimensions(w, h, scale as usize);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = mmpx::mmpx_upscale(&data, w, h, scale as usize, &config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+/// Super-xBR upscale with default config (scale 2, 4 or 8)
+#[wasm_bindgen]
+pub fn super_xbr_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = super_xbr::SuperXbrConfig::default();
+    super_xbr_upscale_config(
+        data, width, height, scale,
+        config.edge_sharpness, config.anti_ringing, alpha_mode, color_space, output_space,
+    )
+}
+
+/// Super-xBR upscale with full config
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn super_xbr_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    edge_sharpness: f32,
+    anti_ringing: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = super_xbr::SuperXbrConfig { edge_sharpness, anti_ringing };
+    let (w, h) = (width as usize, height as usize);
+    
+    let (out_width, out_height) = super_xbr::get_output_dimensions(w, h, scale as usize);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = super_xbr::super_xbr_upscale(&data, w, h, scale as usize, &config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+/// Get Super-xBR output dimensions (the scale is rounded up to a power of two)
+#[wasm_bindgen]
+pub fn super_xbr_get_dimensions(width: u32, height: u32, scale: u32) -> Vec<u32> {
+    let (w, h) = super_xbr::get_output_dimensions(width as usize, height as usize, scale as usize);
+    vec![w as u32, h as u32]
+}
+
+// ============================================================================
+// Depixel Functions
+// ============================================================================
+
+/// Depixelizing upscale with default config (any scale up to 64)
+#[wasm_bindgen]
+pub fn depixel_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = depixel::DepixelConfig::default();
+    depixel_upscale_config(
+        data, width, height, scale, 0, 0,
+        config.similarity_threshold, config.smoothness, config.edge_softness,
+        alpha_mode, color_space, output_space,
+    )
+}
+
+/// Depixelizing upscale with full config (output sizes of 0 are derived from `scale`)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn depixel_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    output_width: u32,
+    output_height: u32,
+    similarity_threshold: f32,
+    smoothness: f32,
+    edge_softness: f32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = depixel::DepixelConfig {
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        similarity_threshold,
+        smoothness,
+        edge_softness,
+    };
+    let (w, h) = (width as usize, height as usize);
+    
+    let (out_width, out_height) = depixel::get_output_dimensions(w, h, scale as usize, &config);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = depixel::depixel_upscale(&data, w, h, scale as usize, &config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+/// Get depixel output dimensions
+#[wasm_bindgen]
+pub fn depixel_get_dimensions(
+    width: u32,
+    height: u32,
+    scale: u32,
+    output_width: u32,
+    output_height: u32,
+) -> Vec<u32> {
+    let config = depixel::DepixelConfig {
+        output_width: (output_width > 0).then_some(output_width as usize),
+        output_height: (output_height > 0).then_some(output_height as usize),
+        ..depixel::DepixelConfig::default()
+    };
+    let (w, h) = depixel::get_output_dimensions(width as usize, height as usize, scale as usize, &config);
+    vec![w as u32, h as u32]
+}
+
+// ============================================================================
+// Vectorisation Functions
+// ============================================================================
+
+fn vector_mode(mode: u32) -> vectorize::VectorMode {
+    if mode == 1 {
+        vectorize::VectorMode::Smooth
+    } else {
+        vectorize::VectorMode::Rectilinear
+    }
+}
+
+/// Trace the image into an SVG document (mode 0 = rectilinear, 1 = smooth)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn vectorize_svg(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    mode: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> String {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = vectorize::VectorizeConfig {
+        mode: vector_mode(mode),
+        ..Default::default()
+    };
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return String::new();
+    };
+    vectorize::vectorize_svg(&data, width as usize, height as usize, scale as usize, &config)
+}
+
+/// Trace the image into an SVG document with a background and the xBRZ
+/// colour tolerance used by the smooth mode
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn vectorize_svg_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    mode: u32,
+    background_color: u32,
+    equal_color_tolerance: f64,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> String {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = vectorize::VectorizeConfig {
+        mode: vector_mode(mode),
+        background_color,
+        xbrz: xbrz::config::ScalerConfig {
+            equal_color_tolerance,
+            ..Default::default()
+        },
+    };
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return String::new();
+    };
+    vectorize::vectorize_svg(&data, width as usize, height as usize, scale as usize, &config)
+}
+
+// ============================================================================
+// Pixel Grid Functions
+// ============================================================================
+
+/// Detect the native pixel grid of nearest-upscaled art, in the input's own
+/// colour space: `[cell_width, cell_height, offset_x, offset_y, columns, rows]`
+#[wasm_bindgen]
+pub fn detect_pixel_grid(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+) -> Vec<u32> {
+    let format = PixelFormat::new(alpha_mode, color_space, None);
+    let (w, h) = (width as usize, height as usize);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return Vec::new();
+    };
+    let grid = pixel_grid::detect_pixel_grid(&data, w, h);
+    let (columns, rows) = grid.dimensions(w, h);
+    [grid.cell_width, grid.cell_height, grid.offset_x, grid.offset_y, columns, rows]
+        .map(|v| v as u32)
+        .to_vec()
+}
+
+/// Reduce every grid block to its dominant colour (cell_width or
+/// cell_height 0 = detect the grid first)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn downscale_to_grid(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    cell_width: u32,
+    cell_height: u32,
+    offset_x: u32,
+    offset_y: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let (w, h) = (width as usize, height as usize);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let grid = if cell_width == 0 || cell_height == 0 {
+        pixel_grid::detect_pixel_grid(&data, w, h)
+    } else {
+        pixel_grid::PixelGrid {
+            cell_width: cell_width as usize,
+            cell_height: cell_height as usize,
+            offset_x: offset_x as usize,
+            offset_y: offset_y as usize,
+        }
+    };
+    
+    let (output, out_width, out_height) = pixel_grid::downscale_to_grid(&data, w, h, &grid);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+// ============================================================================
+// LCD Functions
+// ============================================================================
+
+/// Game Boy (DMG) LCD upscale with the default config. `previous` is the
+/// last output frame, which ghosts into this one when it has the same size.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn lcd_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    previous: Option<Vec<u8>>,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    lcd_render(data, width, height, scale, &lcd::LcdConfig::default(), previous.as_deref(), format)
+}
+
+/// LCD upscale with full config.
+/// `model`: 0 = Game Boy (DMG), 1 = Game Boy Color / Advance, 2 = dot matrix.
+/// `shades` (4 DMG colours, darkest first) and `color_matrix` (9 values, row
+/// major) fall back to the defaults when empty; colours are `0xRRGGBBAA`.
+/// `ghosting` is the share of the `previous` output frame kept in this one.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn lcd_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    model: u32,
+    shades: &[u32],
+    color_matrix: &[f32],
+    luminance: f32,
+    grid_width: f32,
+    grid_opacity: f32,
+    subpixel_opacity: f32,
+    shadow: f32,
+    backlight_bleed: f32,
+    ghosting: f32,
+    background_color: u32,
+    previous: Option<Vec<u8>>,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let defaults = lcd::LcdConfig::default();
+    let config = lcd::LcdConfig {
+        model: match model {
+            1 => lcd::LcdModel::Color,
+            2 => lcd::LcdModel::DotMatrix,
+            _ => lcd::LcdModel::Dmg,
+        },
+        shades: shades.try_into().unwrap_or(defaults.shades),
+        color_matrix: match color_matrix {
+            [a, b, c, d, e, f, g, h, i] => [[*a, *b, *c], [*d, *e, *f], [*g, *h, *i]],
+            _ => defaults.color_matrix,
+        },
+        luminance,
+        grid_width,
+        grid_opacity,
+        subpixel_opacity,
+        shadow,
+        backlight_bleed,
+        ghosting,
+        background_color,
+    };
+    
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    lcd_render(data, width, height, scale, &config, previous.as_deref(), format)
+}
+
+/// Get LCD output dimensions
+#[wasm_bindgen]
+pub fn lcd_get_dimensions(width: u32, height: u32, scale: u32) -> Vec<u32> {
+    let (out_w, out_h) = lcd::get_output_dimensions(width as usize, height as usize, scale as usize);
+    vec![out_w as u32, out_h as u32]
+}
+
+fn lcd_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &lcd::LcdConfig,
+    previous: Option<&[u8]>,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (w, h) = (width as usize, height as usize);
+    
+    let (out_width, out_height) = lcd::get_output_dimensions(w, h, scale as usize);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = lcd::lcd_upscale(&data, w, h, scale as usize, config, previous);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+// ============================================================================
+// Mosaic Functions
+// ============================================================================
+
+fn mosaic_style(style: u32) -> mosaic::MosaicStyle {
+    match style {
+        1 => mosaic::MosaicStyle::Brick,
+        2 => mosaic::MosaicStyle::CrossStitch,
+        3 => mosaic::MosaicStyle::Bead,
+        _ => mosaic::MosaicStyle::Led,
+    }
+}
+
+/// LED mosaic upscale with default config
+#[wasm_bindgen]
+pub fn mosaic_upscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    mosaic_render(data, width, height, scale, &mosaic::MosaicConfig::default(), PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Mosaic upscale with full config
+/// (style: 0 = LED, 1 = brick studs, 2 = cross-stitch, 3 = perler beads)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn mosaic_upscale_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    style: u32,
+    background_color: u32,
+    shading: f32,
+    glow: f32,
+    supersample: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = mosaic::MosaicConfig {
+        style: mosaic_style(style),
+        background_color,
+        shading,
+        glow,
+        supersample: supersample as usize,
+    };
+    mosaic_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get mosaic output dimensions (LED adds a half-cell margin for the glow)
+#[wasm_bindgen]
+pub fn mosaic_get_dimensions(width: u32, height: u32, scale: u32, style: u32) -> Vec<u32> {
+    let (w, h) = mosaic::get_output_dimensions(width as usize, height as usize, scale as usize, &mosaic_style(style));
+    vec![w as u32, h as u32]
+}
+
+/// Pieces needed per colour, most used first, in the input's colour space:
+/// `[rgba, count, rgba, count, ...]`
+#[wasm_bindgen]
+pub fn mosaic_bill_of_materials(
+    data: &[u8],
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+) -> Vec<u32> {
+    let format = PixelFormat::new(alpha_mode, color_space, None);
+    let data = input_pixels(data, format);
+    mosaic::bill_of_materials(&data)
+        .into_iter()
+        .flat_map(|p| [p.rgba, p.count as u32])
+        .collect()
+}
+
+fn mosaic_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    config: &mosaic::MosaicConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (w, h) = (width as usize, height as usize);
+    let (out_width, out_height) = mosaic::get_output_dimensions(w, h, scale as usize, &config.style);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = mosaic::mosaic_upscale(&data, w, h, scale as usize, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+// ============================================================================
+// Palette Functions
+// ============================================================================
+//
+// Palettes cross the boundary as `0xRRGGBBAA` words, the same encoding the
+// border and background colour arguments use.
+
+fn quantize_method(method: u32) -> palette::QuantizeMethod {
+    match method {
+        1 => palette::QuantizeMethod::KMeans,
+        _ => palette::QuantizeMethod::MedianCut,
+    }
+}
+
+/// Exact palette, most used colours first, in the input's colour space:
+/// `[rgba, count, rgba, count, ...]`
+#[wasm_bindgen]
+pub fn palette_extract(
+    data: &[u8],
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+) -> Vec<u32> {
+    let format = PixelFormat::new(alpha_mode, color_space, None);
+    let data = input_pixels(data, format);
+    palette::extract_palette(&data)
+        .into_iter()
+        .flat_map(|p| [p.rgba, p.count as u32])
+        .collect()
+}
+
+/// Reduce an image to at most `max_colors` colours in the input's colour space
+/// (method: 0 = median cut, 1 = k-means)
+#[wasm_bindgen]
+pub fn palette_quantize(
+    data: &[u8],
+    max_colors: u32,
+    method: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+) -> Vec<u32> {
+    let format = PixelFormat::new(alpha_mode, color_space, None);
+    let data = input_pixels(data, format);
+    palette::quantize(&data, max_colors as usize, quantize_method(method))
+}
+
+/// Map every pixel onto the nearest colour of `palette`
+#[wasm_bindgen]
+pub fn palette_remap(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    palette: &[u32],
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = palette::remap_to_palette(&data, palette);
+    update_buffer(output, width, height, format)
+}
+
+/// Quantise an image and map it onto the resulting palette in one call
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn palette_quantize_image(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    max_colors: u32,
+    method: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let colors = palette::quantize(&data, max_colors as usize, quantize_method(method));
+    
+    let output = palette::remap_to_palette(&data, &colors);
+    update_buffer(output, width, height, format)
+}
+
+// ============================================================================
+// Dither Functions
+// ============================================================================
+
+fn dither_method(method: u32) -> dither::DitherMethod {
+    match method {
+        0 => dither::DitherMethod::Bayer2,
+        1 => dither::DitherMethod::Bayer4,
+        2 => dither::DitherMethod::Bayer8,
+        3 => dither::DitherMethod::BlueNoise,
+        5 => dither::DitherMethod::Atkinson,
+        6 => dither::DitherMethod::Sierra,
+        _ => dither::DitherMethod::FloydSteinberg,
+    }
+}
+
+/// Dither to `bits_per_channel` levels per channel with default options
+/// (method: 0-2 = Bayer 2/4/8, 3 = blue noise, 4 = Floyd–Steinberg,
+/// 5 = Atkinson, 6 = Sierra)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn dither_image(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    method: u32,
+    bits_per_channel: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = dither::DitherConfig::default();
+    dither_image_config(
+        data, width, height, method, bits_per_channel,
+        &config.palette, config.serpentine, config.linear_light,
+        alpha_mode, color_space, output_space,
+    )
+}
+
+/// Dither with full options; a non-empty `palette` (`0xRRGGBBAA`) replaces
+/// the bit-depth target
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn dither_image_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    method: u32,
+    bits_per_channel: u32,
+    palette: &[u32],
+    serpentine: bool,
+    linear_light: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    let config = dither::DitherConfig {
+        method: dither_method(method),
+        serpentine,
+        linear_light,
+        color_space: format.space,
+        bits_per_channel: bits_per_channel.clamp(1, 8) as u8,
+        palette: palette.to_vec(),
+    };
+    
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = dither::dither(&data, width as usize, height as usize, &config);
+    update_buffer(output, width, height, format)
+}
+
+// ============================================================================
+// Sprite Effects Functions
+// ============================================================================
+//
+// Thickness, offsets and radius are given in source pixels; pass the
+// upscaler's factor as `pixel_scale` when running the stage on its output
+// (1 = run on the source before upscaling). Each setting is clamped to the
+// `SPRITE_FX_MAX_*` bounds below so the canvas and the dilation work stay
+// bounded.
+
+/// Add a 1px black outline around the sprite
+#[wasm_bindgen]
+pub fn sprite_fx(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    sprite_fx_render(data, width, height, &sprite_fx::SpriteFxConfig::default(), PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Outline, drop shadow and glow with full config
+/// (shadow colour alpha 0 = no shadow, glow radius 0 = no glow)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn sprite_fx_config(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    pixel_scale: u32,
+    outline_color: u32,
+    outline_thickness: u32,
+    inner_outline: bool,
+    eight_connected: bool,
+    shadow_color: u32,
+    shadow_offset_x: i32,
+    shadow_offset_y: i32,
+    glow_color: u32,
+    glow_radius: u32,
+    glow_strength: f32,
+    expand_canvas: bool,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = sprite_fx_settings(
+        pixel_scale, outline_thickness, inner_outline, shadow_color,
+        shadow_offset_x, shadow_offset_y, glow_radius, expand_canvas,
     );
-    
-    let out_width = width * clamped_scale as u32;
-    let out_height = height * clamped_scale as u32;
-    
-    update_buffer(output, out_width, out_height)
+    let config = sprite_fx::SpriteFxConfig {
+        outline_color,
+        connectivity: if eight_connected {
+            sprite_fx::Connectivity::Eight
+        } else {
+            sprite_fx::Connectivity::Four
+        },
+        glow_color,
+        glow_strength,
+        ..config
+    };
+    sprite_fx_render(data, width, height, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Get sprite effects output dimensions (larger than the input only with `expand_canvas`)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn sprite_fx_get_dimensions(
+    width: u32,
+    height: u32,
+    pixel_scale: u32,
+    outline_thickness: u32,
+    inner_outline: bool,
+    shadow_color: u32,
+    shadow_offset_x: i32,
+    shadow_offset_y: i32,
+    glow_radius: u32,
+    expand_canvas: bool,
+) -> Vec<u32> {
+    let config = sprite_fx_settings(
+        pixel_scale, outline_thickness, inner_outline, shadow_color,
+        shadow_offset_x, shadow_offset_y, glow_radius, expand_canvas,
+    );
+    let (w, h) = sprite_fx::get_output_dimensions(width as usize, height as usize, &config);
+    vec![w as u32, h as u32]
+}
+
+/// Largest `pixel_scale` the sprite effects accept
+const SPRITE_FX_MAX_SCALE: u32 = 16;
+/// Largest outline thickness and glow radius, in source pixels
+const SPRITE_FX_MAX_RADIUS: u32 = 32;
+/// Largest shadow offset either way, in source pixels
+const SPRITE_FX_MAX_OFFSET: i32 = 64;
+
+/// The settings that decide the canvas size, clamped and converted to output pixels
+#[allow(clippy::too_many_arguments)]
+fn sprite_fx_settings(
+    pixel_scale: u32,
+    outline_thickness: u32,
+    inner_outline: bool,
+    shadow_color: u32,
+    shadow_offset_x: i32,
+    shadow_offset_y: i32,
+    glow_radius: u32,
+    expand_canvas: bool,
+) -> sprite_fx::SpriteFxConfig {
+    let offset = |o: i32| o.clamp(-SPRITE_FX_MAX_OFFSET, SPRITE_FX_MAX_OFFSET);
+    sprite_fx::SpriteFxConfig {
+        outline_thickness: outline_thickness.min(SPRITE_FX_MAX_RADIUS) as usize,
+        outline_placement: if inner_outline {
+            sprite_fx::OutlinePlacement::Inner
+        } else {
+            sprite_fx::OutlinePlacement::Outer
+        },
+        shadow_color,
+        shadow_offset_x: offset(shadow_offset_x),
+        shadow_offset_y: offset(shadow_offset_y),
+        glow_radius: glow_radius.min(SPRITE_FX_MAX_RADIUS) as usize,
+        expand_canvas,
+        ..Default::default()
+    }
+    .scaled(pixel_scale.clamp(1, SPRITE_FX_MAX_SCALE) as usize)
+}
+
+fn sprite_fx_render(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    config: &sprite_fx::SpriteFxConfig,
+    format: PixelFormat,
+) -> UpscaleResult {
+    let (w, h) = (width as usize, height as usize);
+    let (out_width, out_height) = sprite_fx::get_output_dimensions(w, h, config);
+    let Some(data) = image_pixels(data, width, height, format) else {
+        return empty_result(format);
+    };
+    let output = sprite_fx::apply_sprite_fx(&data, w, h, config);
+    update_buffer(output, out_width as u32, out_height as u32, format)
+}
+
+// ============================================================================
+// Presets
+// ============================================================================
+//
+// The preset catalogue lives on the Rust config types; these exports let the
+// JS side list them and read them back as option objects (camelCase keys
+// matching `CrtOptions` / `HexOptions` / `XbrzOptions`).
+
+/// Names of the built-in CRT presets
+#[wasm_bindgen]
+pub fn crt_preset_names() -> Vec<String> {
+    crt::CrtConfig::PRESET_NAMES.iter().map(|s| s.to_string()).collect()
+}
+
+/// CRT preset as a JSON options object, or `undefined` for unknown names
+#[wasm_bindgen]
+pub fn crt_preset_json(name: &str) -> Option<String> {
+    let c = crt::CrtConfig::preset(name)?;
+    let curvature = match c.curvature {
+        crt::CrtCurvature::Quadratic => "quadratic",
+        crt::CrtCurvature::Spherical => "spherical",
+        crt::CrtCurvature::Cylindrical => "cylindrical",
+    };
+    Some(format!(
+        concat!(
+            "{{\"warpX\":{},\"warpY\":{},\"scanHardness\":{},\"scanOpacity\":{},",
+            "\"maskOpacity\":{},\"enableWarp\":{},\"enableScanlines\":{},\"enableMask\":{},",
+            "\"pixelAspect\":{},\"curvature\":\"{}\",\"curvatureRadius\":{},",
+            "\"viewingDistance\":{},\"overscan\":{},\"tiltX\":{},\"tiltY\":{}}}"
+        ),
+        c.warp_x, c.warp_y, c.scan_hardness, c.scan_opacity,
+        c.mask_opacity, c.enable_warp, c.enable_scanlines, c.enable_mask,
+        c.pixel_aspect, curvature, c.curvature_radius,
+        c.viewing_distance, c.overscan, c.tilt_x, c.tilt_y,
+    ))
+}
+
+/// CRT upscale with a named preset (unknown names fall back to the default)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn crt_upscale_preset(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    name: &str,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = crt::CrtConfig::preset(name).unwrap_or_default();
+    crt_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Names of the built-in HEX presets
+#[wasm_bindgen]
+pub fn hex_preset_names() -> Vec<String> {
+    hex::HexConfig::PRESET_NAMES.iter().map(|s| s.to_string()).collect()
+}
+
+/// HEX preset as a JSON options object, or `undefined` for unknown names
+#[wasm_bindgen]
+pub fn hex_preset_json(name: &str) -> Option<String> {
+    let c = hex::HexConfig::preset(name)?;
+    let orientation = match c.orientation {
+        hex::HexOrientation::FlatTop => "flat-top",
+        hex::HexOrientation::PointyTop => "pointy-top",
+    };
+    Some(format!(
+        concat!(
+            "{{\"orientation\":\"{}\",\"drawBorders\":{},\"borderColor\":{},",
+            "\"borderThickness\":{},\"backgroundColor\":{},\"supersample\":{},",
+            "\"gap\":{},\"bevelWidth\":{},\"bevelStrength\":{},\"gradient\":{},",
+            "\"shadowOffsetX\":{},\"shadowOffsetY\":{},\"shadowColor\":{},\"shadowSoftness\":{},",
+            "\"composite\":{},\"skipTransparentBorders\":{}}}"
+        ),
+        orientation, c.draw_borders, c.border_color,
+        c.border_thickness, c.background_color, c.supersample,
+        c.style.gap, c.style.bevel_width, c.style.bevel_strength, c.style.gradient,
+        c.style.shadow_offset_x, c.style.shadow_offset_y, c.style.shadow_color, c.style.shadow_softness,
+        c.alpha.composite, c.alpha.skip_transparent_borders,
+    ))
+}
+
+/// HEX upscale with a named preset (unknown names fall back to the default)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn hex_upscale_preset(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    name: &str,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = hex::HexConfig::preset(name).unwrap_or_default();
+    hex_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Names of the built-in xBRZ presets
+#[wasm_bindgen]
+pub fn xbrz_preset_names() -> Vec<String> {
+    xbrz::config::ScalerConfig::PRESET_NAMES.iter().map(|s| s.to_string()).collect()
+}
+
+/// xBRZ preset as a JSON options object, or `undefined` for unknown names
+#[wasm_bindgen]
+pub fn xbrz_preset_json(name: &str) -> Option<String> {
+    let c = xbrz::config::ScalerConfig::preset(name)?;
+    Some(format!(
+        concat!(
+            "{{\"equalColorTolerance\":{},\"centerDirectionBias\":{},",
+            "\"dominantDirectionThreshold\":{},\"steepDirectionThreshold\":{}}}"
+        ),
+        c.equal_color_tolerance, c.center_direction_bias,
+        c.dominant_direction_threshold, c.steep_direction_threshold,
+    ))
+}
+
+/// xBRZ upscale with a named preset (unknown names fall back to the default)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn xbrz_upscale_preset(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    name: &str,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = xbrz::config::ScalerConfig::preset(name).unwrap_or_default();
+    xbrz_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
+}
+
+/// Names of the built-in LCD presets
+#[wasm_bindgen]
+pub fn lcd_preset_names() -> Vec<String> {
+    lcd::LcdConfig::PRESET_NAMES.iter().map(|s| s.to_string()).collect()
+}
+
+/// LCD preset as a JSON options object, or `undefined` for unknown names
+#[wasm_bindgen]
+pub fn lcd_preset_json(name: &str) -> Option<String> {
+    let c = lcd::LcdConfig::preset(name)?;
+    let model = match c.model {
+        lcd::LcdModel::Dmg => "dmg",
+        lcd::LcdModel::Color => "color",
+        lcd::LcdModel::DotMatrix => "dot-matrix",
+    };
+    let shades = c.shades.map(|s| s.to_string()).join(",");
+    let matrix = c.color_matrix.as_flattened().iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+    Some(format!(
+        concat!(
+            "{{\"model\":\"{}\",\"shades\":[{}],\"colorMatrix\":[{}],\"luminance\":{},",
+            "\"gridWidth\":{},\"gridOpacity\":{},\"subpixelOpacity\":{},\"shadow\":{},",
+            "\"backlightBleed\":{},\"ghosting\":{},\"backgroundColor\":{}}}"
+        ),
+        model, shades, matrix, c.luminance,
+        c.grid_width, c.grid_opacity, c.subpixel_opacity, c.shadow,
+        c.backlight_bleed, c.ghosting, c.background_color,
+    ))
+}
+
+/// LCD upscale with a named preset (unknown names fall back to the default),
+/// ghosting `previous` by the preset's weight
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn lcd_upscale_preset(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    name: &str,
+    previous: Option<Vec<u8>>,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = lcd::LcdConfig::preset(name).unwrap_or_default();
+    let format = PixelFormat::new(alpha_mode, color_space, output_space);
+    lcd_render(data, width, height, scale, &config, previous.as_deref(), format)
+}
+
+/// Names of the built-in mosaic presets
+#[wasm_bindgen]
+pub fn mosaic_preset_names() -> Vec<String> {
+    mosaic::MosaicConfig::PRESET_NAMES.iter().map(|s| s.to_string()).collect()
+}
+
+/// Mosaic preset as a JSON options object, or `undefined` for unknown names
+#[wasm_bindgen]
+pub fn mosaic_preset_json(name: &str) -> Option<String> {
+    let c = mosaic::MosaicConfig::preset(name)?;
+    let style = match c.style {
+        mosaic::MosaicStyle::Led => "led",
+        mosaic::MosaicStyle::Brick => "brick",
+        mosaic::MosaicStyle::CrossStitch => "cross-stitch",
+        mosaic::MosaicStyle::Bead => "bead",
+    };
+    Some(format!(
+        "{{\"style\":\"{}\",\"backgroundColor\":{},\"shading\":{},\"glow\":{},\"supersample\":{}}}",
+        style, c.background_color, c.shading, c.glow, c.supersample,
+    ))
+}
+
+/// Mosaic upscale with a named preset (unknown names fall back to the default)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn mosaic_upscale_preset(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    scale: u32,
+    name: &str,
+    alpha_mode: Option<u32>,
+    color_space: Option<u32>,
+    output_space: Option<u32>,
+) -> UpscaleResult {
+    let config = mosaic::MosaicConfig::preset(name).unwrap_or_default();
+    mosaic_render(data, width, height, scale, &config, PixelFormat::new(alpha_mode, color_space, output_space))
 }
 
 // ============================================================================
@@ -238,12 +2509,480 @@ mod tests {
     #[test]
     fn test_crt_basic() {
         let img = create_test_image(4, 4);
-        let result = crt_upscale(&img, 4, 4, 2);
+        let result = crt_upscale(&img, 4, 4, 2, None, None, None);
         assert_eq!(result.width, 8);
         assert_eq!(result.height, 8);
         assert_eq!(result.len, 8 * 8 * 4);
     }
     
+    #[test]
+    fn test_crt_sized_letterbox() {
+        // 256x224 with 8:7 pixel aspect fitted into a 1920x1080 canvas
+        let img = create_test_image(256, 224);
+        let dims = crt_get_dimensions(256, 224, 4, 1920, 1080, 8.0 / 7.0);
+        assert_eq!(dims, vec![1920, 1080]);
+        
+        let config = crt::CrtConfig {
+            output_width: Some(1920),
+            output_height: Some(1080),
+            pixel_aspect: 8.0 / 7.0,
+            enable_warp: false,
+            ..Default::default()
+        };
+        let out = crt::crt_upscale(&img, 256, 224, 4, &config);
+        assert_eq!(out.len(), 1920 * 1080 * 4);
+        
+        // Picture is 1080 * (256 * 8/7) / 224 ≈ 1410 wide, centred: pillarbox on both sides
+        let alpha_at = |x: usize, y: usize| out[(y * 1920 + x) * 4 + 3];
+        assert_eq!(alpha_at(100, 540), 0);
+        assert_eq!(alpha_at(1820, 540), 0);
+        assert_eq!(alpha_at(960, 540), 255);
+    }
+    
+    #[test]
+    fn test_crt_pixel_aspect_dimensions() {
+        let dims = crt_get_dimensions(256, 224, 3, 0, 0, 8.0 / 7.0);
+        assert_eq!(dims, vec![878, 672]);
+        
+        let dims = crt_get_dimensions(320, 240, 3, 0, 0, 1.0);
+        assert_eq!(dims, vec![960, 720]);
+    }
+    
+    #[test]
+    fn test_presets_resolve() {
+        for name in crt_preset_names() {
+            assert!(crt_preset_json(&name).is_some(), "crt {}", name);
+        }
+        for name in hex_preset_names() {
+            assert!(hex_preset_json(&name).is_some(), "hex {}", name);
+        }
+        for name in xbrz_preset_names() {
+            assert!(xbrz_preset_json(&name).is_some(), "xbrz {}", name);
+        }
+        assert!(crt_preset_json("nope").is_none());
+        
+        assert_eq!(
+            hex_preset_json("pointy").unwrap(),
+            r#"{"orientation":"pointy-top","drawBorders":false,"borderColor":673720575,"borderThickness":1,"backgroundColor":0,"supersample":1,"gap":0,"bevelWidth":0,"bevelStrength":0,"gradient":0,"shadowOffsetX":0,"shadowOffsetY":0,"shadowColor":0,"shadowSoftness":0,"composite":false,"skipTransparentBorders":false}"#
+        );
+        assert!(crt_preset_json("flat").unwrap().contains(r#""enableWarp":false"#));
+        
+        let img = create_test_image(4, 4);
+        let result = crt_upscale_preset(&img, 4, 4, 2, "authentic", None, None, None);
+        assert_eq!(result.width, 8);
+    }
+    
+    /// `src/presets.generated.ts`: the preset catalogue as constants for
+    /// GPU-only consumers that do not load the WASM module
+    fn presets_ts() -> String {
+        let mut ts = String::from(concat!(
+            "// Generated from the Rust preset catalogue by `test_generated_presets_are_current`\n",
+            "// in src/wasm/lib.rs. Do not edit; regenerate with\n",
+            "// `UPDATE_PRESETS=1 cargo test generated_presets`.\n",
+            "\n",
+            "import type { CrtOptions, HexOptions, XbrzOptions } from './types.js';\n",
+        ));
+        type PresetJson = fn(&str) -> Option<String>;
+        let catalogues: [(&str, &str, &str, Vec<String>, PresetJson); 3] = [
+            ("CRT_PRESETS", "CrtOptions", "crt", crt_preset_names(), crt_preset_json),
+            ("HEX_PRESETS", "HexOptions", "hex", hex_preset_names(), hex_preset_json),
+            ("XBRZ_PRESETS", "XbrzOptions", "xbrz", xbrz_preset_names(), xbrz_preset_json),
+        ];
+        for (constant, options, key, names, json) in catalogues {
+            ts += &format!(
+                "\n/** @deprecated Read presets with `presetsFromWasm(wasm).{key}` */\nexport const {constant}: Record<string, Partial<{options}>> = {{\n"
+            );
+            for name in names {
+                ts += &format!("  {name}: {},\n", json(&name).unwrap());
+            }
+            ts += "};\n";
+        }
+        ts
+    }
+    
+    #[test]
+    fn test_generated_presets_are_current() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/presets.generated.ts");
+        let expected = presets_ts();
+        if std::env::var_os("UPDATE_PRESETS").is_some() {
+            std::fs::write(path, &expected).unwrap();
+        }
+        let current = std::fs::read_to_string(path).unwrap_or_default();
+        assert!(current == expected, "{path} is stale; regenerate with `UPDATE_PRESETS=1 cargo test generated_presets`");
+    }
+    
+    #[test]
+    fn test_crt_options_carry_presets() {
+        // Fill the options the way `WasmRenderer.renderCrt` does from a preset
+        for name in crt::CrtConfig::PRESET_NAMES {
+            let preset = crt::CrtConfig::preset(name).unwrap();
+            let mut options = CrtRenderOptions::new();
+            options.warp_x = preset.warp_x;
+            options.warp_y = preset.warp_y;
+            options.scan_hardness = preset.scan_hardness;
+            options.scan_opacity = preset.scan_opacity;
+            options.mask_opacity = preset.mask_opacity;
+            options.enable_warp = preset.enable_warp;
+            options.enable_scanlines = preset.enable_scanlines;
+            options.enable_mask = preset.enable_mask;
+            options.pixel_aspect = preset.pixel_aspect;
+            options.curvature = preset.curvature as u32;
+            options.curvature_radius = preset.curvature_radius;
+            options.viewing_distance = preset.viewing_distance;
+            options.overscan = preset.overscan;
+            options.tilt_x = preset.tilt_x;
+            options.tilt_y = preset.tilt_y;
+            
+            let config = options.config();
+            assert_eq!(config.curvature, preset.curvature, "{name}");
+            assert_eq!(
+                (config.pixel_aspect, config.curvature_radius, config.overscan),
+                (preset.pixel_aspect, preset.curvature_radius, preset.overscan),
+                "{name}"
+            );
+            
+            let img = create_test_image(5, 4);
+            let result = crt_upscale_options(&img, 5, 4, 3, &options, None, None, None);
+            assert_eq!(crt_options_get_dimensions(5, 4, 3, &options), vec![result.width, result.height]);
+            let by_name = crt_upscale_preset(&img, 5, 4, 3, name, None, None, None);
+            assert_eq!((result.width, result.height), (by_name.width, by_name.height), "{name}");
+        }
+        assert_eq!(CrtRenderOptions::new().config().curvature, crt::CrtCurvature::Quadratic);
+    }
+    
+    #[test]
+    fn test_tessellation_dimensions() {
+        let img = create_test_image(4, 3);
+        
+        let result = triangle_upscale(&img, 4, 3, 8, None, None, None);
+        assert_eq!(triangle_get_dimensions(4, 3, 8, 0), vec![result.width, result.height]);
+        assert_eq!(triangle_get_dimensions(4, 3, 8, 1), vec![32, 24]);
+        
+        let result = brick_upscale(&img, 4, 3, 8, None, None, None);
+        assert_eq!(brick_get_dimensions(4, 3, 8, 0.5), vec![result.width, result.height]);
+        assert_eq!(result.width, 36);
+        
+        let result = diamond_upscale(&img, 4, 3, 8, None, None, None);
+        assert_eq!(diamond_get_dimensions(4, 3, 8, 0.5), vec![result.width, result.height]);
+        
+        let result = voronoi_upscale(&img, 4, 3, 8, None, None, None);
+        assert_eq!(voronoi_get_dimensions(4, 3, 8), vec![32, 24]);
+        assert_eq!(result.len, 32 * 24 * 4);
+    }
+    
+    #[test]
+    fn test_nearest_and_sharp_bilinear() {
+        let img = create_test_image(4, 4);
+        
+        let result = nearest_upscale(&img, 4, 4, 4, None, None, None);
+        assert_eq!((result.width, result.height), (16, 16));
+        
+        let result = nearest_upscale_config(&img, 4, 4, 1, 30, 20, 1.0, true, 0x000000FF, None, None, None);
+        assert_eq!((result.width, result.height), (30, 20));
+        assert_eq!(nearest_get_dimensions(256, 224, 3, 0, 0, 8.0 / 7.0, false), vec![878, 672]);
+        
+        let result = sharp_bilinear_upscale_config(&img, 4, 4, 1, 30, 0, 1.0, 0, None, None, None);
+        assert_eq!((result.width, result.height), (30, 30));
+        assert_eq!(sharp_bilinear_get_dimensions(4, 4, 3, 0, 0, 1.0), vec![12, 12]);
+    }
+    
+    #[test]
+    fn test_scale2x_scale_factors() {
+        let img = create_test_image(4, 4);
+        for (scale, expected) in [(1, 8), (2, 8), (3, 12), (4, 16), (9, 16)] {
+            let result = scale2x_upscale(&img, 4, 4, scale, None, None, None);
+            assert_eq!(result.width, expected);
+            assert_eq!(result.len, expected * expected * 4);
+        }
+    }
+    
+    #[test]
+    fn test_hqx_scale_factors() {
+        let img = create_test_image(4, 4);
+        for (scale, expected) in [(1, 8), (2, 8), (3, 12), (4, 16), (9, 16)] {
+            let result = hqx_upscale(&img, 4, 4, scale, None, None, None);
+            assert_eq!(result.width, expected);
+            assert_eq!(result.len, expected * expected * 4);
+        }
+    }
+    
+    #[test]
+    fn test_mmpx_and_super_xbr_scale_factors() {
+        let img = create_test_image(4, 4);
+        for (scale, expected) in [(1, 8), (2, 8), (3, 16), (4, 16)] {
+            let result = mmpx_upscale(&img, 4, 4, scale, None, None, None);
+            assert_eq!(result.width, expected);
+            assert_eq!(result.len, expected * expected * 4);
+        }
+        for (scale, expected) in [(2, 8), (3, 16), (8, 32)] {
+            let result = super_xbr_upscale(&img, 4, 4, scale, None, None, None);
+            assert_eq!(result.width, expected);
+            assert_eq!(super_xbr_get_dimensions(4, 4, scale), vec![expected, expected]);
+        }
+    }
+    
+    #[test]
+    fn test_depixel_arbitrary_size() {
+        let img = create_test_image(4, 4);
+        let result = depixel_upscale(&img, 4, 4, 12, None, None, None);
+        assert_eq!(result.width, 48);
+        assert_eq!(result.len, 48 * 48 * 4);
+
+        let result = depixel_upscale_config(&img, 4, 4, 1, 50, 0, 30.0, 1.0, 1.0, None, None, None);
+        assert_eq!((result.width, result.height), (50, 50));
+        assert_eq!(depixel_get_dimensions(4, 4, 1, 0, 30), vec![30, 30]);
+    }
+    
+    #[test]
+    fn test_vectorize_svg_modes() {
+        let img = create_test_image(4, 4);
+        let exact = vectorize_svg(&img, 4, 4, 10, 0, None, None, None);
+        assert!(exact.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40""#));
+        assert!(exact.ends_with("</svg>"));
+        assert!(!exact.contains('Q'));
+
+        let smooth = vectorize_svg_config(&img, 4, 4, 10, 1, 0x000000FF, 30.0, None, None, None);
+        assert!(smooth.contains(r##"<rect width="40" height="40" fill="#000000"/>"##));
+    }
+    
+    #[test]
+    fn test_pixel_grid_round_trip() {
+        let img = create_test_image(4, 4);
+        let big = nearest::nearest_upscale(&img, 4, 4, 3, &nearest::NearestConfig::default());
+        assert_eq!(detect_pixel_grid(&big, 12, 12, None, None), vec![3, 3, 0, 0, 4, 4]);
+
+        let result = downscale_to_grid(&big, 12, 12, 0, 0, 0, 0, None, None, None);
+        assert_eq!((result.width, result.height, result.len), (4, 4, 4 * 4 * 4));
+        let result = downscale_to_grid(&big, 12, 12, 2, 6, 1, 0, None, None, None);
+        assert_eq!((result.width, result.height), (7, 2));
+    }
+    
+    #[test]
+    fn test_palette_exports() {
+        let img = create_test_image(4, 4);
+        let exact = palette_extract(&img, None, None);
+        assert_eq!(exact.len() % 2, 0);
+        assert_eq!(exact.iter().skip(1).step_by(2).sum::<u32>(), 16);
+
+        let colors = palette_quantize(&img, 3, 1, None, None);
+        assert!(!colors.is_empty() && colors.len() <= 3);
+        let result = palette_quantize_image(&img, 4, 4, 3, 1, None, None, None);
+        assert_eq!((result.width, result.height, result.len), (4, 4, 64));
+        let remapped = palette::remap_to_palette(&img, &colors);
+        assert!(remapped.chunks(4).all(|px| colors.contains(&u32::from_be_bytes([px[0], px[1], px[2], px[3]]))));
+    }
+
+    #[test]
+    fn test_mismatched_buffers_return_empty() {
+        // A 4x4 image passed off as 5x5, and dimensions whose size overflows
+        let img = create_test_image(4, 4);
+        for (width, height) in [(5, 5), (u32::MAX, u32::MAX)] {
+            for result in [
+                palette_remap(&img, width, height, &[0x000000FF, 0xFFFFFFFF], None, None, None),
+                palette_quantize_image(&img, width, height, 3, 1, None, None, None),
+                crt_upscale(&img, width, height, 2, None, None, None),
+                nearest_upscale(&img, width, height, 4, None, None, None),
+                hqx_upscale(&img, width, height, 2, None, None, None),
+                depixel_upscale(&img, width, height, 12, None, None, None),
+                dither_image(&img, width, height, 1, 1, None, None, None),
+            ] {
+                assert_eq!((result.width, result.height, result.len), (0, 0, 0));
+            }
+            assert!(vectorize_svg(&img, width, height, 10, 0, None, None, None).is_empty());
+            assert!(detect_pixel_grid(&img, width, height, None, None).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_dither_exports() {
+        let img = create_test_image(8, 8);
+        let result = dither_image(&img, 8, 8, 1, 1, None, None, None);
+        assert_eq!((result.width, result.height, result.len), (8, 8, 256));
+
+        let config = dither::DitherConfig {
+            method: dither::DitherMethod::Atkinson,
+            palette: vec![0x000000FF, 0xFFFFFFFF],
+            ..dither::DitherConfig::default()
+        };
+        let output = dither::dither(&img, 8, 8, &config);
+        assert!(output.chunks(4).all(|px| px == [0, 0, 0, 255] || px == [255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_lcd_presets() {
+        let img = create_test_image(4, 3);
+        for name in lcd::LcdConfig::PRESET_NAMES {
+            let json = lcd_preset_json(name).unwrap();
+            assert!(json.starts_with("{\"model\":") && json.ends_with('}'));
+            let result = lcd_upscale_preset(&img, 4, 3, 3, name, None, None, None, None);
+            assert_eq!((result.width, result.height), (12, 9));
+        }
+        assert!(lcd_preset_json("unknown").is_none());
+        assert_eq!(lcd_get_dimensions(4, 3, 1), vec![8, 6]);
+    }
+
+    #[test]
+    fn test_mosaic_exports() {
+        let img = create_test_image(3, 2);
+        for name in mosaic::MosaicConfig::PRESET_NAMES {
+            assert!(mosaic_preset_json(name).is_some());
+            let config = mosaic::MosaicConfig::preset(name).unwrap();
+            let dims = mosaic::get_output_dimensions(3, 2, 4, &config.style);
+            let result = mosaic_upscale_preset(&img, 3, 2, 4, name, None, None, None);
+            assert_eq!((result.width as usize, result.height as usize), dims);
+        }
+        assert_eq!(mosaic_get_dimensions(3, 2, 4, 1), vec![12, 8]);
+
+        let bom = mosaic_bill_of_materials(&img, None, None);
+        assert_eq!(bom.iter().skip(1).step_by(2).sum::<u32>(), 6);
+    }
+
+    #[test]
+    fn test_sprite_fx_after_upscale() {
+        // A 1px outline requested in source pixels is 3px wide on a 3x upscale
+        let mut img = vec![0u8; 3 * 3 * 4];
+        img[16..20].copy_from_slice(&[255, 255, 255, 255]);
+        let big = nearest::nearest_upscale(&img, 3, 3, 3, &nearest::NearestConfig::default());
+
+        let result = sprite_fx_config(&big, 9, 9, 3, 0xFF0000FF, 1, false, true, 0, 0, 0, 0, 0, 1.0, false, None, None, None);
+        assert_eq!((result.width, result.height), (9, 9));
+        assert_eq!(sprite_fx_get_dimensions(9, 9, 3, 1, false, 0, 0, 0, 0, true), vec![15, 15]);
+
+        let config = sprite_fx::SpriteFxConfig { outline_color: 0xFF0000FF, ..Default::default() }.scaled(3);
+        let out = sprite_fx::apply_sprite_fx(&big, 9, 9, &config);
+        assert_eq!(out.chunks(4).filter(|px| *px == [255, 0, 0, 255]).count(), 81 - 9);
+    }
+
+    #[test]
+    fn test_sprite_fx_settings_are_bounded() {
+        // Clamped to 16x scale, 32px outline and glow and 64px offsets: 512px of
+        // outline plus 1024px of shadow on one side, outline and glow on the other
+        let dims = sprite_fx_get_dimensions(9, 9, u32::MAX, u32::MAX, false, 0x000000FF, i32::MIN, i32::MAX, u32::MAX, true);
+        assert_eq!(dims, vec![9 + 1536 + 1024, 9 + 1024 + 1536]);
+
+        let config = sprite_fx::SpriteFxConfig {
+            outline_thickness: usize::MAX,
+            shadow_color: 0x000000FF,
+            shadow_offset_x: i32::MIN,
+            shadow_offset_y: i32::MAX,
+            expand_canvas: true,
+            ..Default::default()
+        }
+        .scaled(usize::MAX);
+        assert_eq!(config.padding(), [usize::MAX; 4]);
+        assert_eq!(sprite_fx::get_output_dimensions(9, 9, &config), (usize::MAX, usize::MAX));
+    }
+
+    type Engine = Box<dyn Fn(&[u8]) -> Vec<u8>>;
+    
+    /// Premultiplied white sprite with a soft rim, as a WebGL canvas hands it over
+    fn premultiplied_sprite() -> Vec<u8> {
+        let mut img = vec![0u8; 8 * 8 * 4];
+        for y in 1..7 {
+            for x in 1..7 {
+                let a = if x == 1 || x == 6 || y == 1 || y == 6 { 96 } else { 255 };
+                img[(y * 8 + x) * 4..][..4].copy_from_slice(&[a, a, a, a]);
+            }
+        }
+        img
+    }
+    
+    /// Run `engine` on the sprite through a premultiplied per-call format
+    fn premultiplied_round_trip(engine: impl Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+        let format = PixelFormat::new(Some(1), None, None);
+        let output = output_pixels(engine(&input_pixels(&premultiplied_sprite(), format)), format);
+        assert!(output.chunks(4).all(|px| px[..3].iter().all(|&c| c <= px[3])), "not premultiplied");
+        output
+    }
+    
+    #[test]
+    fn test_premultiplied_soft_edges() {
+        assert_eq!(PixelFormat::new(Some(1), None, None).alpha, alpha::AlphaMode::Premultiplied);
+        assert_eq!(PixelFormat::new(Some(7), None, None).alpha, alpha::AlphaMode::Straight);
+        assert_eq!(PixelFormat::new(None, None, None).alpha, alpha::AlphaMode::Straight);
+        
+        let straight = alpha::to_straight(&premultiplied_sprite(), alpha::AlphaMode::Premultiplied).into_owned();
+        let outputs = [
+            xbrz::xbrz_upscale(&straight, 8, 8, 3, 30.0, 4.0, 3.6, 2.2, Default::default()),
+            sharp_bilinear::sharp_bilinear_upscale(&straight, 8, 8, 3, &Default::default()),
+        ];
+        for output in outputs {
+            for px in output.chunks(4).filter(|px| px[3] > 0) {
+                assert!(px[..3].iter().all(|&c| c >= 250), "dark fringe {:?}", px);
+            }
+            let pre = alpha::from_straight(output, alpha::AlphaMode::Premultiplied);
+            assert!(pre.chunks(4).any(|px| px[3] == 96 && px[..3] == [96; 3]));
+            assert!(pre.chunks(4).all(|px| px[..3].iter().all(|&c| c <= px[3])));
+        }
+    }
+    
+    #[test]
+    fn test_premultiplied_round_trip_keeps_white_rims() {
+        // Engines that only copy or blend source colours: the white sprite
+        // comes back white at every alpha; copying engines keep its rim
+        // exactly as it went in
+        let engines: [(&str, bool, Engine); 7] = [
+            ("scale2x", true, Box::new(|d| scale2x::scale2x_upscale(d, 8, 8, 2, &Default::default()))),
+            ("mmpx", true, Box::new(|d| mmpx::mmpx_upscale(d, 8, 8, 2, &Default::default()))),
+            ("super_xbr", false, Box::new(|d| super_xbr::super_xbr_upscale(d, 8, 8, 2, &Default::default()))),
+            ("triangle", true, Box::new(|d| tess::triangle::triangle_upscale(d, 8, 8, 6, &Default::default()))),
+            ("brick", true, Box::new(|d| tess::brick::brick_upscale(d, 8, 8, 6, &Default::default()))),
+            ("diamond", true, Box::new(|d| tess::diamond::diamond_upscale(d, 8, 8, 6, &Default::default()))),
+            ("voronoi", true, Box::new(|d| tess::voronoi::voronoi_upscale(d, 8, 8, 6, &Default::default()))),
+        ];
+        for (name, copies, engine) in engines {
+            let output = premultiplied_round_trip(engine);
+            assert!(output.chunks(4).all(|px| px[..3].iter().all(|&c| px[3] - c <= 1)), "{name}: dark fringe");
+            assert!(!copies || output.chunks(4).any(|px| px == [96; 4]), "{name}: rim lost");
+        }
+    }
+    
+    #[test]
+    fn test_premultiplied_round_trip_matches_straight() {
+        // Engines that draw their own opaque surroundings: a premultiplied
+        // call gives the straight call's picture, premultiplied
+        let straight = alpha::to_straight(&premultiplied_sprite(), alpha::AlphaMode::Premultiplied).into_owned();
+        let engines: [(&str, Engine); 2] = [
+            ("lcd", Box::new(|d| lcd::lcd_upscale(d, 8, 8, 3, &Default::default(), None))),
+            ("mosaic", Box::new(|d| mosaic::mosaic_upscale(d, 8, 8, 4, &Default::default()))),
+        ];
+        for (name, engine) in engines {
+            let expected = alpha::from_straight(engine(&straight), alpha::AlphaMode::Premultiplied);
+            assert_eq!(premultiplied_round_trip(engine), expected, "{name}");
+        }
+    }
+    
+    #[test]
+    fn test_color_space_tags() {
+        assert_eq!(color_space(1), color_space::ColorSpace::DisplayP3);
+        assert_eq!(color_space(2), color_space::ColorSpace::LinearSrgb);
+        assert_eq!(color_space(9), color_space::ColorSpace::Srgb);
+        
+        // The output space defaults to the input space
+        let img = create_test_image(4, 4);
+        let result = crt_upscale(&img, 4, 4, 2, None, None, None);
+        assert_eq!(result.color_space, color_space::ColorSpace::Srgb as u32);
+        let result = crt_upscale(&img, 4, 4, 2, None, Some(2), None);
+        assert_eq!(result.color_space, color_space::ColorSpace::LinearSrgb as u32);
+        let result = crt_upscale(&img, 4, 4, 2, None, Some(0), Some(1));
+        assert_eq!(result.color_space, color_space::ColorSpace::DisplayP3 as u32);
+    }
+    
+    #[test]
+    fn test_input_converted_to_output_space() {
+        let format = PixelFormat::new(None, Some(0), Some(1));
+        assert_eq!(format.space, color_space::ColorSpace::DisplayP3);
+        assert_eq!(*input_pixels(&[255, 0, 0, 200], format), [234, 51, 35, 200]);
+        
+        // Premultiplied input is unpremultiplied before conversion
+        let format = PixelFormat::new(Some(1), Some(2), Some(0));
+        assert_eq!(*input_pixels(&[64, 64, 64, 128], format), [188, 188, 188, 128]);
+        
+        // Matching spaces pass the data through untouched
+        let data = [1, 2, 3, 4];
+        assert!(matches!(input_pixels(&data, PixelFormat::new(None, Some(1), None)), Cow::Borrowed(_)));
+    }
+    
     #[test]
     fn test_hex_dimensions() {
         let dims = hex_get_dimensions(4, 4, 16, 0);
@@ -251,10 +2990,36 @@ mod tests {
         assert!(dims[1] > 0);
     }
     
+    #[test]
+    fn test_hex_options_combine() {
+        let img = create_test_image(6, 4);
+        let mut options = HexRenderOptions::new();
+        assert_eq!(options.supersample, 1);
+        
+        // Resampling, anti-aliasing, styling and alpha handling in one call
+        options.columns = 3;
+        options.supersample = 4;
+        options.gap = 2.0;
+        options.bevel_width = 2.0;
+        options.bevel_strength = 0.5;
+        options.composite = true;
+        options.background_color = 0x000000FF;
+        let result = hex_upscale_options(&img, 6, 4, 8, &options, None, None, None);
+        assert_eq!(hex_options_get_dimensions(6, 4, 8, &options), vec![result.width, result.height]);
+        assert_eq!(result.len, result.width * result.height * 4);
+        
+        let config = options.config();
+        assert_eq!((config.supersample, config.style.gap), (4, 2.0));
+        assert!(config.alpha.composite);
+        
+        options.columns = 0;
+        assert_eq!(hex_options_get_dimensions(6, 4, 8, &options), hex_get_dimensions(6, 4, 8, 0));
+    }
+    
     #[test]
     fn test_hex_render() {
         let img = create_test_image(4, 4);
-        let result = hex_upscale(&img, 4, 4, 8);
+        let result = hex_upscale(&img, 4, 4, 8, None, None, None);
         assert!(result.width > 0);
         assert!(result.height > 0);
     }
@@ -262,7 +3027,7 @@ mod tests {
     #[test]
     fn test_xbrz_basic() {
         let img = create_test_image(4, 4);
-        let result = xbrz_upscale(&img, 4, 4, 2);
+        let result = xbrz_upscale(&img, 4, 4, 2, None, None, None);
         assert_eq!(result.width, 8);
         assert_eq!(result.height, 8);
     }
@@ -272,7 +3037,7 @@ mod tests {
         let img = create_test_image(4, 4);
         
         for scale in 2..=6 {
-            let result = xbrz_upscale(&img, 4, 4, scale);
+            let result = xbrz_upscale(&img, 4, 4, scale, None, None, None);
             assert_eq!(result.width, 4 * scale);
             assert_eq!(result.height, 4 * scale);
         }
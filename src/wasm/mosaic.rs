@@ -0,0 +1,405 @@
+//! Mosaic Rendering Engine
+//! Renders every source pixel as a physical piece: an LED dot, a brick stud,
+//! a cross-stitch or a fused perler bead.
+//!
+//! Each style is a [`CellShape`] that only describes one cell in cell units;
+//! placement, anti-aliasing, compositing over the background and LED glow
+//! are shared. [`bill_of_materials`] counts the pieces of every colour needed
+//! to build the result.
+
+use crate::canvas::rgba_parts;
+use crate::palette::{self, PaletteColor};
+
+/// Physical piece used for each pixel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MosaicStyle {
+    /// Round LED dots on black with a soft glow
+    Led = 0,
+    /// Top view of square bricks with a round stud
+    Brick = 1,
+    /// X stitches on fabric
+    CrossStitch = 2,
+    /// Fused beads with a hole in the middle
+    Bead = 3,
+}
+
+/// Mosaic configuration
+#[derive(Clone, Copy)]
+pub struct MosaicConfig {
+    pub style: MosaicStyle,
+    /// Colour between and behind the pieces (the fabric for cross-stitch)
+    pub background_color: u32,
+    /// Strength of highlights and shadows (0 = flat colour)
+    pub shading: f32,
+    /// Strength of the halo around lit LED dots
+    pub glow: f32,
+    /// Subsamples per axis for anti-aliased edges (1 = off, max 8)
+    pub supersample: usize,
+}
+
+impl Default for MosaicConfig {
+    fn default() -> Self {
+        Self {
+            style: MosaicStyle::Led,
+            background_color: 0x000000FF,
+            shading: 1.0,
+            glow: 0.5,
+            supersample: 3,
+        }
+    }
+}
+
+impl MosaicConfig {
+    /// Names accepted by [`MosaicConfig::preset`], in display order
+    pub const PRESET_NAMES: &'static [&'static str] = &["default", "led", "brick", "cross-stitch", "perler"];
+
+    /// Look up a named preset
+    pub fn preset(name: &str) -> Option<Self> {
+        let base = Self::default();
+        Some(match name {
+            "default" | "led" => base,
+            "brick" => Self {
+                style: MosaicStyle::Brick,
+                background_color: 0x1A1A1AFF,
+                glow: 0.0,
+                ..base
+            },
+            "cross-stitch" => Self {
+                style: MosaicStyle::CrossStitch,
+                background_color: 0xF2EEE2FF,
+                glow: 0.0,
+                ..base
+            },
+            "perler" => Self {
+                style: MosaicStyle::Bead,
+                background_color: 0xFFFFFFFF,
+                glow: 0.0,
+                ..base
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Shape and shading of one mosaic cell
+pub(crate) trait CellShape {
+    /// Premultiplied colour at `(u, v)` (cell units, origin at the cell
+    /// centre, y down) of a piece showing premultiplied `color`, or `None`
+    /// where the background shows through
+    fn shade(&self, u: f32, v: f32, color: [f32; 4]) -> Option<[f32; 4]>;
+
+    /// Share of the piece's colour it spills at distance `d` from its centre
+    fn glow(&self, _d: f32) -> f32 {
+        0.0
+    }
+}
+
+/// Brighten (`k > 0`, towards white) or darken (`k < 0`) a premultiplied colour
+#[inline]
+fn lit(c: [f32; 4], k: f32) -> [f32; 4] {
+    let k = k.clamp(-1.0, 1.0);
+    let rgb = [0, 1, 2].map(|i| if k >= 0.0 { c[i] + (c[3] - c[i]) * k } else { c[i] * (1.0 + k) });
+    [rgb[0], rgb[1], rgb[2], c[3]]
+}
+
+/// Light falls from the top left; 1 on that side of a round piece, -1 opposite
+#[inline]
+fn facing_light(u: f32, v: f32) -> f32 {
+    let r = (u * u + v * v).sqrt();
+    if r > 0.0 { -(u + v) / (r * std::f32::consts::SQRT_2) } else { 0.0 }
+}
+
+const LED_RADIUS: f32 = 0.4;
+/// How far past the dot edge its halo reaches, in cells
+const LED_GLOW_FALLOFF: f32 = 0.3;
+
+struct LedDot {
+    shading: f32,
+    glow: f32,
+}
+
+impl CellShape for LedDot {
+    fn shade(&self, u: f32, v: f32, color: [f32; 4]) -> Option<[f32; 4]> {
+        let r = (u * u + v * v).sqrt();
+        // Hot centre fading towards the rim
+        (r <= LED_RADIUS).then(|| lit(color, self.shading * 0.35 * (1.0 - r / LED_RADIUS).powi(2)))
+    }
+
+    fn glow(&self, d: f32) -> f32 {
+        if d <= LED_RADIUS {
+            return 0.0;
+        }
+        let t = (d - LED_RADIUS) / LED_GLOW_FALLOFF;
+        self.glow * 0.5 * (-t * t).exp()
+    }
+}
+
+const STUD_RADIUS: f32 = 0.3;
+/// Half width of the seam between bricks
+const BRICK_SEAM: f32 = 0.03;
+
+struct BrickStud {
+    shading: f32,
+}
+
+impl CellShape for BrickStud {
+    fn shade(&self, u: f32, v: f32, color: [f32; 4]) -> Option<[f32; 4]> {
+        let edge = 0.5 - u.abs().max(v.abs());
+        if edge < BRICK_SEAM {
+            return None;
+        }
+        let s = self.shading;
+        let r = (u * u + v * v).sqrt();
+
+        let k = if r <= STUD_RADIUS {
+            let rim = if r > STUD_RADIUS - 0.04 { -0.2 } else { 0.0 };
+            s * (0.25 * facing_light(u, v) + rim)
+        } else if ((u - 0.06).powi(2) + (v - 0.06).powi(2)).sqrt() <= STUD_RADIUS {
+            // Shadow of the stud towards the bottom right
+            -0.3 * s
+        } else if edge < BRICK_SEAM + 0.05 {
+            // Bevel: lit on the top and left sides
+            let top_left = if u.abs() > v.abs() { u < 0.0 } else { v < 0.0 };
+            s * if top_left { 0.2 } else { -0.25 }
+        } else {
+            0.0
+        };
+        Some(lit(color, k))
+    }
+}
+
+/// Half width of a stitch stroke
+const STITCH_HALF_WIDTH: f32 = 0.12;
+/// Stitches end this far from the cell centre along each axis
+const STITCH_REACH: f32 = 0.42;
+/// Twists of the thread along one stroke
+const STITCH_TWISTS: f32 = 4.0;
+
+struct CrossStitch {
+    shading: f32,
+}
+
+impl CellShape for CrossStitch {
+    fn shade(&self, u: f32, v: f32, color: [f32; 4]) -> Option<[f32; 4]> {
+        if u.abs() > STITCH_REACH || v.abs() > STITCH_REACH {
+            return None;
+        }
+        let s = self.shading;
+        let across_top = (u + v).abs() / std::f32::consts::SQRT_2;
+        let across_bottom = (u - v).abs() / std::f32::consts::SQRT_2;
+
+        // The "/" stroke lies on top of the "\" stroke
+        let (across, along, under) = if across_top <= STITCH_HALF_WIDTH {
+            (across_top, (u - v) / std::f32::consts::SQRT_2, 0.0)
+        } else if across_bottom <= STITCH_HALF_WIDTH {
+            (across_bottom, (u + v) / std::f32::consts::SQRT_2, -0.1)
+        } else {
+            return None;
+        };
+
+        let twist = (along * std::f32::consts::PI * 2.0 * STITCH_TWISTS).sin();
+        let round = (across / STITCH_HALF_WIDTH).powi(2);
+        Some(lit(color, s * (0.15 * twist - 0.3 * round + under)))
+    }
+}
+
+const BEAD_OUTER: f32 = 0.47;
+const BEAD_HOLE: f32 = 0.2;
+
+struct PerlerBead {
+    shading: f32,
+}
+
+impl CellShape for PerlerBead {
+    fn shade(&self, u: f32, v: f32, color: [f32; 4]) -> Option<[f32; 4]> {
+        let r = (u * u + v * v).sqrt();
+        if !(BEAD_HOLE..=BEAD_OUTER).contains(&r) {
+            return None;
+        }
+        // Rounded ring: highlight on its crest facing the light, dark at both edges
+        let n = ((r - BEAD_HOLE) / (BEAD_OUTER - BEAD_HOLE)) * 2.0 - 1.0;
+        let crest = 1.0 - n * n;
+        Some(lit(color, self.shading * (0.3 * facing_light(u, v) * crest - 0.15 * n * n)))
+    }
+}
+
+fn cell_shape(config: &MosaicConfig) -> Box<dyn CellShape> {
+    let shading = config.shading.max(0.0);
+    match config.style {
+        MosaicStyle::Led => Box::new(LedDot { shading, glow: config.glow.max(0.0) }),
+        MosaicStyle::Brick => Box::new(BrickStud { shading }),
+        MosaicStyle::CrossStitch => Box::new(CrossStitch { shading }),
+        MosaicStyle::Bead => Box::new(PerlerBead { shading }),
+    }
+}
+
+/// Cell placement on the output canvas
+struct MosaicGeometry {
+    /// Cell size in output pixels
+    pitch: f32,
+    /// Border around the grid so LED halos are not clipped
+    margin: f32,
+}
+
+impl MosaicGeometry {
+    fn new(scale: usize, style: MosaicStyle) -> Self {
+        let pitch = scale.clamp(2, 32) as f32;
+        let margin = if style == MosaicStyle::Led { pitch * 0.5 } else { 0.0 };
+        Self { pitch, margin }
+    }
+
+    fn output_dimensions(&self, input_width: usize, input_height: usize) -> (usize, usize) {
+        let out_w = input_width as f32 * self.pitch + 2.0 * self.margin;
+        let out_h = input_height as f32 * self.pitch + 2.0 * self.margin;
+        (out_w.ceil() as usize, out_h.ceil() as usize)
+    }
+
+    /// Position `(x, y)` in cell units relative to the grid origin
+    #[inline]
+    fn to_cells(&self, x: f32, y: f32) -> (f32, f32) {
+        ((x - self.margin) / self.pitch, (y - self.margin) / self.pitch)
+    }
+}
+
+pub fn get_output_dimensions(src_w: usize, src_h: usize, scale: usize, style: &MosaicStyle) -> (usize, usize) {
+    MosaicGeometry::new(scale, *style).output_dimensions(src_w, src_h)
+}
+
+pub fn mosaic_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &MosaicConfig,
+) -> Vec<u8> {
+    let geometry = MosaicGeometry::new(scale, config.style);
+    let (out_w, out_h) = geometry.output_dimensions(src_w, src_h);
+    let mut output = vec![0u8; out_w * out_h * 4];
+    if src_w == 0 || src_h == 0 {
+        return output;
+    }
+    assert_eq!(input.len(), src_w * src_h * 4);
+
+    let shape = cell_shape(config);
+    let [r, g, b, a] = rgba_parts(config.background_color).map(|c| c as f32 / 255.0);
+    let bg = [r * a, g * a, b * a, a];
+    let cells: Vec<[f32; 4]> = input
+        .chunks_exact(4)
+        .map(|px| {
+            let a = px[3] as f32 / 255.0;
+            let f = |c: u8| c as f32 / 255.0 * a;
+            [f(px[0]), f(px[1]), f(px[2]), a]
+        })
+        .collect();
+    let cell_at = |col: f32, row: f32| -> Option<[f32; 4]> {
+        let inside = col >= 0.0 && row >= 0.0 && col < src_w as f32 && row < src_h as f32;
+        inside.then(|| cells[row as usize * src_w + col as usize]).filter(|c| c[3] > 0.0)
+    };
+
+    let sample = |x: f32, y: f32| -> [f32; 4] {
+        let (cx, cy) = geometry.to_cells(x, y);
+        let (col, row) = (cx.floor(), cy.floor());
+        let piece = cell_at(col, row).and_then(|c| shape.shade(cx - col - 0.5, cy - row - 0.5, c));
+        match piece {
+            // Premultiplied source-over
+            Some(p) => [0, 1, 2, 3].map(|i| p[i] + bg[i] * (1.0 - p[3])),
+            None => bg,
+        }
+    };
+
+    let n = config.supersample.clamp(1, 8);
+    let offsets: Vec<f32> = (0..n).map(|i| (i as f32 + 0.5) / n as f32 - 0.5).collect();
+    let with_glow = config.style == MosaicStyle::Led && config.glow > 0.0;
+
+    for y in 0..out_h {
+        for x in 0..out_w {
+            let (x_f, y_f) = (x as f32 + 0.5, y as f32 + 0.5);
+            let mut sum = [0.0f32; 4];
+            for &dy in &offsets {
+                for &dx in &offsets {
+                    let s = sample(x_f + dx, y_f + dy);
+                    sum = [0, 1, 2, 3].map(|i| sum[i] + s[i]);
+                }
+            }
+            let mut c = sum.map(|s| s / (n * n) as f32);
+
+            // Halo of the nearby dots, added as light
+            if with_glow {
+                let (cx, cy) = geometry.to_cells(x_f, y_f);
+                for row in [cy.floor() - 1.0, cy.floor(), cy.floor() + 1.0] {
+                    for col in [cx.floor() - 1.0, cx.floor(), cx.floor() + 1.0] {
+                        let Some(cell) = cell_at(col, row) else { continue };
+                        let d = ((cx - col - 0.5).powi(2) + (cy - row - 0.5).powi(2)).sqrt();
+                        let g = shape.glow(d);
+                        c = [0, 1, 2, 3].map(|i| c[i] + cell[i] * g);
+                    }
+                }
+            }
+
+            let a = c[3].min(1.0);
+            let out_idx = (y * out_w + x) * 4;
+            for i in 0..3 {
+                let straight = if a > 0.0 { c[i] / a } else { 0.0 };
+                output[out_idx + i] = (straight * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+            }
+            output[out_idx + 3] = (a * 255.0 + 0.5) as u8;
+        }
+    }
+
+    output
+}
+
+/// Pieces needed per colour, most used first (transparent pixels need none)
+pub fn bill_of_materials(input: &[u8]) -> Vec<PaletteColor> {
+    palette::extract_palette(input)
+        .into_iter()
+        .filter(|p| p.rgba & 0xFF != 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimensions_and_led_glow() {
+        assert_eq!(get_output_dimensions(3, 2, 10, &MosaicStyle::Led), (40, 30));
+        assert_eq!(get_output_dimensions(3, 2, 10, &MosaicStyle::Bead), (30, 20));
+
+        let img = [255, 0, 0, 255];
+        let out = mosaic_upscale(&img, 1, 1, 20, &MosaicConfig::default());
+        let at = |x: usize, y: usize| &out[(y * 40 + x) * 4..(y * 40 + x) * 4 + 4];
+        assert!(at(20, 20)[0] > 250, "dot centre is lit");
+        let halo = at(20, 20 - 10)[0];
+        assert!(halo > 0 && halo < 200, "glow just outside the dot: {halo}");
+        assert_eq!(at(0, 0), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_styles_leave_background_where_expected() {
+        let img = [40, 120, 200, 255];
+        let shaped = |name: &str| {
+            let config = MosaicConfig { supersample: 1, ..MosaicConfig::preset(name).unwrap() };
+            mosaic_upscale(&img, 1, 1, 20, &config)
+        };
+        let at = |out: &[u8], x: usize, y: usize| out[(y * 20 + x) * 4..(y * 20 + x) * 4 + 4].to_vec();
+
+        // Bead hole and stitch gap show the background, the brick does not
+        assert_eq!(at(&shaped("perler"), 10, 10), [255, 255, 255, 255]);
+        assert_ne!(at(&shaped("perler"), 10, 3), [255, 255, 255, 255]);
+        assert_eq!(at(&shaped("cross-stitch"), 10, 3), [0xF2, 0xEE, 0xE2, 0xFF]);
+        assert_ne!(at(&shaped("cross-stitch"), 10, 10), [0xF2, 0xEE, 0xE2, 0xFF]);
+        let brick = shaped("brick");
+        assert_eq!(at(&brick, 0, 0), [0x1A, 0x1A, 0x1A, 0xFF]);
+        assert!(at(&brick, 7, 7)[2] > at(&brick, 13, 13)[2], "stud lit from the top left");
+    }
+
+    #[test]
+    fn test_bill_of_materials() {
+        let img = [1, 1, 1, 255, 9, 9, 9, 255, 1, 1, 1, 255, 0, 0, 0, 0];
+        assert_eq!(bill_of_materials(&img), vec![
+            PaletteColor { rgba: 0x010101FF, count: 2 },
+            PaletteColor { rgba: 0x090909FF, count: 1 },
+        ]);
+    }
+}
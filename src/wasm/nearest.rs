@@ -0,0 +1,143 @@
+//! Nearest-Neighbour Upscaling Engine
+//! Pixel-perfect scaling by integer or arbitrary factors, with pixel aspect
+//! correction and letterboxing into an explicit canvas.
+
+use crate::canvas::{rgba_parts, Letterbox};
+
+/// Nearest-neighbour configuration
+#[derive(Clone)]
+pub struct NearestConfig {
+    /// Explicit canvas width; `None` derives it from `scale`
+    pub output_width: Option<usize>,
+    /// Explicit canvas height; `None` derives it from `scale`
+    pub output_height: Option<usize>,
+    /// Width / height of one source pixel (1.0 = square, 8/7 for NES/SNES)
+    pub pixel_aspect: f32,
+    /// Snap the picture to whole multiples of the source size per axis, so
+    /// every source pixel covers the same number of output pixels
+    pub integer: bool,
+    /// Letterbox colour (RGBA)
+    pub background_color: u32,
+}
+
+impl Default for NearestConfig {
+    fn default() -> Self {
+        Self {
+            output_width: None,
+            output_height: None,
+            pixel_aspect: 1.0,
+            integer: true,
+            background_color: 0x000000FF,
+        }
+    }
+}
+
+/// Compute the output dimensions `nearest_upscale` will produce for the given config
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &NearestConfig,
+) -> (usize, usize) {
+    let layout = Letterbox::new(
+        src_w, src_h, scale,
+        config.output_width, config.output_height, config.pixel_aspect, config.integer,
+    );
+    (layout.out_w, layout.out_h)
+}
+
+pub fn nearest_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &NearestConfig,
+) -> Vec<u8> {
+    let layout = Letterbox::new(
+        src_w, src_h, scale,
+        config.output_width, config.output_height, config.pixel_aspect, config.integer,
+    );
+    let out_w = layout.out_w;
+    let Some(len) = layout.buffer_len() else {
+        return Vec::new();
+    };
+    let mut output = vec![0u8; len];
+    if src_w == 0 || src_h == 0 {
+        return output;
+    }
+
+    let bg = rgba_parts(config.background_color);
+
+    // Source column of every output column (None = letterbox)
+    let columns: Vec<Option<usize>> = (0..out_w)
+        .map(|x| layout.source_x(x, src_w).map(|u| (u as usize).min(src_w - 1)))
+        .collect();
+
+    for (y, out_row) in output.chunks_exact_mut(out_w * 4).enumerate() {
+        let Some(v) = layout.source_y(y, src_h) else {
+            out_row.chunks_exact_mut(4).for_each(|px| px.copy_from_slice(&bg));
+            continue;
+        };
+        let src_row = &input[(v as usize).min(src_h - 1) * src_w * 4..][..src_w * 4];
+
+        for (px, column) in out_row.chunks_exact_mut(4).zip(&columns) {
+            match column {
+                Some(sx) => px.copy_from_slice(&src_row[sx * 4..sx * 4 + 4]),
+                None => px.copy_from_slice(&bg),
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> Vec<u8> {
+        // 2x2: red, green / blue, white
+        vec![
+            255, 0, 0, 255, 0, 255, 0, 255,
+            0, 0, 255, 255, 255, 255, 255, 255,
+        ]
+    }
+
+    #[test]
+    fn test_integer_scale_is_exact() {
+        let out = nearest_upscale(&checker(), 2, 2, 3, &NearestConfig::default());
+        assert_eq!(out.len(), 6 * 6 * 4);
+        let px = |x: usize, y: usize| &out[(y * 6 + x) * 4..(y * 6 + x) * 4 + 4];
+        for y in 0..6 {
+            for x in 0..6 {
+                let expected = &checker()[((y / 3) * 2 + x / 3) * 4..][..4];
+                assert_eq!(px(x, y), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_canvas_and_pixel_aspect() {
+        // Integer snapping letterboxes a 2x2 image into 7x5 at 2x
+        let config = NearestConfig {
+            output_width: Some(7),
+            output_height: Some(5),
+            ..NearestConfig::default()
+        };
+        let out = nearest_upscale(&checker(), 2, 2, 1, &config);
+        assert_eq!(&out[0..4], &[0, 0, 0, 255]);
+        assert_eq!(&out[(7 + 1) * 4..(7 + 1) * 4 + 4], &[255, 0, 0, 255]);
+
+        // Non-integer fills the canvas height exactly (5x5 picture at x = 1)
+        let smooth = NearestConfig { integer: false, ..config };
+        let out = nearest_upscale(&checker(), 2, 2, 1, &smooth);
+        assert_eq!(&out[(4 * 7) * 4..][..4], &[0, 0, 0, 255]);
+        assert_eq!(&out[(4 * 7 + 1) * 4..][..4], &[0, 0, 255, 255]);
+        assert_eq!(&out[(4 * 7 + 5) * 4..][..4], &[255, 255, 255, 255]);
+        assert_eq!(&out[(4 * 7 + 6) * 4..][..4], &[0, 0, 0, 255]);
+
+        // Pixel aspect widens the natural size
+        let wide = NearestConfig { pixel_aspect: 2.0, ..NearestConfig::default() };
+        assert_eq!(get_output_dimensions(2, 2, 3, &wide), (12, 6));
+    }
+}
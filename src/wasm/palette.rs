@@ -0,0 +1,298 @@
+//! Palette Extraction and Colour Quantisation
+//! Exact palettes with pixel counts, reduction to N colours by median cut or
+//! k-means, and remapping of images onto a fixed palette.
+//!
+//! Colours are compared in the same YCbCr space the xBRZ engine uses
+//! (`dist_ycbcr`), with alpha as a fourth axis so translucent colours do not
+//! merge with opaque ones. Fully transparent pixels all count as
+//! `0x00000000` and never take a palette slot.
+
+use std::collections::HashMap;
+
+use crate::canvas::rgba_parts;
+use crate::xbrz::ycbcr_lookup::{dist_ycbcr, ycbcr_diff, REC_2020};
+
+/// Quantisation algorithm
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum QuantizeMethod {
+    /// Recursively split the colour box with the widest spread at its median
+    #[default]
+    MedianCut = 0,
+    /// Median cut refined by k-means (Lloyd) iterations
+    KMeans = 1,
+}
+
+/// One palette entry with the number of pixels using it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaletteColor {
+    /// Colour as `0xRRGGBBAA`
+    pub rgba: u32,
+    pub count: usize,
+}
+
+/// Upper bound on k-means refinement passes
+const KMEANS_ITERATIONS: usize = 16;
+
+/// Fully transparent pixels collapse to one colour
+#[inline]
+fn normalize(px: &[u8]) -> [u8; 4] {
+    if px[3] == 0 { [0; 4] } else { [px[0], px[1], px[2], px[3]] }
+}
+
+/// Perceptual distance: xBRZ's YCbCr distance plus the alpha difference
+#[inline]
+fn distance(a: [u8; 4], b: [u8; 4]) -> f64 {
+    let d = dist_ycbcr(
+        a[0] as i16 - b[0] as i16,
+        a[1] as i16 - b[1] as i16,
+        a[2] as i16 - b[2] as i16,
+    );
+    let da = a[3] as f64 - b[3] as f64;
+    (d * d + da * da).sqrt()
+}
+
+/// Position of a colour on the (Y, Cb, Cr, alpha) axes
+#[inline]
+fn coords(c: [u8; 4]) -> [f64; 4] {
+    let [y, cb, cr] = ycbcr_diff(c[0] as i16, c[1] as i16, c[2] as i16, REC_2020);
+    [y, cb, cr, c[3] as f64]
+}
+
+/// Exact palette of `input`, most used colours first
+pub fn extract_palette(input: &[u8]) -> Vec<PaletteColor> {
+    let mut counts: HashMap<[u8; 4], usize> = HashMap::new();
+    for px in input.chunks_exact(4) {
+        *counts.entry(normalize(px)).or_default() += 1;
+    }
+
+    let mut palette: Vec<PaletteColor> = counts
+        .into_iter()
+        .map(|(c, count)| PaletteColor { rgba: u32::from_be_bytes(c), count })
+        .collect();
+    palette.sort_by(|a, b| b.count.cmp(&a.count).then(a.rgba.cmp(&b.rgba)));
+    palette
+}
+
+/// Count-weighted mean of a set of colours
+fn weighted_mean(colors: &[([u8; 4], usize)]) -> [u8; 4] {
+    let mut sum = [0.0f64; 4];
+    let mut total = 0.0;
+    for &(c, n) in colors {
+        for (s, v) in sum.iter_mut().zip(c) {
+            *s += v as f64 * n as f64;
+        }
+        total += n as f64;
+    }
+    sum.map(|s| (s / total.max(1.0)).round() as u8)
+}
+
+/// Box of colours for median cut
+struct ColorBox {
+    colors: Vec<([u8; 4], usize)>,
+    /// Axis with the widest spread and its extent
+    axis: usize,
+    spread: f64,
+}
+
+impl ColorBox {
+    fn new(colors: Vec<([u8; 4], usize)>) -> Self {
+        let mut lo = [f64::INFINITY; 4];
+        let mut hi = [f64::NEG_INFINITY; 4];
+        for &(c, _) in &colors {
+            for (axis, v) in coords(c).into_iter().enumerate() {
+                lo[axis] = lo[axis].min(v);
+                hi[axis] = hi[axis].max(v);
+            }
+        }
+        let (axis, spread) = (0..4)
+            .map(|axis| (axis, hi[axis] - lo[axis]))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, 0.0));
+        Self { colors, axis, spread }
+    }
+
+    /// Split at the count-weighted median along the widest axis
+    fn split(mut self) -> (Self, Self) {
+        let axis = self.axis;
+        self.colors.sort_by(|a, b| coords(a.0)[axis].total_cmp(&coords(b.0)[axis]));
+
+        let total: usize = self.colors.iter().map(|&(_, n)| n).sum();
+        let mut seen = 0;
+        let mut at = 1;
+        for (i, &(_, n)) in self.colors.iter().enumerate() {
+            seen += n;
+            if seen * 2 >= total {
+                at = i + 1;
+                break;
+            }
+        }
+        let at = at.clamp(1, self.colors.len() - 1);
+        let upper = self.colors.split_off(at);
+        (Self::new(self.colors), Self::new(upper))
+    }
+}
+
+fn median_cut(colors: Vec<([u8; 4], usize)>, n: usize) -> Vec<[u8; 4]> {
+    let mut boxes = vec![ColorBox::new(colors)];
+    while boxes.len() < n {
+        let Some(widest) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.colors.len() > 1)
+            .max_by(|a, b| a.1.spread.total_cmp(&b.1.spread))
+            .map(|(i, _)| i)
+        else {
+            break;
+        };
+        let (a, b) = boxes.swap_remove(widest).split();
+        boxes.push(a);
+        boxes.push(b);
+    }
+    boxes.iter().map(|b| weighted_mean(&b.colors)).collect()
+}
+
+/// Index of the palette colour perceptually closest to `c`
+pub(crate) fn nearest(palette: &[[u8; 4]], c: [u8; 4]) -> usize {
+    (0..palette.len())
+        .min_by(|&a, &b| distance(palette[a], c).total_cmp(&distance(palette[b], c)))
+        .unwrap_or(0)
+}
+
+fn k_means(colors: &[([u8; 4], usize)], mut centroids: Vec<[u8; 4]>) -> Vec<[u8; 4]> {
+    let mut assignment = vec![usize::MAX; colors.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut changed = false;
+        for (slot, &(c, _)) in assignment.iter_mut().zip(colors) {
+            let best = nearest(&centroids, c);
+            changed |= *slot != best;
+            *slot = best;
+        }
+        if !changed {
+            break;
+        }
+
+        for (k, centroid) in centroids.iter_mut().enumerate() {
+            let members: Vec<([u8; 4], usize)> = colors
+                .iter()
+                .zip(&assignment)
+                .filter(|&(_, &a)| a == k)
+                .map(|(&m, _)| m)
+                .collect();
+            // Empty clusters keep their previous centre
+            if !members.is_empty() {
+                *centroid = weighted_mean(&members);
+            }
+        }
+    }
+    centroids
+}
+
+/// Reduce the colours of `input` to at most `max_colors` (transparent pixels excluded)
+pub fn quantize(input: &[u8], max_colors: usize, method: QuantizeMethod) -> Vec<u32> {
+    let colors: Vec<([u8; 4], usize)> = extract_palette(input)
+        .into_iter()
+        .map(|p| (rgba_parts(p.rgba), p.count))
+        .filter(|(c, _)| c[3] > 0)
+        .collect();
+    if colors.is_empty() || max_colors == 0 {
+        return vec![];
+    }
+    if colors.len() <= max_colors {
+        return colors.iter().map(|&(c, _)| u32::from_be_bytes(c)).collect();
+    }
+
+    let mut palette = median_cut(colors.clone(), max_colors);
+    if method == QuantizeMethod::KMeans {
+        palette = k_means(&colors, palette);
+    }
+    palette.sort_unstable();
+    palette.dedup();
+    palette.into_iter().map(u32::from_be_bytes).collect()
+}
+
+/// Replace every pixel by the nearest palette colour; transparent pixels stay
+/// transparent and an empty palette leaves the image unchanged
+pub fn remap_to_palette(input: &[u8], palette: &[u32]) -> Vec<u8> {
+    if palette.is_empty() {
+        return input.to_vec();
+    }
+    let palette: Vec<[u8; 4]> = palette.iter().map(|&c| rgba_parts(c)).collect();
+
+    let mut cache: HashMap<[u8; 4], [u8; 4]> = HashMap::new();
+    input
+        .chunks_exact(4)
+        .flat_map(|px| {
+            let c = normalize(px);
+            if c[3] == 0 {
+                return c;
+            }
+            *cache.entry(c).or_insert_with(|| palette[nearest(&palette, c)])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Four noisy clusters of 16 shades each
+    fn clustered() -> Vec<u8> {
+        let centres = [[220, 40, 40], [40, 200, 60], [30, 30, 210], [240, 240, 240]];
+        (0..64)
+            .flat_map(|i| {
+                let [r, g, b] = centres[i % 4];
+                let d = (i / 4) as u8;
+                [r + d % 4, g + d / 4, b + d % 3, 255]
+            })
+            .collect()
+    }
+
+    fn error(img: &[u8], palette: &[u32]) -> f64 {
+        let mapped = remap_to_palette(img, palette);
+        img.chunks(4)
+            .zip(mapped.chunks(4))
+            .map(|(a, b)| distance(normalize(a), normalize(b)))
+            .sum()
+    }
+
+    #[test]
+    fn test_extract_counts_and_order() {
+        let img = [
+            1, 2, 3, 255, 1, 2, 3, 255, 9, 9, 9, 128, 5, 5, 5, 0, 7, 7, 7, 0,
+        ];
+        let palette = extract_palette(&img);
+        assert_eq!(palette, vec![
+            PaletteColor { rgba: 0x00000000, count: 2 },
+            PaletteColor { rgba: 0x010203FF, count: 2 },
+            PaletteColor { rgba: 0x09090980, count: 1 },
+        ]);
+    }
+
+    #[test]
+    fn test_quantize_finds_clusters() {
+        let img = clustered();
+        assert_eq!(extract_palette(&img).len(), 64);
+
+        for method in [QuantizeMethod::MedianCut, QuantizeMethod::KMeans] {
+            let palette = quantize(&img, 4, method);
+            assert_eq!(palette.len(), 4);
+            // Every pixel lands within a few steps of its cluster
+            let mapped = remap_to_palette(&img, &palette);
+            assert!(img.iter().zip(&mapped).all(|(&a, &b)| a.abs_diff(b) <= 4));
+        }
+
+        let cut = quantize(&img, 3, QuantizeMethod::MedianCut);
+        let refined = quantize(&img, 3, QuantizeMethod::KMeans);
+        assert!(error(&img, &refined) <= error(&img, &cut));
+    }
+
+    #[test]
+    fn test_remap_keeps_transparency_and_small_palettes() {
+        let img = [250, 10, 10, 255, 0, 0, 0, 0, 10, 10, 240, 255];
+        assert_eq!(quantize(&img, 8, QuantizeMethod::KMeans), vec![0x0A0AF0FF, 0xFA0A0AFF]);
+
+        let mapped = remap_to_palette(&img, &[0xFF0000FF, 0x0000FFFF]);
+        assert_eq!(mapped, [255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 255, 255]);
+        assert_eq!(remap_to_palette(&img, &[]), img);
+    }
+}
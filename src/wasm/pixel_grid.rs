@@ -0,0 +1,243 @@
+//! Native Resolution Detection
+//! Recovers the original pixel grid of art that was already upscaled with
+//! nearest neighbour (possibly JPEG-compressed afterwards), so the engines can
+//! work on clean 1:1 source pixels.
+//!
+//! Each axis is handled on its own: the colour change between neighbouring
+//! columns (or rows) is summed over the image, and the block size and phase
+//! whose grid lines collect clearly more change than the positions between
+//! them wins. Every block is then reduced to its dominant colour, which is
+//! robust against compression noise and ringing near block edges.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::xbrz::pixel::Rgba8;
+use crate::xbrz::ycbcr_lookup::YCbCrLookup;
+
+/// Largest block size considered
+const MAX_CELL_SIZE: usize = 256;
+/// Grid lines must carry this many times the change found between them
+const GRID_CONTRAST: f32 = 2.0;
+/// Quantisation step when looking for a block's dominant colour (4 bits per channel)
+const COLOR_BUCKET: u8 = 16;
+
+/// Detected (or user-specified) grid of source pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelGrid {
+    /// Block size in image pixels
+    pub cell_width: usize,
+    pub cell_height: usize,
+    /// Position of a grid line within `0..cell_*`; a non-zero offset leaves a
+    /// partial block at the left / top edge
+    pub offset_x: usize,
+    pub offset_y: usize,
+}
+
+impl Default for PixelGrid {
+    fn default() -> Self {
+        Self {
+            cell_width: 1,
+            cell_height: 1,
+            offset_x: 0,
+            offset_y: 0,
+        }
+    }
+}
+
+impl PixelGrid {
+    /// Source pixels covered by the grid on an image of the given size
+    pub fn dimensions(&self, width: usize, height: usize) -> (usize, usize) {
+        (
+            cell_count(width, self.cell_width, self.offset_x),
+            cell_count(height, self.cell_height, self.offset_y),
+        )
+    }
+}
+
+/// End of the first (possibly partial) block along an axis
+fn first_line(size: usize, offset: usize) -> usize {
+    match offset % size {
+        0 => size,
+        offset => offset,
+    }
+}
+
+fn cell_count(len: usize, size: usize, offset: usize) -> usize {
+    let size = size.max(1);
+    if len == 0 {
+        return 0;
+    }
+    1 + len.saturating_sub(first_line(size, offset)).div_ceil(size)
+}
+
+/// Image range of block `index` along one axis
+fn cell_range(index: usize, len: usize, size: usize, offset: usize) -> Range<usize> {
+    let size = size.max(1);
+    let end = first_line(size, offset) + index * size;
+    end.saturating_sub(size)..end.min(len)
+}
+
+/// Best `(size, phase)` for a profile where `change[i]` is the colour change
+/// between positions `i` and `i + 1`
+fn detect_axis(change: &[f32]) -> (usize, usize) {
+    let len = change.len() + 1;
+    let mut best = (1, 0);
+    let mut best_score = 0.0;
+    let total: f32 = change.iter().sum();
+
+    for size in 2..=(len / 2).min(MAX_CELL_SIZE) {
+        for phase in 0..size {
+            // Grid lines sit before positions phase, phase + size, ...
+            let lines = (first_line(size, phase)..len).step_by(size);
+            let on_count = lines.len();
+            let on: f32 = lines.map(|i| change[i - 1]).sum();
+            let off_count = change.len() - on_count;
+            if on_count == 0 || off_count == 0 {
+                continue;
+            }
+
+            let mean_on = on / on_count as f32;
+            let mean_off = (total - on) / off_count as f32;
+            let score = mean_on - mean_off;
+            if mean_on > GRID_CONTRAST * mean_off && score > best_score {
+                best = (size, phase);
+                best_score = score;
+            }
+        }
+    }
+    best
+}
+
+/// Detect the block size and phase of nearest-upscaled art
+pub fn detect_pixel_grid(input: &[u8], width: usize, height: usize) -> PixelGrid {
+    if width < 2 && height < 2 {
+        return PixelGrid::default();
+    }
+    assert_eq!(input.len(), width * height * 4);
+    let (_, pixels, _) = unsafe { input.align_to::<Rgba8>() };
+    let ycbcr = YCbCrLookup::instance();
+
+    let mut columns = vec![0.0f32; width.saturating_sub(1)];
+    let mut rows = vec![0.0f32; height.saturating_sub(1)];
+    for y in 0..height {
+        for x in 0..width {
+            let p = pixels[y * width + x];
+            if x + 1 < width {
+                columns[x] += ycbcr.dist(p, pixels[y * width + x + 1]);
+            }
+            if y + 1 < height {
+                rows[y] += ycbcr.dist(p, pixels[(y + 1) * width + x]);
+            }
+        }
+    }
+
+    let (cell_width, offset_x) = detect_axis(&columns);
+    let (cell_height, offset_y) = detect_axis(&rows);
+    PixelGrid { cell_width, cell_height, offset_x, offset_y }
+}
+
+/// Reduce every block of `grid` to its dominant colour; returns the image
+/// and its dimensions
+pub fn downscale_to_grid(
+    input: &[u8],
+    width: usize,
+    height: usize,
+    grid: &PixelGrid,
+) -> (Vec<u8>, usize, usize) {
+    let (cols, rows) = grid.dimensions(width, height);
+    let mut output = Vec::with_capacity(cols * rows * 4);
+    if cols == 0 || rows == 0 {
+        return (output, cols, rows);
+    }
+    assert_eq!(input.len(), width * height * 4);
+
+    let mut buckets: HashMap<[u8; 4], ([u32; 4], u32)> = HashMap::new();
+    for row in 0..rows {
+        let ys = cell_range(row, height, grid.cell_height, grid.offset_y);
+        for col in 0..cols {
+            let xs = cell_range(col, width, grid.cell_width, grid.offset_x);
+
+            // Most common quantised colour, averaged over the pixels that fall into it
+            buckets.clear();
+            for y in ys.clone() {
+                for px in input[(y * width + xs.start) * 4..(y * width + xs.end) * 4].chunks_exact(4) {
+                    let key = [px[0], px[1], px[2], px[3]].map(|c| c / COLOR_BUCKET);
+                    let (sum, count) = buckets.entry(key).or_default();
+                    for (s, &c) in sum.iter_mut().zip(px) {
+                        *s += c as u32;
+                    }
+                    *count += 1;
+                }
+            }
+            let (sum, count) = buckets
+                .iter()
+                .max_by_key(|(key, (_, count))| (*count, std::cmp::Reverse(**key)))
+                .map(|(_, v)| *v)
+                .unwrap_or_default();
+            output.extend(sum.map(|s| ((s + count / 2) / count.max(1)) as u8));
+        }
+    }
+
+    (output, cols, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn art(w: usize, h: usize) -> Vec<u8> {
+        (0..w * h)
+            .flat_map(|i| [(i * 53 % 256) as u8, (i * 97 % 256) as u8, (i * 151 % 256) as u8, 255])
+            .collect()
+    }
+
+    /// Nearest upscale by `s`, dropping the first `crop` columns and rows
+    fn blow_up(src: &[u8], w: usize, h: usize, s: usize, crop: usize) -> (Vec<u8>, usize, usize) {
+        let (ow, oh) = (w * s - crop, h * s - crop);
+        let mut out = Vec::with_capacity(ow * oh * 4);
+        for y in crop..h * s {
+            for x in crop..w * s {
+                out.extend_from_slice(&src[((y / s) * w + x / s) * 4..][..4]);
+            }
+        }
+        (out, ow, oh)
+    }
+
+    #[test]
+    fn test_recovers_grid_and_phase() {
+        let src = art(5, 4);
+        let (big, w, h) = blow_up(&src, 5, 4, 3, 2);
+        let grid = detect_pixel_grid(&big, w, h);
+        assert_eq!(grid, PixelGrid { cell_width: 3, cell_height: 3, offset_x: 1, offset_y: 1 });
+
+        let (small, cols, rows) = downscale_to_grid(&big, w, h, &grid);
+        assert_eq!((cols, rows), (5, 4));
+        assert_eq!(small, src);
+    }
+
+    #[test]
+    fn test_survives_compression_noise() {
+        let src = art(6, 5);
+        let (mut big, w, h) = blow_up(&src, 6, 5, 4, 0);
+        for (i, c) in big.iter_mut().enumerate().filter(|(i, _)| i % 4 != 3) {
+            let noise = (i.wrapping_mul(2_654_435_761) >> 7) % 13;
+            *c = c.saturating_add(noise as u8).saturating_sub(6);
+        }
+
+        let grid = detect_pixel_grid(&big, w, h);
+        assert_eq!((grid.cell_width, grid.cell_height), (4, 4));
+        let (small, cols, rows) = downscale_to_grid(&big, w, h, &grid);
+        assert_eq!((cols, rows), (6, 5));
+        assert!(small.iter().zip(&src).all(|(&a, &b)| a.abs_diff(b) <= 8));
+    }
+
+    #[test]
+    fn test_native_art_is_left_alone() {
+        let src = art(7, 6);
+        assert_eq!(detect_pixel_grid(&src, 7, 6), PixelGrid::default());
+        let (same, cols, rows) = downscale_to_grid(&src, 7, 6, &PixelGrid::default());
+        assert_eq!((cols, rows), (7, 6));
+        assert_eq!(same, src);
+    }
+}
@@ -0,0 +1,175 @@
+//! Sharp Bilinear Upscaling Engine
+//! Nearest-neighbour prescale by the largest integer factor that fits, then
+//! bilinear filtering to the final size. Pixels stay crisp while non-integer
+//! factors get an even one-pixel blend instead of uneven column widths.
+
+use crate::canvas::{rgba_parts, Letterbox};
+
+/// Sharp bilinear configuration
+#[derive(Clone)]
+pub struct SharpBilinearConfig {
+    /// Explicit canvas width; `None` derives it from `scale`
+    pub output_width: Option<usize>,
+    /// Explicit canvas height; `None` derives it from `scale`
+    pub output_height: Option<usize>,
+    /// Width / height of one source pixel (1.0 = square, 8/7 for NES/SNES)
+    pub pixel_aspect: f32,
+    /// Letterbox colour (RGBA)
+    pub background_color: u32,
+}
+
+impl Default for SharpBilinearConfig {
+    fn default() -> Self {
+        Self {
+            output_width: None,
+            output_height: None,
+            pixel_aspect: 1.0,
+            background_color: 0x000000FF,
+        }
+    }
+}
+
+/// Per-axis sample: the two source texels to blend and the weight of the second
+#[derive(Clone, Copy)]
+struct Tap {
+    i0: usize,
+    i1: usize,
+    t: f32,
+}
+
+/// Taps for every output column (or row); `None` in the letterbox
+fn axis_taps(
+    out_len: usize,
+    src_len: usize,
+    content_len: f32,
+    coord: impl Fn(usize) -> Option<f32>,
+) -> Vec<Option<Tap>> {
+    // Integer prescale factor: the largest whole multiple not exceeding the final size
+    let prescale = (content_len / src_len as f32).floor().max(1.0);
+    let texels = src_len as f32 * prescale;
+    let last = src_len - 1;
+
+    (0..out_len)
+        .map(|i| {
+            coord(i).map(|u| {
+                // Position in the prescaled image, relative to texel centres
+                let p = (u * prescale - 0.5).clamp(0.0, texels - 1.0);
+                let k = p.floor();
+                Tap {
+                    i0: ((k / prescale) as usize).min(last),
+                    i1: (((k + 1.0) / prescale) as usize).min(last),
+                    t: p - k,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Compute the output dimensions `sharp_bilinear_upscale` will produce for the given config
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &SharpBilinearConfig,
+) -> (usize, usize) {
+    let layout = Letterbox::new(
+        src_w, src_h, scale,
+        config.output_width, config.output_height, config.pixel_aspect, false,
+    );
+    (layout.out_w, layout.out_h)
+}
+
+pub fn sharp_bilinear_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &SharpBilinearConfig,
+) -> Vec<u8> {
+    let layout = Letterbox::new(
+        src_w, src_h, scale,
+        config.output_width, config.output_height, config.pixel_aspect, false,
+    );
+    let (out_w, out_h) = (layout.out_w, layout.out_h);
+    let Some(len) = layout.buffer_len() else {
+        return Vec::new();
+    };
+    let mut output = vec![0u8; len];
+    if src_w == 0 || src_h == 0 {
+        return output;
+    }
+
+    let bg = rgba_parts(config.background_color);
+    let cols = axis_taps(out_w, src_w, layout.content_w, |x| layout.source_x(x, src_w));
+    let rows = axis_taps(out_h, src_h, layout.content_h, |y| layout.source_y(y, src_h));
+
+    let texel = |x: usize, y: usize| -> [f32; 4] {
+        let idx = (y * src_w + x) * 4;
+        let a = input[idx + 3] as f32;
+        // Premultiplied so transparent texels do not bleed their colour
+        [input[idx] as f32 * a, input[idx + 1] as f32 * a, input[idx + 2] as f32 * a, a]
+    };
+    let lerp = |a: [f32; 4], b: [f32; 4], t: f32| -> [f32; 4] {
+        [
+            a[0] + (b[0] - a[0]) * t,
+            a[1] + (b[1] - a[1]) * t,
+            a[2] + (b[2] - a[2]) * t,
+            a[3] + (b[3] - a[3]) * t,
+        ]
+    };
+
+    for (out_row, row) in output.chunks_exact_mut(out_w * 4).zip(&rows) {
+        for (px, col) in out_row.chunks_exact_mut(4).zip(&cols) {
+            let (Some(row), Some(col)) = (row, col) else {
+                px.copy_from_slice(&bg);
+                continue;
+            };
+
+            let top = lerp(texel(col.i0, row.i0), texel(col.i1, row.i0), col.t);
+            let bottom = lerp(texel(col.i0, row.i1), texel(col.i1, row.i1), col.t);
+            let [r, g, b, a] = lerp(top, bottom, row.t);
+
+            if a > 0.0 {
+                px.copy_from_slice(&[
+                    (r / a).round().min(255.0) as u8,
+                    (g / a).round().min(255.0) as u8,
+                    (b / a).round().min(255.0) as u8,
+                    a.round() as u8,
+                ]);
+            } else {
+                px.copy_from_slice(&[0, 0, 0, 0]);
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blends_only_at_pixel_boundaries() {
+        // Black | white, upscaled 2 -> 5 columns (prescale 2x, then 4 -> 5)
+        let img = [0, 0, 0, 255, 255, 255, 255, 255];
+        let config = SharpBilinearConfig {
+            output_width: Some(5),
+            ..SharpBilinearConfig::default()
+        };
+        assert_eq!(get_output_dimensions(2, 1, 1, &config), (5, 3));
+
+        let out = sharp_bilinear_upscale(&img, 2, 1, 1, &config);
+        let reds: Vec<u8> = out[..5 * 4].chunks(4).map(|p| p[0]).collect();
+        assert_eq!(reds[0], 0);
+        assert_eq!(reds[4], 255);
+        assert!(reds[1] < 20);
+        assert!(reds[2] > 0 && reds[2] < 255);
+        assert!(reds.windows(2).all(|w| w[0] <= w[1]));
+
+        // Integer factors reproduce nearest neighbour exactly
+        let exact = sharp_bilinear_upscale(&img, 2, 1, 3, &SharpBilinearConfig::default());
+        let reds: Vec<u8> = exact[..6 * 4].chunks(4).map(|p| p[0]).collect();
+        assert_eq!(reds, [0, 0, 0, 255, 255, 255]);
+    }
+}
@@ -0,0 +1,340 @@
+//! Sprite Effects Stage
+//! Outline, drop shadow and glow around the alpha mask of an RGBA sprite.
+//!
+//! The stage works in the pixels of whatever buffer it is given, so it can
+//! run before an upscaler (in source pixels) or after one (in output pixels);
+//! [`SpriteFxConfig::scaled`] converts source-pixel settings for the latter.
+//! Layers are composited premultiplied, from the bottom: glow, shadow, outer
+//! outline, sprite, inner outline. Shadow and glow follow the sprite together
+//! with its outer outline.
+
+use crate::canvas::rgba_parts;
+
+/// Where the outline is drawn relative to the alpha mask
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutlinePlacement {
+    /// Around the sprite, on transparent pixels
+    Outer = 0,
+    /// Along the sprite's own edge pixels
+    Inner = 1,
+}
+
+/// Which neighbours a thickness step reaches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Edge neighbours only: rounded-looking diamond corners
+    Four = 0,
+    /// Edge and corner neighbours: square corners
+    Eight = 1,
+}
+
+/// Sprite effects configuration
+#[derive(Clone, Copy)]
+pub struct SpriteFxConfig {
+    pub outline_color: u32,
+    /// Outline thickness in pixels (0 = no outline)
+    pub outline_thickness: usize,
+    pub outline_placement: OutlinePlacement,
+    pub connectivity: Connectivity,
+    /// Shadow colour as `0xRRGGBBAA` (alpha 0 = no shadow)
+    pub shadow_color: u32,
+    pub shadow_offset_x: i32,
+    pub shadow_offset_y: i32,
+    pub glow_color: u32,
+    /// Glow radius in pixels (0 = no glow)
+    pub glow_radius: usize,
+    /// Glow opacity multiplier
+    pub glow_strength: f32,
+    /// Alpha at or above which a pixel counts as part of the sprite
+    pub alpha_threshold: u8,
+    /// Grow the canvas so outline, shadow and glow are not clipped
+    pub expand_canvas: bool,
+}
+
+impl Default for SpriteFxConfig {
+    fn default() -> Self {
+        Self {
+            outline_color: 0x000000FF,
+            outline_thickness: 1,
+            outline_placement: OutlinePlacement::Outer,
+            connectivity: Connectivity::Eight,
+            shadow_color: 0x00000000,
+            shadow_offset_x: 1,
+            shadow_offset_y: 1,
+            glow_color: 0xFFFFFFFF,
+            glow_radius: 0,
+            glow_strength: 1.0,
+            alpha_threshold: 128,
+            expand_canvas: false,
+        }
+    }
+}
+
+impl SpriteFxConfig {
+    /// Settings given in source pixels, converted for a buffer upscaled by `scale`
+    /// (saturating rather than overflowing for extreme values)
+    pub fn scaled(&self, scale: usize) -> Self {
+        let scale = scale.max(1);
+        let offset_scale = i32::try_from(scale).unwrap_or(i32::MAX);
+        Self {
+            outline_thickness: self.outline_thickness.saturating_mul(scale),
+            shadow_offset_x: self.shadow_offset_x.saturating_mul(offset_scale),
+            shadow_offset_y: self.shadow_offset_y.saturating_mul(offset_scale),
+            glow_radius: self.glow_radius.saturating_mul(scale),
+            ..*self
+        }
+    }
+
+    /// Canvas growth `[left, top, right, bottom]` needed to avoid clipping
+    pub fn padding(&self) -> [usize; 4] {
+        if !self.expand_canvas {
+            return [0; 4];
+        }
+        let outline = match self.outline_placement {
+            OutlinePlacement::Outer => self.outline_thickness,
+            OutlinePlacement::Inner => 0,
+        };
+        let around = outline.saturating_add(self.glow_radius);
+        let shadow = |offset: i32| {
+            if rgba_parts(self.shadow_color)[3] > 0 { (offset.max(0) as usize).saturating_add(outline) } else { 0 }
+        };
+        [
+            around.max(shadow(self.shadow_offset_x.saturating_neg())),
+            around.max(shadow(self.shadow_offset_y.saturating_neg())),
+            around.max(shadow(self.shadow_offset_x)),
+            around.max(shadow(self.shadow_offset_y)),
+        ]
+    }
+}
+
+pub fn get_output_dimensions(src_w: usize, src_h: usize, config: &SpriteFxConfig) -> (usize, usize) {
+    let [left, top, right, bottom] = config.padding();
+    (src_w.saturating_add(left).saturating_add(right), src_h.saturating_add(top).saturating_add(bottom))
+}
+
+/// Grow `mask` by `steps` neighbour steps; positions outside the canvas count as `outside`
+fn dilate(mask: &[bool], width: usize, height: usize, steps: usize, connectivity: Connectivity, outside: bool) -> Vec<bool> {
+    let neighbours: &[(isize, isize)] = match connectivity {
+        Connectivity::Four => &[(1, 0), (-1, 0), (0, 1), (0, -1)],
+        Connectivity::Eight => &[(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)],
+    };
+    let mut current = mask.to_vec();
+    for _ in 0..steps {
+        current = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                current[i]
+                    || neighbours.iter().any(|&(dx, dy)| {
+                        match (x.checked_add_signed(dx), y.checked_add_signed(dy)) {
+                            (Some(nx), Some(ny)) if nx < width && ny < height => current[ny * width + nx],
+                            _ => outside,
+                        }
+                    })
+            })
+            .collect();
+    }
+    current
+}
+
+/// Separable Gaussian blur of a single channel with sigma `radius / 2`
+fn blur(values: &[f32], width: usize, height: usize, radius: usize) -> Vec<f32> {
+    let sigma = (radius as f32 * 0.5).max(0.5);
+    let kernel: Vec<f32> = (-(radius as isize)..=radius as isize)
+        .map(|d| (-(d * d) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = kernel.iter().sum();
+    let kernel: Vec<f32> = kernel.iter().map(|k| k / total).collect();
+
+    let pass = |src: &[f32], horizontal: bool| -> Vec<f32> {
+        (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                kernel
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(k, w)| {
+                        let d = k as isize - radius as isize;
+                        let (nx, ny) = if horizontal { (x.checked_add_signed(d)?, y) } else { (x, y.checked_add_signed(d)?) };
+                        (nx < width && ny < height).then(|| src[ny * width + nx] * w)
+                    })
+                    .sum()
+            })
+            .collect()
+    };
+    pass(&pass(values, true), false)
+}
+
+/// Premultiplied source-over
+#[inline]
+fn over(top: [f32; 4], bottom: [f32; 4]) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| top[i] + bottom[i] * (1.0 - top[3]))
+}
+
+/// Premultiplied colour of `color` at opacity `alpha`
+#[inline]
+fn tinted(color: [f32; 4], alpha: f32) -> [f32; 4] {
+    let a = color[3] * alpha.clamp(0.0, 1.0);
+    [color[0] * a, color[1] * a, color[2] * a, a]
+}
+
+/// Apply outline, shadow and glow; the output is
+/// [`get_output_dimensions`] in size, with the sprite offset by the padding
+pub fn apply_sprite_fx(input: &[u8], src_w: usize, src_h: usize, config: &SpriteFxConfig) -> Vec<u8> {
+    assert_eq!(input.len(), src_w * src_h * 4);
+    let [left, top, _, _] = config.padding();
+    let (width, height) = get_output_dimensions(src_w, src_h, config);
+    let len = width * height;
+    let color = |c: u32| rgba_parts(c).map(|v| v as f32 / 255.0);
+
+    // Sprite on the (possibly grown) canvas, premultiplied
+    let mut sprite = vec![[0.0f32; 4]; len];
+    for y in 0..src_h {
+        for x in 0..src_w {
+            let px = &input[(y * src_w + x) * 4..][..4];
+            let a = px[3] as f32 / 255.0;
+            let f = |c: u8| c as f32 / 255.0 * a;
+            sprite[(y + top) * width + x + left] = [f(px[0]), f(px[1]), f(px[2]), a];
+        }
+    }
+    let threshold = config.alpha_threshold.max(1) as f32 / 255.0;
+    let mask: Vec<bool> = sprite.iter().map(|p| p[3] >= threshold).collect();
+
+    let thickness = config.outline_thickness;
+    let outline: Vec<bool> = match config.outline_placement {
+        _ if thickness == 0 => vec![false; len],
+        OutlinePlacement::Outer => dilate(&mask, width, height, thickness, config.connectivity, false)
+            .iter()
+            .zip(&mask)
+            .map(|(&grown, &inside)| grown && !inside)
+            .collect(),
+        OutlinePlacement::Inner => {
+            let outside: Vec<bool> = mask.iter().map(|&m| !m).collect();
+            dilate(&outside, width, height, thickness, config.connectivity, true)
+                .iter()
+                .zip(&mask)
+                .map(|(&near_edge, &inside)| near_edge && inside)
+                .collect()
+        }
+    };
+    let outline_color = color(config.outline_color);
+
+    // Silhouette that casts the shadow and the glow
+    let silhouette: Vec<f32> = (0..len)
+        .map(|i| {
+            let ring = config.outline_placement == OutlinePlacement::Outer && outline[i];
+            if ring { sprite[i][3].max(outline_color[3]) } else { sprite[i][3] }
+        })
+        .collect();
+
+    let glow_color = color(config.glow_color);
+    let glow = (config.glow_radius > 0).then(|| blur(&silhouette, width, height, config.glow_radius));
+    let shadow_color = color(config.shadow_color);
+    let (sx, sy) = (config.shadow_offset_x as isize, config.shadow_offset_y as isize);
+
+    let mut output = vec![0u8; len * 4];
+    for (i, out) in output.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (i % width, i / width);
+        let mut c = [0.0f32; 4];
+
+        if let Some(glow) = &glow {
+            c = tinted(glow_color, glow[i] * config.glow_strength);
+        }
+        if shadow_color[3] > 0.0 {
+            let caster = match (x.checked_add_signed(-sx), y.checked_add_signed(-sy)) {
+                (Some(cx), Some(cy)) if cx < width && cy < height => silhouette[cy * width + cx],
+                _ => 0.0,
+            };
+            c = over(tinted(shadow_color, caster), c);
+        }
+        if outline[i] && config.outline_placement == OutlinePlacement::Outer {
+            c = over(tinted(outline_color, 1.0), c);
+        }
+        c = over(sprite[i], c);
+        if outline[i] && config.outline_placement == OutlinePlacement::Inner {
+            c = over(tinted(outline_color, 1.0), c);
+        }
+
+        let a = c[3].min(1.0);
+        for k in 0..3 {
+            let straight = if a > 0.0 { c[k] / a } else { 0.0 };
+            out[k] = (straight * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+        }
+        out[3] = (a * 255.0 + 0.5) as u8;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `w` x `h` transparent canvas with an opaque white block at `rect`
+    fn sprite(w: usize, h: usize, rect: (usize, usize, usize, usize)) -> Vec<u8> {
+        let (x0, y0, x1, y1) = rect;
+        (0..w * h)
+            .flat_map(|i| {
+                let (x, y) = (i % w, i / w);
+                if (x0..x1).contains(&x) && (y0..y1).contains(&y) { [255; 4] } else { [0; 4] }
+            })
+            .collect()
+    }
+
+    fn count(img: &[u8], px: [u8; 4]) -> usize {
+        img.chunks(4).filter(|p| *p == px).count()
+    }
+
+    #[test]
+    fn test_outer_outline_connectivity() {
+        let img = sprite(7, 7, (3, 3, 4, 4));
+        let outline = |connectivity, outline_thickness| {
+            let config = SpriteFxConfig { connectivity, outline_thickness, outline_color: 0xFF0000FF, ..SpriteFxConfig::default() };
+            count(&apply_sprite_fx(&img, 7, 7, &config), [255, 0, 0, 255])
+        };
+        assert_eq!(outline(Connectivity::Four, 1), 4);
+        assert_eq!(outline(Connectivity::Eight, 1), 8);
+        assert_eq!(outline(Connectivity::Four, 2), 12);
+        assert_eq!(outline(Connectivity::Eight, 2), 24);
+    }
+
+    #[test]
+    fn test_inner_outline_stays_inside() {
+        let img = sprite(7, 7, (1, 1, 6, 6));
+        let config = SpriteFxConfig {
+            outline_placement: OutlinePlacement::Inner,
+            outline_color: 0x0000FFFF,
+            ..SpriteFxConfig::default()
+        };
+        let out = apply_sprite_fx(&img, 7, 7, &config);
+        assert_eq!(count(&out, [0, 0, 255, 255]), 16);
+        assert_eq!(count(&out, [255; 4]), 9);
+        assert_eq!(count(&out, [0; 4]), 49 - 25);
+    }
+
+    #[test]
+    fn test_shadow_glow_and_canvas_growth() {
+        let img = sprite(2, 2, (0, 0, 2, 2));
+        let config = SpriteFxConfig {
+            outline_thickness: 0,
+            shadow_color: 0x000000FF,
+            shadow_offset_x: 2,
+            shadow_offset_y: 1,
+            expand_canvas: true,
+            ..SpriteFxConfig::default()
+        };
+        assert_eq!(config.padding(), [0, 0, 2, 1]);
+        let (w, h) = get_output_dimensions(2, 2, &config);
+        let out = apply_sprite_fx(&img, 2, 2, &config);
+        let at = |x: usize, y: usize| &out[(y * w + x) * 4..(y * w + x) * 4 + 4];
+        assert_eq!((w, h), (4, 3));
+        assert_eq!(at(0, 0), [255; 4]);
+        assert_eq!(at(3, 2), [0, 0, 0, 255]);
+        assert_eq!(at(0, 2), [0; 4]);
+
+        let glow = SpriteFxConfig { outline_thickness: 0, glow_radius: 3, expand_canvas: true, ..SpriteFxConfig::default() };
+        let (w, _) = get_output_dimensions(2, 2, &glow);
+        let out = apply_sprite_fx(&img, 2, 2, &glow);
+        let alpha = |x: usize| out[(3 * w + x) * 4 + 3];
+        assert!(alpha(2) > alpha(1) && alpha(1) > alpha(0), "glow fades with distance");
+        assert_eq!(glow.scaled(4).glow_radius, 12);
+    }
+}
@@ -0,0 +1,100 @@
+//! Tessellation Engines
+//! Non-square cell grids (triangles, bricks, diamonds, Voronoi cells) built on
+//! the HEX rendering model.
+//!
+//! Each engine only describes its grid: which source pixel covers an output
+//! position and how far that position is from the cell edge. Borders,
+//! background and anti-aliasing are shared by [`render`], so every engine
+//! takes the same options as `HexConfig`, with `border_thickness` in the same
+//! units (see `hex::border_band_pixels`).
+
+pub mod brick;
+pub mod diamond;
+pub mod triangle;
+pub mod voronoi;
+
+use crate::canvas::rgba_parts;
+use crate::hex::{border_band_pixels, supersample_pixel};
+
+/// A cell grid over the output image
+pub(crate) trait Tiling {
+    /// Output size in pixels
+    fn output_dimensions(&self) -> (usize, usize);
+
+    /// Source pixel `(col, row)` whose cell covers image-space position
+    /// `(x, y)` and the distance in pixels from the cell edge, or `None`
+    /// outside every cell
+    fn locate(&self, x: f32, y: f32) -> Option<(usize, usize, f32)>;
+}
+
+/// Border, background and anti-aliasing options shared by every engine
+pub(crate) struct Surface {
+    pub draw_borders: bool,
+    pub border_color: u32,
+    /// Border band in `HexConfig::border_thickness` units
+    pub border_thickness: usize,
+    pub background_color: u32,
+    pub supersample: usize,
+}
+
+/// Rasterize `tiling`, colouring each cell with its source pixel
+pub(crate) fn render(
+    tiling: &impl Tiling,
+    input: &[u8],
+    src_w: usize,
+    surface: &Surface,
+) -> Vec<u8> {
+    let (out_w, out_h) = tiling.output_dimensions();
+    let mut output = vec![0u8; out_w * out_h * 4];
+
+    let bg = rgba_parts(surface.background_color);
+    let border = rgba_parts(surface.border_color);
+    let border_px = if surface.draw_borders { border_band_pixels(surface.border_thickness as f32) } else { 0.0 };
+
+    let sample = |x: f32, y: f32| -> [u8; 4] {
+        match tiling.locate(x, y) {
+            Some((_, _, edge)) if edge < border_px => border,
+            Some((col, row, _)) => {
+                let idx = (row * src_w + col) * 4;
+                [input[idx], input[idx + 1], input[idx + 2], input[idx + 3]]
+            }
+            None => bg,
+        }
+    };
+
+    let n = surface.supersample.clamp(1, 8);
+    let offsets: Vec<f32> = (0..n).map(|i| (i as f32 + 0.5) / n as f32 - 0.5).collect();
+
+    for y in 0..out_h {
+        let y_f = y as f32 + 0.5;
+        for x in 0..out_w {
+            let x_f = x as f32 + 0.5;
+            let out_idx = (y * out_w + x) * 4;
+
+            // Only pixels straddling a cell edge or the inner border edge need subsamples
+            let near_edge = n > 1 && tiling.locate(x_f, y_f).is_none_or(|(_, _, edge)| {
+                edge < 1.0 || (border_px > 0.0 && (edge - border_px).abs() < 1.0)
+            });
+
+            let color = if near_edge {
+                supersample_pixel(&offsets, |dx, dy| sample(x_f + dx, y_f + dy))
+            } else {
+                sample(x_f, y_f)
+            };
+            output[out_idx..out_idx + 4].copy_from_slice(&color);
+        }
+    }
+
+    output
+}
+
+/// Distance from `p` to the nearest edge of triangle `tri`, for `p` inside it
+pub(crate) fn triangle_edge_distance(p: (f32, f32), tri: [(f32, f32); 3]) -> f32 {
+    (0..3)
+        .map(|i| {
+            let (a, b) = (tri[i], tri[(i + 1) % 3]);
+            let (ex, ey) = (b.0 - a.0, b.1 - a.1);
+            ((ex * (p.1 - a.1) - ey * (p.0 - a.0)) / (ex * ex + ey * ey).sqrt()).abs()
+        })
+        .fold(f32::INFINITY, f32::min)
+}
@@ -0,0 +1,138 @@
+//! Brick grid: square cells with every other row shifted sideways.
+
+use super::{render, Surface, Tiling};
+
+/// Brick configuration
+#[derive(Clone)]
+pub struct BrickConfig {
+    /// Shift of odd rows as a fraction of the cell width (0 = plain squares)
+    pub offset: f32,
+    pub draw_borders: bool,
+    pub border_color: u32,
+    /// Border band in `HexConfig::border_thickness` units
+    pub border_thickness: usize,
+    pub background_color: u32,
+    /// Subsamples per axis for anti-aliased cell edges (1 = off, max 8)
+    pub supersample: usize,
+}
+
+impl Default for BrickConfig {
+    fn default() -> Self {
+        Self {
+            offset: 0.5,
+            draw_borders: false,
+            border_color: 0x282828FF,
+            border_thickness: 1,
+            background_color: 0x00000000,
+            supersample: 1,
+        }
+    }
+}
+
+struct BrickGrid {
+    src_w: usize,
+    src_h: usize,
+    size: f32,
+    shift: f32,
+}
+
+impl BrickGrid {
+    fn new(src_w: usize, src_h: usize, scale: usize, config: &BrickConfig) -> Self {
+        let size = scale.clamp(2, 32) as f32;
+        Self {
+            src_w,
+            src_h,
+            size,
+            shift: config.offset.clamp(0.0, 1.0) * size,
+        }
+    }
+}
+
+impl Tiling for BrickGrid {
+    fn output_dimensions(&self) -> (usize, usize) {
+        let shift = if self.src_h > 1 { self.shift } else { 0.0 };
+        (
+            (self.src_w as f32 * self.size + shift).ceil() as usize,
+            (self.src_h as f32 * self.size) as usize,
+        )
+    }
+
+    fn locate(&self, x: f32, y: f32) -> Option<(usize, usize, f32)> {
+        let row = (y / self.size).floor();
+        let x = if row as i64 & 1 == 1 { x - self.shift } else { x };
+        let col = (x / self.size).floor();
+        if col < 0.0 || row < 0.0 || col >= self.src_w as f32 || row >= self.src_h as f32 {
+            return None;
+        }
+
+        let fx = x - col * self.size;
+        let fy = y - row * self.size;
+        let edge = fx.min(self.size - fx).min(fy).min(self.size - fy);
+        Some((col as usize, row as usize, edge))
+    }
+}
+
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &BrickConfig,
+) -> (usize, usize) {
+    BrickGrid::new(src_w, src_h, scale, config).output_dimensions()
+}
+
+pub fn brick_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &BrickConfig,
+) -> Vec<u8> {
+    let grid = BrickGrid::new(src_w, src_h, scale, config);
+    render(&grid, input, src_w, &Surface {
+        draw_borders: config.draw_borders,
+        border_color: config.border_color,
+        border_thickness: config.border_thickness,
+        background_color: config.background_color,
+        supersample: config.supersample,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_odd_rows_are_shifted() {
+        let mut img = vec![0u8; 2 * 2 * 4];
+        for (i, px) in img.chunks_mut(4).enumerate() {
+            px.copy_from_slice(&[i as u8 * 60, 0, 0, 255]);
+        }
+        let config = BrickConfig {
+            draw_borders: true,
+            background_color: 0x0000FFFF,
+            ..BrickConfig::default()
+        };
+        let (w, h) = get_output_dimensions(2, 2, 8, &config);
+        assert_eq!((w, h), (20, 16));
+
+        let out = brick_upscale(&img, 2, 2, 8, &config);
+        let pixel = |x: usize, y: usize| &out[(y * w + x) * 4..(y * w + x) * 4 + 4];
+        assert_eq!(pixel(4, 4), [0, 0, 0, 255]);
+        assert_eq!(pixel(2, 12), [0, 0, 255, 255]);
+        assert_eq!(pixel(10, 12), [120, 0, 0, 255]);
+        assert_eq!(pixel(18, 12), [180, 0, 0, 255]);
+        assert_eq!(pixel(18, 4), [0, 0, 255, 255]);
+        // Mortar line between the bricks of row 0
+        assert_eq!(pixel(8, 4), [0x28, 0x28, 0x28, 0xFF]);
+    }
+
+    #[test]
+    fn test_border_band_matches_hex() {
+        // 11 units are 10.48 px, the HEX band at an edge midpoint
+        let config = BrickConfig { offset: 0.0, draw_borders: true, border_thickness: 11, ..BrickConfig::default() };
+        let out = brick_upscale(&[200, 0, 0, 255], 1, 1, 32, &config);
+        let border_columns = (0..16).take_while(|&x| out[(16 * 32 + x) * 4] == 0x28).count();
+        assert_eq!(border_columns, 10);
+    }
+}
@@ -0,0 +1,132 @@
+//! Diamond grid: the source drawn in isometric projection, one rhombus per pixel.
+//!
+//! Source pixel `(0, 0)` is the top corner of the output; columns run down
+//! to the right and rows down to the left.
+
+use super::{render, Surface, Tiling};
+
+/// Diamond configuration
+#[derive(Clone)]
+pub struct DiamondConfig {
+    /// Rhombus height / width (0.5 = classic 2:1 isometric, 1.0 = squares at 45 degrees)
+    pub aspect: f32,
+    pub draw_borders: bool,
+    pub border_color: u32,
+    /// Border band in `HexConfig::border_thickness` units
+    pub border_thickness: usize,
+    pub background_color: u32,
+    /// Subsamples per axis for anti-aliased cell edges (1 = off, max 8)
+    pub supersample: usize,
+}
+
+impl Default for DiamondConfig {
+    fn default() -> Self {
+        Self {
+            aspect: 0.5,
+            draw_borders: false,
+            border_color: 0x282828FF,
+            border_thickness: 1,
+            background_color: 0x00000000,
+            supersample: 1,
+        }
+    }
+}
+
+struct DiamondGrid {
+    src_w: usize,
+    src_h: usize,
+    half_w: f32,
+    half_h: f32,
+    /// Distance between opposite sides of a rhombus
+    altitude: f32,
+}
+
+impl DiamondGrid {
+    /// Rhombi are `2 * scale` wide
+    fn new(src_w: usize, src_h: usize, scale: usize, config: &DiamondConfig) -> Self {
+        let half_w = scale.clamp(2, 32) as f32;
+        let half_h = half_w * config.aspect.clamp(0.25, 2.0);
+        Self {
+            src_w,
+            src_h,
+            half_w,
+            half_h,
+            altitude: 2.0 * half_w * half_h / half_w.hypot(half_h),
+        }
+    }
+}
+
+impl Tiling for DiamondGrid {
+    fn output_dimensions(&self) -> (usize, usize) {
+        let n = (self.src_w + self.src_h) as f32;
+        ((n * self.half_w).ceil() as usize, (n * self.half_h).ceil() as usize)
+    }
+
+    fn locate(&self, x: f32, y: f32) -> Option<(usize, usize, f32)> {
+        let u = (x - self.src_h as f32 * self.half_w) / self.half_w;
+        let v = y / self.half_h;
+        let a = (v + u) * 0.5;
+        let b = (v - u) * 0.5;
+        let (col, row) = (a.floor(), b.floor());
+        if col < 0.0 || row < 0.0 || col >= self.src_w as f32 || row >= self.src_h as f32 {
+            return None;
+        }
+
+        let (fa, fb) = (a - col, b - row);
+        let edge = fa.min(1.0 - fa).min(fb).min(1.0 - fb) * self.altitude;
+        Some((col as usize, row as usize, edge))
+    }
+}
+
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &DiamondConfig,
+) -> (usize, usize) {
+    DiamondGrid::new(src_w, src_h, scale, config).output_dimensions()
+}
+
+pub fn diamond_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &DiamondConfig,
+) -> Vec<u8> {
+    let grid = DiamondGrid::new(src_w, src_h, scale, config);
+    render(&grid, input, src_w, &Surface {
+        draw_borders: config.draw_borders,
+        border_color: config.border_color,
+        border_thickness: config.border_thickness,
+        background_color: config.background_color,
+        supersample: config.supersample,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isometric_layout() {
+        // Row 0: red, green; row 1: blue, white
+        let img = [
+            255, 0, 0, 255, 0, 255, 0, 255,
+            0, 0, 255, 255, 255, 255, 255, 255,
+        ];
+        let config = DiamondConfig::default();
+        let (w, h) = get_output_dimensions(2, 2, 8, &config);
+        assert_eq!((w, h), (32, 16));
+
+        let out = diamond_upscale(&img, 2, 2, 8, &config);
+        let pixel = |x: usize, y: usize| &out[(y * w + x) * 4..(y * w + x) * 4 + 4];
+        assert_eq!(pixel(16, 4), [255, 0, 0, 255]);
+        assert_eq!(pixel(24, 8), [0, 255, 0, 255]);
+        assert_eq!(pixel(8, 8), [0, 0, 255, 255]);
+        assert_eq!(pixel(16, 12), [255, 255, 255, 255]);
+        // Corners of the bounding box are outside the projected image
+        assert_eq!(pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(31, 15), [0, 0, 0, 0]);
+    }
+}
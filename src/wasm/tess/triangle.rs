@@ -0,0 +1,213 @@
+//! Triangular grid: rows of alternating up/down equilateral triangles.
+
+use super::{render, triangle_edge_distance, Surface, Tiling};
+
+/// How triangles take their colour from the source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriangleMode {
+    /// Every source pixel becomes an up/down triangle pair (side = scale)
+    Pair = 0,
+    /// The source is scaled by `scale` and covered by triangles of side
+    /// `cell_size`, each taking the source pixel under its centroid
+    Resample = 1,
+}
+
+/// Triangle configuration
+#[derive(Clone)]
+pub struct TriangleConfig {
+    pub mode: TriangleMode,
+    /// Triangle side in output pixels for `Resample` (0 = 2 * scale)
+    pub cell_size: usize,
+    pub draw_borders: bool,
+    pub border_color: u32,
+    /// Border band in `HexConfig::border_thickness` units
+    pub border_thickness: usize,
+    pub background_color: u32,
+    /// Subsamples per axis for anti-aliased cell edges (1 = off, max 8)
+    pub supersample: usize,
+}
+
+impl Default for TriangleConfig {
+    fn default() -> Self {
+        Self {
+            mode: TriangleMode::Pair,
+            cell_size: 0,
+            draw_borders: false,
+            border_color: 0x282828FF,
+            border_thickness: 1,
+            background_color: 0x00000000,
+            supersample: 1,
+        }
+    }
+}
+
+struct TriangleGrid {
+    mode: TriangleMode,
+    src_w: usize,
+    src_h: usize,
+    /// Source-to-output scale (`Resample` only)
+    scale: f32,
+    side: f32,
+    row_height: f32,
+}
+
+impl TriangleGrid {
+    fn new(src_w: usize, src_h: usize, scale: usize, config: &TriangleConfig) -> Self {
+        let scale = scale.clamp(2, 32);
+        let side = match config.mode {
+            TriangleMode::Pair => scale,
+            TriangleMode::Resample if config.cell_size == 0 => scale * 2,
+            TriangleMode::Resample => config.cell_size.max(2),
+        } as f32;
+
+        Self {
+            mode: config.mode,
+            src_w,
+            src_h,
+            scale: scale as f32,
+            side,
+            row_height: side * 3.0_f32.sqrt() * 0.5,
+        }
+    }
+
+    /// Vertices of triangle `index` in row `row`
+    fn vertices(&self, index: i32, row: i32) -> [(f32, f32); 3] {
+        let half = self.side * 0.5;
+        let x = index as f32 * half;
+        let top = row as f32 * self.row_height;
+        let bottom = top + self.row_height;
+        if (index + row) & 1 == 0 {
+            [(x, bottom), (x + self.side, bottom), (x + half, top)]
+        } else {
+            [(x, top), (x + self.side, top), (x + half, bottom)]
+        }
+    }
+}
+
+impl Tiling for TriangleGrid {
+    fn output_dimensions(&self) -> (usize, usize) {
+        if self.src_w == 0 || self.src_h == 0 {
+            return (0, 0);
+        }
+        match self.mode {
+            TriangleMode::Pair => (
+                ((self.src_w as f32 * 2.0 + 1.0) * self.side * 0.5).ceil() as usize,
+                (self.src_h as f32 * self.row_height).ceil() as usize,
+            ),
+            TriangleMode::Resample => (
+                (self.src_w as f32 * self.scale) as usize,
+                (self.src_h as f32 * self.scale) as usize,
+            ),
+        }
+    }
+
+    fn locate(&self, x: f32, y: f32) -> Option<(usize, usize, f32)> {
+        let row = (y / self.row_height).floor() as i32;
+        let u = x / (self.side * 0.5);
+        let strip = u.floor() as i32;
+        let fu = u - strip as f32;
+        let fy = y / self.row_height - row as f32;
+
+        // The strip [k, k+1] is split by a diagonal between triangles k-1 and k
+        let up = (strip + row) & 1 == 0;
+        let index = if (up && fu + fy >= 1.0) || (!up && fu >= fy) { strip } else { strip - 1 };
+
+        let tri = self.vertices(index, row);
+        let edge = triangle_edge_distance((x, y), tri);
+
+        match self.mode {
+            TriangleMode::Pair => {
+                if index < 0 || row < 0 || index as usize >= self.src_w * 2 || row as usize >= self.src_h {
+                    return None;
+                }
+                Some((index as usize / 2, row as usize, edge))
+            }
+            TriangleMode::Resample => {
+                let cx = (tri[0].0 + tri[1].0 + tri[2].0) / 3.0;
+                let cy = (tri[0].1 + tri[1].1 + tri[2].1) / 3.0;
+                let col = (cx / self.scale).floor().clamp(0.0, (self.src_w - 1) as f32);
+                let row = (cy / self.scale).floor().clamp(0.0, (self.src_h - 1) as f32);
+                Some((col as usize, row as usize, edge))
+            }
+        }
+    }
+}
+
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &TriangleConfig,
+) -> (usize, usize) {
+    TriangleGrid::new(src_w, src_h, scale, config).output_dimensions()
+}
+
+pub fn triangle_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &TriangleConfig,
+) -> Vec<u8> {
+    if src_w == 0 || src_h == 0 {
+        return Vec::new();
+    }
+    let grid = TriangleGrid::new(src_w, src_h, scale, config);
+    render(&grid, input, src_w, &Surface {
+        draw_borders: config.draw_borders,
+        border_color: config.border_color,
+        border_thickness: config.border_thickness,
+        background_color: config.background_color,
+        supersample: config.supersample,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairs_cover_their_pixel() {
+        // Red pixel left, blue pixel right
+        let img = [255, 0, 0, 255, 0, 0, 255, 255];
+        let config = TriangleConfig::default();
+        let (w, h) = get_output_dimensions(2, 1, 10, &config);
+        assert_eq!((w, h), (25, 9));
+
+        let out = triangle_upscale(&img, 2, 1, 10, &config);
+        let pixel = |x: usize, y: usize| &out[(y * w + x) * 4..(y * w + x) * 4 + 4];
+        // Triangle 1 (down) of pixel 0 and triangle 2 (up) of pixel 1
+        assert_eq!(pixel(10, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(10, 8), [0, 0, 255, 255]);
+        // Outside the first up triangle's left edge
+        assert_eq!(pixel(0, 0), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_resample_covers_output() {
+        let img = vec![128u8; 3 * 2 * 4];
+        let config = TriangleConfig {
+            mode: TriangleMode::Resample,
+            draw_borders: true,
+            border_color: 0xFF0000FF,
+            ..TriangleConfig::default()
+        };
+        assert_eq!(get_output_dimensions(3, 2, 8, &config), (24, 16));
+
+        let out = triangle_upscale(&img, 3, 2, 8, &config);
+        assert!(out.chunks(4).all(|p| p[3] != 0));
+        assert!(out.chunks(4).any(|p| p == [255, 0, 0, 255]));
+        assert!(out.chunks(4).any(|p| p == [128, 128, 128, 128]));
+    }
+
+    #[test]
+    fn test_empty_source() {
+        for mode in [TriangleMode::Pair, TriangleMode::Resample] {
+            let config = TriangleConfig { mode, ..TriangleConfig::default() };
+            for (w, h) in [(0, 3), (3, 0), (0, 0)] {
+                assert_eq!(get_output_dimensions(w, h, 8, &config), (0, 0));
+                assert!(triangle_upscale(&[], w, h, 8, &config).is_empty());
+            }
+        }
+    }
+}
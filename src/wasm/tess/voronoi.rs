@@ -0,0 +1,176 @@
+//! Voronoi grid: every source pixel owns the region closest to a jittered
+//! site inside its square, giving an organic "stained glass" look.
+
+use super::{render, Surface, Tiling};
+
+/// Voronoi configuration
+#[derive(Clone)]
+pub struct VoronoiConfig {
+    /// How far sites wander from the pixel centres (0 = square cells, 1 = anywhere in the pixel)
+    pub jitter: f32,
+    /// Seed of the site pattern
+    pub seed: u32,
+    pub draw_borders: bool,
+    pub border_color: u32,
+    /// Border band in `HexConfig::border_thickness` units
+    pub border_thickness: usize,
+    pub background_color: u32,
+    /// Subsamples per axis for anti-aliased cell edges (1 = off, max 8)
+    pub supersample: usize,
+}
+
+impl Default for VoronoiConfig {
+    fn default() -> Self {
+        Self {
+            jitter: 0.8,
+            seed: 0,
+            draw_borders: false,
+            border_color: 0x282828FF,
+            border_thickness: 1,
+            background_color: 0x00000000,
+            supersample: 1,
+        }
+    }
+}
+
+/// Sites further than two cells away can never be the nearest ones
+const SEARCH_RADIUS: i64 = 2;
+
+struct VoronoiGrid {
+    src_w: usize,
+    src_h: usize,
+    size: f32,
+    /// Site of every source pixel in output pixels
+    sites: Vec<(f32, f32)>,
+}
+
+/// Deterministic hash of a cell to two values in [-0.5, 0.5)
+fn jitter_offsets(col: usize, row: usize, seed: u32) -> (f32, f32) {
+    let mut h = (col as u32).wrapping_mul(0x8DA6_B343)
+        ^ (row as u32).wrapping_mul(0xD816_3841)
+        ^ seed.wrapping_mul(0xCB1A_B31F);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846C_A68B);
+    h ^= h >> 16;
+    ((h & 0xFFFF) as f32 / 65536.0 - 0.5, (h >> 16) as f32 / 65536.0 - 0.5)
+}
+
+impl VoronoiGrid {
+    fn new(src_w: usize, src_h: usize, scale: usize, config: &VoronoiConfig) -> Self {
+        let size = scale.clamp(2, 32) as f32;
+        let jitter = config.jitter.clamp(0.0, 1.0);
+        let sites = (0..src_h)
+            .flat_map(|row| (0..src_w).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                let (jx, jy) = jitter_offsets(col, row, config.seed);
+                (
+                    (col as f32 + 0.5 + jx * jitter) * size,
+                    (row as f32 + 0.5 + jy * jitter) * size,
+                )
+            })
+            .collect();
+
+        Self { src_w, src_h, size, sites }
+    }
+}
+
+impl Tiling for VoronoiGrid {
+    fn output_dimensions(&self) -> (usize, usize) {
+        (
+            (self.src_w as f32 * self.size) as usize,
+            (self.src_h as f32 * self.size) as usize,
+        )
+    }
+
+    fn locate(&self, x: f32, y: f32) -> Option<(usize, usize, f32)> {
+        if self.sites.is_empty() {
+            return None;
+        }
+        let cx = (x / self.size).floor() as i64;
+        let cy = (y / self.size).floor() as i64;
+        let cols = (cx - SEARCH_RADIUS).max(0)..=(cx + SEARCH_RADIUS).min(self.src_w as i64 - 1);
+        let rows = (cy - SEARCH_RADIUS).max(0)..=(cy + SEARCH_RADIUS).min(self.src_h as i64 - 1);
+
+        let dist2 = |(sx, sy): (f32, f32)| (sx - x) * (sx - x) + (sy - y) * (sy - y);
+        let candidates = || {
+            rows.clone().flat_map(|r| cols.clone().map(move |c| r as usize * self.src_w + c as usize))
+        };
+
+        let nearest = candidates().min_by(|&a, &b| dist2(self.sites[a]).total_cmp(&dist2(self.sites[b])))?;
+        let site = self.sites[nearest];
+        let d_near = dist2(site);
+
+        // Distance to the bisector with each other site is the distance to the cell edge
+        let edge = candidates()
+            .filter(|&i| i != nearest)
+            .map(|i| {
+                let other = self.sites[i];
+                let span = (other.0 - site.0).hypot(other.1 - site.1);
+                (dist2(other) - d_near) / (2.0 * span.max(f32::EPSILON))
+            })
+            .fold(f32::INFINITY, f32::min);
+
+        Some((nearest % self.src_w, nearest / self.src_w, edge))
+    }
+}
+
+pub fn get_output_dimensions(
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &VoronoiConfig,
+) -> (usize, usize) {
+    VoronoiGrid::new(src_w, src_h, scale, config).output_dimensions()
+}
+
+pub fn voronoi_upscale(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &VoronoiConfig,
+) -> Vec<u8> {
+    let grid = VoronoiGrid::new(src_w, src_h, scale, config);
+    render(&grid, input, src_w, &Surface {
+        draw_borders: config.draw_borders,
+        border_color: config.border_color,
+        border_thickness: config.border_thickness,
+        background_color: config.background_color,
+        supersample: config.supersample,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sites_own_their_neighbourhood() {
+        let mut img = vec![0u8; 4 * 4 * 4];
+        for (i, px) in img.chunks_mut(4).enumerate() {
+            px.copy_from_slice(&[i as u8 * 16, 0, 0, 255]);
+        }
+        let config = VoronoiConfig { seed: 7, ..VoronoiConfig::default() };
+        assert_eq!(get_output_dimensions(4, 4, 10, &config), (40, 40));
+
+        let grid = VoronoiGrid::new(4, 4, 10, &config);
+        let out = voronoi_upscale(&img, 4, 4, 10, &config);
+        for (i, &(sx, sy)) in grid.sites.iter().enumerate() {
+            let idx = (sy as usize * 40 + sx as usize) * 4;
+            assert_eq!(out[idx], i as u8 * 16);
+        }
+
+        // Without jitter the cells are the source squares
+        let square = VoronoiConfig { jitter: 0.0, ..VoronoiConfig::default() };
+        let out = voronoi_upscale(&img, 4, 4, 10, &square);
+        assert_eq!(out[(15 * 40 + 25) * 4], 6 * 16);
+
+        // Same seed, same pattern
+        assert_eq!(
+            voronoi_upscale(&img, 4, 4, 10, &config),
+            voronoi_upscale(&img, 4, 4, 10, &config)
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! Pixel Art Vectorisation
+//! Traces the source image into an SVG document that scales to any size:
+//!
+//! - `Rectilinear`: lossless. Every colour becomes one path whose outline is
+//!   the union of its pixels (shared edges cancel, straight runs merge).
+//! - `Smooth`: the pixel corners xBRZ would blend are cut along the blend
+//!   diagonal and handed to the neighbours across it, and the resulting
+//!   outline is drawn with quadratic curves everywhere except at the
+//!   right-angle corners xBRZ keeps sharp.
+//!
+//! Corner decisions come straight from the xBRZ pre-processing step, so the
+//! smooth outlines follow the same edges `xbrz_upscale` rounds off.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::canvas::rgba_parts;
+use crate::hex::{fill_attrs, fmt_coord};
+use crate::xbrz::blend::BlendType;
+use crate::xbrz::config::ScalerConfig;
+use crate::xbrz::kernel::Kernel4x4;
+use crate::xbrz::oob_reader::{OobReader, OobReaderTransparent};
+use crate::xbrz::pixel::Rgba8;
+
+/// Outline style
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum VectorMode {
+    /// Exact pixel outlines
+    #[default]
+    Rectilinear = 0,
+    /// xBRZ-style cut corners drawn as curves
+    Smooth = 1,
+}
+
+/// Vectorisation configuration
+#[derive(Clone, Default)]
+pub struct VectorizeConfig {
+    pub mode: VectorMode,
+    /// Fill behind the shapes (RGBA, alpha 0 = none)
+    pub background_color: u32,
+    /// xBRZ settings deciding which corners the smooth mode cuts
+    pub xbrz: ScalerConfig,
+}
+
+/// Vertex on the quarter-pixel lattice (source pixel coordinates * 4)
+type Vertex = (i32, i32);
+
+/// Pixel corners in outline order: top-left, top-right, bottom-right, bottom-left
+const CORNERS: [Vertex; 4] = [(0, 0), (4, 0), (4, 4), (0, 4)];
+/// Side midpoints; side `k` runs from corner `k` to corner `k + 1`
+const MIDS: [Vertex; 4] = [(2, 0), (4, 2), (2, 4), (0, 2)];
+/// Neighbour across each side
+const SIDES: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Which corners of every pixel xBRZ blends
+fn blended_corners(pixels: &[Rgba8], width: usize, height: usize, config: &ScalerConfig) -> Vec<[bool; 4]> {
+    let mut cuts = vec![[false; 4]; width * height];
+    let mut mark = |x: isize, y: isize, corner: usize, blend: BlendType| {
+        if blend != BlendType::None && (0..width as isize).contains(&x) && (0..height as isize).contains(&y) {
+            cuts[y as usize * width + x as usize][corner] = true;
+        }
+    };
+
+    // Each kernel position decides the four corners meeting at the bottom-right of `f`
+    for y in -1..height as isize {
+        let oob_reader = OobReaderTransparent::new(pixels, width, height, y);
+        let mut kernel = Kernel4x4::init_row(&oob_reader);
+        for x in -1..width as isize {
+            if x >= 0 {
+                kernel.next_column(&oob_reader, x);
+            }
+            let blend = kernel.pre_process_corners(config);
+            mark(x, y, 2, blend.top_left);
+            mark(x + 1, y, 3, blend.top_right);
+            mark(x, y + 1, 1, blend.bottom_left);
+            mark(x + 1, y + 1, 0, blend.bottom_right);
+        }
+    }
+    cuts
+}
+
+/// Directed outline edges of one colour; an edge added in both directions cancels
+#[derive(Default)]
+struct Outline {
+    edges: HashSet<(Vertex, Vertex)>,
+}
+
+impl Outline {
+    fn add_polygon(&mut self, points: &[Vertex]) {
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            if !self.edges.remove(&(b, a)) {
+                self.edges.insert((a, b));
+            }
+        }
+    }
+
+    /// Closed loops with collinear points removed
+    fn loops(&self) -> Vec<Vec<Vertex>> {
+        let mut next: HashMap<Vertex, Vec<Vertex>> = HashMap::new();
+        for &(a, b) in &self.edges {
+            next.entry(a).or_default().push(b);
+        }
+        for targets in next.values_mut() {
+            targets.sort_unstable();
+        }
+        // Deterministic output regardless of hash order
+        let mut starts: Vec<Vertex> = next.keys().copied().collect();
+        starts.sort_unstable();
+
+        let mut loops = Vec::new();
+        for start in starts {
+            while let Some(first) = next.get_mut(&start).and_then(|v| v.pop()) {
+                let mut points = vec![start];
+                let mut current = first;
+                while current != start {
+                    points.push(current);
+                    match next.get_mut(&current).and_then(|v| v.pop()) {
+                        Some(n) => current = n,
+                        None => break,
+                    }
+                }
+                loops.push(simplify(points));
+            }
+        }
+        loops
+    }
+}
+
+/// Drop points that lie on a straight line between their neighbours
+fn simplify(points: Vec<Vertex>) -> Vec<Vertex> {
+    let n = points.len();
+    (0..n)
+        .filter(|&i| {
+            let (p, v, q) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            (v.0 - p.0) * (q.1 - v.1) != (v.1 - p.1) * (q.0 - v.0)
+        })
+        .map(|i| points[i])
+        .collect()
+}
+
+/// Append one closed loop to path data `d`, scaled to output pixels
+fn write_loop(d: &mut String, points: &[Vertex], scale: f32, smooth: bool) {
+    let n = points.len();
+    let at = |(x, y): Vertex| (x as f32 * scale / 4.0, y as f32 * scale / 4.0);
+    let axis = |a: Vertex, b: Vertex| a.0 == b.0 || a.1 == b.1;
+    // End of the previous curve, to skip zero-length lines between curves
+    let mut pen: Option<(f32, f32)> = None;
+
+    for (i, &v) in points.iter().enumerate() {
+        let (prev, next) = (points[(i + n - 1) % n], points[(i + 1) % n]);
+        let (vx, vy) = at(v);
+        let cmd = if i == 0 { 'M' } else { 'L' };
+
+        if smooth && !(axis(prev, v) && axis(v, next)) {
+            // Round the turn with a quadratic through at most half of each side
+            let (px, py) = at(prev);
+            let (nx, ny) = at(next);
+            let len_in = (vx - px).hypot(vy - py);
+            let len_out = (nx - vx).hypot(ny - vy);
+            let offset = 0.5 * len_in.min(len_out);
+            let (ax, ay) = (vx + (px - vx) * offset / len_in, vy + (py - vy) * offset / len_in);
+            let (bx, by) = (vx + (nx - vx) * offset / len_out, vy + (ny - vy) * offset / len_out);
+            if pen != Some((ax, ay)) {
+                let _ = write!(d, "{}{} {}", cmd, fmt_coord(ax), fmt_coord(ay));
+            }
+            let _ = write!(d, "Q{} {} {} {}", fmt_coord(vx), fmt_coord(vy), fmt_coord(bx), fmt_coord(by));
+            pen = Some((bx, by));
+        } else {
+            let _ = write!(d, "{}{} {}", cmd, fmt_coord(vx), fmt_coord(vy));
+            pen = Some((vx, vy));
+        }
+    }
+    d.push('Z');
+}
+
+pub fn get_output_dimensions(src_w: usize, src_h: usize, scale: usize) -> (usize, usize) {
+    let scale = scale.max(1);
+    (src_w * scale, src_h * scale)
+}
+
+/// Trace `input` into an SVG document of `src * scale` pixels
+pub fn vectorize_svg(
+    input: &[u8],
+    src_w: usize,
+    src_h: usize,
+    scale: usize,
+    config: &VectorizeConfig,
+) -> String {
+    let (out_w, out_h) = get_output_dimensions(src_w, src_h, scale);
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" shape-rendering="geometricPrecision">"#,
+        w = out_w,
+        h = out_h
+    );
+
+    let bg = rgba_parts(config.background_color);
+    if bg[3] > 0 {
+        let _ = write!(svg, r#"<rect width="{}" height="{}" {}/>"#, out_w, out_h, fill_attrs(bg));
+    }
+
+    if src_w > 0 && src_h > 0 {
+        assert_eq!(input.len(), src_w * src_h * 4);
+        let (_, pixels, _) = unsafe { input.align_to::<Rgba8>() };
+        let smooth = config.mode == VectorMode::Smooth;
+        let cuts = if smooth {
+            blended_corners(pixels, src_w, src_h, &config.xbrz)
+        } else {
+            vec![[false; 4]; src_w * src_h]
+        };
+
+        let color = |x: usize, y: usize| -> [u8; 4] { input[(y * src_w + x) * 4..][..4].try_into().unwrap() };
+        let neighbour = |x: usize, y: usize, side: usize| {
+            let (dx, dy) = SIDES[side];
+            let nx = x.checked_add_signed(dx).filter(|&nx| nx < src_w)?;
+            let ny = y.checked_add_signed(dy).filter(|&ny| ny < src_h)?;
+            Some(color(nx, ny))
+        };
+
+        // Outlines per colour, in order of first appearance
+        let mut order: Vec<[u8; 4]> = Vec::new();
+        let mut outlines: HashMap<[u8; 4], Outline> = HashMap::new();
+        let mut add = |rgba: [u8; 4], points: &[Vertex]| {
+            if rgba[3] == 0 {
+                return;
+            }
+            outlines
+                .entry(rgba)
+                .or_insert_with(|| {
+                    order.push(rgba);
+                    Outline::default()
+                })
+                .add_polygon(points);
+        };
+
+        for y in 0..src_h {
+            for x in 0..src_w {
+                let origin = (x as i32 * 4, y as i32 * 4);
+                let offset = |(vx, vy): Vertex| (origin.0 + vx, origin.1 + vy);
+                let cut = cuts[y * src_w + x];
+
+                let mut polygon = Vec::with_capacity(8);
+                for k in 0..4 {
+                    let corner = offset(CORNERS[k]);
+                    // Cut corners end halfway between the corner and the pixel centre
+                    let inner = ((corner.0 + origin.0 + 2) / 2, (corner.1 + origin.1 + 2) / 2);
+                    polygon.push(if cut[k] { inner } else { corner });
+                    polygon.push(offset(MIDS[k]));
+
+                    if cut[k] {
+                        // The cut-off triangle goes half to each neighbour across the diagonal
+                        let (before, after) = ((k + 3) % 4, k);
+                        if let Some(rgba) = neighbour(x, y, before) {
+                            add(rgba, &[offset(MIDS[before]), corner, inner]);
+                        }
+                        if let Some(rgba) = neighbour(x, y, after) {
+                            add(rgba, &[corner, offset(MIDS[after]), inner]);
+                        }
+                    }
+                }
+                add(color(x, y), &polygon);
+            }
+        }
+
+        for rgba in order {
+            let mut d = String::new();
+            for points in outlines[&rgba].loops() {
+                write_loop(&mut d, &points, scale.max(1) as f32, smooth);
+            }
+            let _ = write!(svg, r#"<path {} fill-rule="evenodd" d="{}"/>"#, fill_attrs(rgba), d);
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+    fn image(pixels: &[[u8; 4]]) -> Vec<u8> {
+        pixels.concat()
+    }
+
+    #[test]
+    fn test_rectilinear_merges_regions() {
+        let svg = vectorize_svg(&image(&[RED; 4]), 2, 2, 8, &VectorizeConfig::default());
+        assert!(svg.contains(r#"width="16" height="16""#));
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert!(svg.contains(r#"d="M0 0L16 0L16 16L0 16Z""#));
+
+        // An L-shape keeps its inner corner; transparent pixels are skipped
+        let svg = vectorize_svg(&image(&[RED, CLEAR, RED, BLUE]), 2, 2, 1, &VectorizeConfig::default());
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains(r##"fill="#ff0000" fill-rule="evenodd" d="M0 0L1 0L1 2L0 2Z""##));
+        assert!(svg.contains(r#"d="M1 1L2 1L2 2L1 2Z""#));
+        assert!(!svg.contains('Q'));
+    }
+
+    #[test]
+    fn test_smooth_follows_xbrz_blending() {
+        // A lone pixel: xBRZ blends all four corners, the outline becomes a curve
+        let mut pixels = [CLEAR; 9];
+        pixels[4] = RED;
+        let config = VectorizeConfig { mode: VectorMode::Smooth, ..VectorizeConfig::default() };
+        let svg = vectorize_svg(&image(&pixels), 3, 3, 4, &config);
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert_eq!(svg.matches('Q').count(), 4);
+
+        // Diagonal two-colour staircase: the cut corners are shared, nothing is lost
+        let stairs = image(&[RED, BLUE, RED, RED]);
+        let svg = vectorize_svg(&stairs, 2, 2, 4, &config);
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains('Q'));
+
+        // A flat sprite only has its outer corners rounded, as in xBRZ
+        let svg = vectorize_svg(&image(&[BLUE; 9]), 3, 3, 2, &config);
+        assert_eq!(svg.matches('M').count(), 1);
+        assert_eq!(svg.matches('Q').count(), 8);
+        assert!(svg.contains("L4.29 0Q5 0 5.5 0.5Q6 1 6 1.71L6 4.29"));
+    }
+}
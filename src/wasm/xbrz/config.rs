@@ -1,8 +1,13 @@
+use crate::color_space::ColorSpace;
+
+#[derive(Clone, Copy)]
 pub struct ScalerConfig {
     pub equal_color_tolerance: f64,
     pub center_direction_bias: f64,
     pub dominant_direction_threshold: f64,
     pub steep_direction_threshold: f64,
+    /// Colour space the source is in; colour distances are measured in it
+    pub color_space: ColorSpace,
 }
 
 impl Default for ScalerConfig {
@@ -12,6 +17,34 @@ impl Default for ScalerConfig {
             center_direction_bias: 4.0,
             dominant_direction_threshold: 3.6,
             steep_direction_threshold: 2.2,
+            color_space: ColorSpace::Srgb,
         }
     }
 }
+
+impl ScalerConfig {
+    /// Names accepted by [`ScalerConfig::preset`], in display order
+    pub const PRESET_NAMES: &'static [&'static str] = &["default", "sharp", "smooth", "standard"];
+
+    /// Look up a named preset
+    pub fn preset(name: &str) -> Option<Self> {
+        Some(match name {
+            "default" | "standard" => Self::default(),
+            "sharp" => Self {
+                equal_color_tolerance: 20.0,
+                center_direction_bias: 4.0,
+                dominant_direction_threshold: 3.2,
+                steep_direction_threshold: 2.0,
+                ..Self::default()
+            },
+            "smooth" => Self {
+                equal_color_tolerance: 40.0,
+                center_direction_bias: 4.0,
+                dominant_direction_threshold: 4.0,
+                steep_direction_threshold: 2.4,
+                ..Self::default()
+            },
+            _ => return None,
+        })
+    }
+}
@@ -4,7 +4,7 @@ use super::blend::{Blend2x2, BlendType};
 use super::config::ScalerConfig;
 use super::oob_reader::OobReader;
 use super::pixel::Pixel;
-use super::ycbcr_lookup::YCbCrLookup;
+use super::ycbcr_lookup::ColorDistance;
 
 /// 4x4 kernel with logical positions:
 /// ```text
@@ -110,7 +110,7 @@ impl<P: Pixel> Kernel4x4<P> {
     #[inline]
     pub(crate) fn pre_process_corners(&self, cfg: &ScalerConfig) -> Blend2x2 {
         let mut result = Blend2x2::default();
-        let ycbcr = YCbCrLookup::instance();
+        let ycbcr = ColorDistance::new(cfg.color_space);
 
         if self.f == self.g && self.j == self.k {
             return result;
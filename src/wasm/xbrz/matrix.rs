@@ -36,6 +36,7 @@ impl<'out, P: Pixel, const N: usize, const R: u8> OutputMatrix<'out, P, N, R> {
         &mut self.inner[j + i * self.out_width]
     }
 
+    #[allow(dead_code)]
     pub(crate) fn into_inner(self) -> &'out mut [P] {
         let Self { inner, .. } = self;
         inner
@@ -11,17 +11,19 @@ use self::config::ScalerConfig;
 use self::oob_reader::OobReaderTransparent;
 use self::pixel::{Pixel, Rgba8};
 use self::scaler::{Scaler, Scaler2x, Scaler3x, Scaler4x, Scaler5x, Scaler6x};
+use crate::color_space::ColorSpace;
 
+#[allow(unused_imports)]
 pub use self::config::ScalerConfig as XbrzScalerConfig;
 
-mod blend;
+pub(crate) mod blend;
 pub mod config;
-mod kernel;
+pub(crate) mod kernel;
 mod matrix;
-mod oob_reader;
-mod pixel;
+pub(crate) mod oob_reader;
+pub(crate) mod pixel;
 mod scaler;
-mod ycbcr_lookup;
+pub(crate) mod ycbcr_lookup;
 
 /// Use the xBRZ algorithm to scale up an image by an integer factor.
 ///
@@ -38,6 +40,7 @@ mod ycbcr_lookup;
 ///
 /// Panics if the `source` slice length is not exactly equal to `src_width * src_height * 4`,
 /// or if `factor` is not one of 1, 2, 3, 4, 5 or 6.
+#[allow(dead_code)]
 pub fn scale_rgba(source: &[u8], src_width: usize, src_height: usize, factor: usize) -> Vec<u8> {
     scale::<Rgba8>(source, src_width, src_height, factor)
 }
@@ -53,6 +56,7 @@ pub fn scale_rgba_config(
     scale_with_config::<Rgba8>(source, src_width, src_height, factor, config)
 }
 
+#[allow(dead_code)]
 fn scale<P: Pixel>(source: &[u8], src_width: usize, src_height: usize, factor: usize) -> Vec<u8> {
     let config = ScalerConfig::default();
     scale_with_config::<P>(source, src_width, src_height, factor, &config)
@@ -155,9 +159,11 @@ fn scale_with_config<P: Pixel>(
 /// * `center_direction_bias` - Bias for center direction (default: 4.0)
 /// * `dominant_direction_threshold` - Threshold for dominant direction (default: 3.6)
 /// * `steep_direction_threshold` - Threshold for steep direction (default: 2.2)
+/// * `color_space` - Colour space of `input`, in which colour distances are measured
 /// 
 /// # Returns
 /// Scaled image as RGBA bytes
+#[allow(clippy::too_many_arguments)]
 pub fn xbrz_upscale(
     input: &[u8],
     src_w: usize,
@@ -167,6 +173,7 @@ pub fn xbrz_upscale(
     center_direction_bias: f64,
     dominant_direction_threshold: f64,
     steep_direction_threshold: f64,
+    color_space: ColorSpace,
 ) -> Vec<u8> {
     let scale = scale.clamp(1, 6);
     
@@ -179,6 +186,7 @@ pub fn xbrz_upscale(
         center_direction_bias,
         dominant_direction_threshold,
         steep_direction_threshold,
+        color_space,
     };
     
     scale_rgba_config(input, src_w, src_h, scale, &config)
@@ -88,3 +88,38 @@ impl<'src, P: Pixel> OobReader<'src, P> for OobReaderTransparent<'src, P> {
         }
     }
 }
+
+/// Out-of-bounds pixels repeat the nearest edge pixel
+pub(crate) struct OobReaderDuplicate<'src, P: Pixel> {
+    src_ym1: &'src [P],
+    src_y: &'src [P],
+    src_yp1: &'src [P],
+    src_yp2: &'src [P],
+    x_last: isize,
+}
+
+impl<'src, P: Pixel> OobReader<'src, P> for OobReaderDuplicate<'src, P> {
+    fn new(src: &'src [P], width: usize, height: usize, y: isize) -> Self {
+        assert_eq!(src.len(), width * height);
+        assert!(width > 0 && height > 0);
+        let row = |dy: isize| {
+            let y = (y + dy).clamp(0, height as isize - 1) as usize;
+            &src[y * width..(y + 1) * width]
+        };
+        Self {
+            src_ym1: row(-1),
+            src_y: row(0),
+            src_yp1: row(1),
+            src_yp2: row(2),
+            x_last: width as isize - 1,
+        }
+    }
+
+    fn fill_dhlp(&self, kernel: &mut Kernel4x4<P>, x: isize) {
+        let x_p2 = (x + 2).clamp(0, self.x_last) as usize;
+        kernel.d = self.src_ym1[x_p2];
+        kernel.h = self.src_y[x_p2];
+        kernel.l = self.src_yp1[x_p2];
+        kernel.p = self.src_yp2[x_p2];
+    }
+}
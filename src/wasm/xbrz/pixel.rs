@@ -127,7 +127,7 @@ impl Pixel for Rgb8 {
         [self.0[1], self.0[2], self.0[3]]
     }
 
-    fn gradient<const M: usize, const N: usize>(front: Self, back: Self) -> Self {
+    fn gradient<const M: usize, const N: usize>(_front: Self, _back: Self) -> Self {
         todo!()
     }
 }
@@ -138,6 +138,8 @@ impl Argb8 {
     }
 }
 
+/// Alpha-weighted blend, which is interpolation of premultiplied colour, so
+/// straight-alpha pixels need no conversion and transparent ones add no colour
 fn gradient_rgba<P: Pixel, const M: usize, const N: usize>(front: P, back: P) -> P {
     debug_assert!(0 < M && M < N && N <= 1000);
 
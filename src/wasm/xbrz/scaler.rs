@@ -6,7 +6,7 @@ use super::kernel::{Kernel4x4, Rotation, RotKernel3x3};
 use super::matrix::OutputMatrix;
 use super::oob_reader::OobReader;
 use super::pixel::Pixel;
-use super::ycbcr_lookup::YCbCrLookup;
+use super::ycbcr_lookup::{ColorDistance, YCbCrLookup};
 
 fn alpha_grad<P: Pixel, const M: usize, const N: usize>(pix_back: &mut P, pix_front: P) {
     *pix_back = P::gradient::<M, N>(pix_front, *pix_back);
@@ -45,8 +45,8 @@ pub(crate) trait Scaler<const SCALE: usize> {
         config: &ScalerConfig,
     ) {
         // SAFETY: should be initialised by scale_image()
-        debug_assert!(YCbCrLookup::instance_is_initialised());
-        let ycbcr = unsafe { YCbCrLookup::instance_unchecked() };
+        debug_assert!(YCbCrLookup::instance_is_initialised(config.color_space));
+        let ycbcr = unsafe { ColorDistance::new_unchecked(config.color_space) };
         let blend = blend_info.rotate(Rotation::from_u8(R));
 
         if blend.bottom_right == BlendType::None {
@@ -128,12 +128,12 @@ pub(crate) trait Scaler<const SCALE: usize> {
         config: &ScalerConfig,
         y_range: Range<usize>,
     ) {
-        let y_first = y_range.start.max(0);
+        let y_first = y_range.start;
         let y_last = y_range.end.min(src_height);
         assert!(y_first < y_last);
         assert!(src_width > 0);
         assert!(src_height > 0);
-        YCbCrLookup::initialise();
+        YCbCrLookup::initialise(config.color_space);
 
         let dest_width = src_width * SCALE;
         let dest_height = src_height * SCALE;
@@ -1,6 +1,9 @@
+use std::ptr;
+
 use parking_lot::Once;
 
 use super::pixel::Pixel;
+use crate::color_space::{self, ColorSpace};
 
 /// Reinterpret u8 bits as i8
 #[inline(always)]
@@ -16,66 +19,102 @@ fn i8_as_u8(v: i8) -> u8 {
 
 pub(crate) enum YCbCrLookup {
     IDiff555(Box<[f32]>),
+    #[cfg_attr(not(feature = "large_lut"), allow(dead_code))]
     IDiff888(Box<[f32]>),
 }
 
-// SAFETY: Only written to once by the closure in instance(), which is mediated by a parking_lot::Once.
-static mut LOOKUP_INSTANCE: Option<YCbCrLookup> = None;
-static LOOKUP_LOCK: Once = Once::new();
+// SAFETY: Each slot is only written to once by the closure in initialise(), which is mediated by
+// the slot's parking_lot::Once.
+static mut LOOKUP_INSTANCES: [Option<YCbCrLookup>; 2] = [None, None];
+static LOOKUP_LOCKS: [Once; 2] = [Once::new(), Once::new()];
+
+/// Luma weights of red and blue for an RGB -> YCbCr conversion
+#[derive(Clone, Copy)]
+pub(crate) struct LumaCoefficients {
+    pub k_r: f64,
+    pub k_b: f64,
+}
 
+/// Rec.2020, used by xBRZ
+pub(crate) const REC_2020: LumaCoefficients = LumaCoefficients { k_r: 0.2627, k_b: 0.0593 };
+/// Display-P3 primaries (D65 white)
+pub(crate) const DISPLAY_P3: LumaCoefficients = LumaCoefficients { k_r: 0.2290, k_b: 0.0793 };
+/// Rec.601, the `RGBtoYUV` weights of hqx
+pub(crate) const REC_601: LumaCoefficients = LumaCoefficients { k_r: 0.299, k_b: 0.114 };
+
+/// Lookup slot and luma weights for the primaries of `space`; sRGB keeps the
+/// Rec.2020 weights xBRZ was tuned with
+fn lookup_slot(space: ColorSpace) -> (usize, LumaCoefficients) {
+    match space {
+        ColorSpace::Srgb | ColorSpace::LinearSrgb => (0, REC_2020),
+        ColorSpace::DisplayP3 => (1, DISPLAY_P3),
+    }
+}
+
+/// YCbCr components `[y, cb, cr]` of an RGB difference (the conversion is
+/// linear, so this is also the difference of the two colours' components)
 #[inline]
-fn dist_ycbcr(r_diff: i16, g_diff: i16, b_diff: i16) -> f64 {
+pub(crate) fn ycbcr_diff(r_diff: i16, g_diff: i16, b_diff: i16, coeffs: LumaCoefficients) -> [f64; 3] {
     let r_diff = r_diff as f64;
     let g_diff = g_diff as f64;
     let b_diff = b_diff as f64;
 
-    // using Rec.2020 RGB -> YCbCr conversion
-    const K_B: f64 = 0.0593;
-    const K_R: f64 = 0.2627;
-    const K_G: f64 = 1.0 - K_B - K_R;
+    let k_g = 1.0 - coeffs.k_b - coeffs.k_r;
+    let scale_b = 0.5 / (1.0 - coeffs.k_b);
+    let scale_r = 0.5 / (1.0 - coeffs.k_r);
+
+    let y = coeffs.k_r * r_diff + k_g * g_diff + coeffs.k_b * b_diff;
+    [y, scale_b * (b_diff - y), scale_r * (r_diff - y)]
+}
 
-    const SCALE_B: f64 = 0.5 / (1.0 - K_B);
-    const SCALE_R: f64 = 0.5 / (1.0 - K_R);
+#[inline]
+pub(crate) fn dist_ycbcr(r_diff: i16, g_diff: i16, b_diff: i16) -> f64 {
+    // using Rec.2020 RGB -> YCbCr conversion
+    dist_ycbcr_with(r_diff, g_diff, b_diff, REC_2020)
+}
 
-    let y = K_R * r_diff + K_G * g_diff + K_B * b_diff;
-    let c_b = SCALE_B * (b_diff - y);
-    let c_r = SCALE_R * (r_diff - y);
+#[inline]
+pub(crate) fn dist_ycbcr_with(r_diff: i16, g_diff: i16, b_diff: i16, coeffs: LumaCoefficients) -> f64 {
+    let [y, c_b, c_r] = ycbcr_diff(r_diff, g_diff, b_diff, coeffs);
 
     (y * y + c_b * c_b + c_r * c_r).sqrt()
 }
 
 impl YCbCrLookup {
+    /// Lookup for sRGB data
     #[inline]
     pub(crate) fn instance() -> &'static Self {
-        Self::initialise();
+        Self::initialise(ColorSpace::Srgb);
 
-        unsafe { Self::instance_unchecked() }
+        unsafe { Self::instance_unchecked(ColorSpace::Srgb) }
     }
 
     #[inline]
-    pub(crate) fn initialise() {
-        LOOKUP_LOCK.call_once(|| unsafe {
+    pub(crate) fn initialise(space: ColorSpace) {
+        let (slot, coeffs) = lookup_slot(space);
+        LOOKUP_LOCKS[slot].call_once(|| unsafe {
+            let instances = &mut *ptr::addr_of_mut!(LOOKUP_INSTANCES);
             #[cfg(feature = "large_lut")]
             {
-                LOOKUP_INSTANCE = Some(Self::new_large());
+                instances[slot] = Some(Self::new_large(coeffs));
             }
             #[cfg(not(feature = "large_lut"))]
             {
-                LOOKUP_INSTANCE = Some(Self::new_small());
+                instances[slot] = Some(Self::new_small(coeffs));
             }
         });
     }
 
     #[inline]
-    pub(crate) unsafe fn instance_unchecked() -> &'static Self {
-        unsafe { LOOKUP_INSTANCE.as_ref().unwrap_unchecked() }
+    pub(crate) unsafe fn instance_unchecked(space: ColorSpace) -> &'static Self {
+        unsafe { (*ptr::addr_of!(LOOKUP_INSTANCES))[lookup_slot(space).0].as_ref().unwrap_unchecked() }
     }
 
-    pub(crate) fn instance_is_initialised() -> bool {
-        unsafe { LOOKUP_INSTANCE.is_some() }
+    pub(crate) fn instance_is_initialised(space: ColorSpace) -> bool {
+        unsafe { (*ptr::addr_of!(LOOKUP_INSTANCES))[lookup_slot(space).0].is_some() }
     }
 
-    pub(crate) fn new_small() -> Self {
+    pub(crate) fn new_small(coeffs: LumaCoefficients) -> Self {
         let mut lookup = Vec::with_capacity(0x8000);
 
         for i in 0..0x8000 {
@@ -83,13 +122,14 @@ impl YCbCrLookup {
             let g_diff = u8_as_i8((((i >> 5) & 0x1F) << 3) as u8) as i16 * 2;
             let b_diff = u8_as_i8(((i & 0x1F) << 3) as u8) as i16 * 2;
 
-            lookup.push(dist_ycbcr(r_diff, g_diff, b_diff) as f32);
+            lookup.push(dist_ycbcr_with(r_diff, g_diff, b_diff, coeffs) as f32);
         }
 
         Self::IDiff555(lookup.into_boxed_slice())
     }
 
-    pub(crate) fn new_large() -> Self {
+    #[cfg_attr(not(feature = "large_lut"), allow(dead_code))]
+    pub(crate) fn new_large(coeffs: LumaCoefficients) -> Self {
         let mut lookup = Vec::with_capacity(0x100_0000);
 
         for i in 0..0x100_0000 {
@@ -97,7 +137,7 @@ impl YCbCrLookup {
             let g_diff = u8_as_i8(((i >> 8) & 0xFF) as u8) as i16 * 2;
             let b_diff = u8_as_i8((i & 0xFF) as u8) as i16 * 2;
 
-            lookup.push(dist_ycbcr(r_diff, g_diff, b_diff) as f32);
+            lookup.push(dist_ycbcr_with(r_diff, g_diff, b_diff, coeffs) as f32);
         }
 
         Self::IDiff888(lookup.into_boxed_slice())
@@ -124,10 +164,15 @@ impl YCbCrLookup {
     }
 
     pub(crate) fn dist<P: Pixel>(&self, pix1: P, pix2: P) -> f32 {
-        let a1 = pix1.alpha() as f32 / u8::MAX as f32;
-        let a2 = pix2.alpha() as f32 / u8::MAX as f32;
+        self.dist_parts(pix1.to_rgb(), pix1.alpha(), pix2.to_rgb(), pix2.alpha())
+    }
 
-        let d = self.dist_rgb(pix1.to_rgb(), pix2.to_rgb());
+    #[inline]
+    fn dist_parts(&self, rgb1: [u8; 3], alpha1: u8, rgb2: [u8; 3], alpha2: u8) -> f32 {
+        let a1 = alpha1 as f32 / u8::MAX as f32;
+        let a2 = alpha2 as f32 / u8::MAX as f32;
+
+        let d = self.dist_rgb(rgb1, rgb2);
         if a1 < a2 {
             a1 * d + 255.0 * (a2 - a1)
         } else {
@@ -136,10 +181,51 @@ impl YCbCrLookup {
     }
 }
 
+/// Colour distance in one colour space: the lookup for its primaries and,
+/// for linear-light data, the sRGB curve applied first so that distances
+/// (and xBRZ's tolerances) stay perceptual
+#[derive(Clone, Copy)]
+pub(crate) struct ColorDistance {
+    lookup: &'static YCbCrLookup,
+    encode: Option<&'static [u8; 256]>,
+}
+
+impl ColorDistance {
+    #[inline]
+    pub(crate) fn new(space: ColorSpace) -> Self {
+        YCbCrLookup::initialise(space);
+
+        unsafe { Self::new_unchecked(space) }
+    }
+
+    /// # Safety
+    ///
+    /// The lookup for `space` must have been initialised
+    #[inline]
+    pub(crate) unsafe fn new_unchecked(space: ColorSpace) -> Self {
+        Self {
+            lookup: unsafe { YCbCrLookup::instance_unchecked(space) },
+            encode: space.is_linear().then(color_space::linear_to_srgb_lut),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn dist<P: Pixel>(&self, pix1: P, pix2: P) -> f32 {
+        match self.encode {
+            None => self.lookup.dist(pix1, pix2),
+            Some(lut) => {
+                let encode = |rgb: [u8; 3]| rgb.map(|c| lut[c as usize]);
+                self.lookup.dist_parts(encode(pix1.to_rgb()), pix1.alpha(), encode(pix2.to_rgb()), pix2.alpha())
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::super::pixel::Rgb8;
-    use super::{dist_ycbcr, YCbCrLookup};
+    use super::{dist_ycbcr, ColorDistance, YCbCrLookup, REC_2020};
+    use crate::color_space::ColorSpace;
 
     fn test_lut(lut: &YCbCrLookup, rgb1: (u8, u8, u8), rgb2: (u8, u8, u8)) {
         let (r1, g1, b1) = rgb1;
@@ -171,13 +257,27 @@ mod test {
 
     #[test]
     fn test_large_lut() {
-        let lookup = YCbCrLookup::new_large();
+        let lookup = YCbCrLookup::new_large(REC_2020);
         test_whole_lut(&lookup);
     }
 
     #[test]
     fn test_small_lut() {
-        let lookup = YCbCrLookup::new_small();
+        let lookup = YCbCrLookup::new_small(REC_2020);
         test_whole_lut(&lookup);
     }
+
+    #[test]
+    fn test_color_space_distance() {
+        let (dark, darker) = (Rgb8::from_parts(6, 6, 6), Rgb8::from_parts(1, 1, 1));
+        let srgb = ColorDistance::new(ColorSpace::Srgb);
+        let linear = ColorDistance::new(ColorSpace::LinearSrgb);
+        // Shadows are stretched out when linear data is compared perceptually
+        assert!(linear.dist(dark, darker) > 4.0 * srgb.dist(dark, darker));
+
+        // P3 weighs green less in luma than the Rec.2020 weights
+        let p3 = ColorDistance::new(ColorSpace::DisplayP3);
+        let (green, black) = (Rgb8::from_parts(0, 128, 0), Rgb8::from_parts(0, 0, 0));
+        assert_ne!(p3.dist(green, black), srgb.dist(green, black));
+    }
 }
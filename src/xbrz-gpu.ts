@@ -287,9 +287,5 @@ export class XbrzGpuRenderer implements Renderer<XbrzOptions> {
   }
 }
 
-export const XBRZ_PRESETS: Record<string, Partial<XbrzOptions>> = {
-  default: {},
-  sharp: { centerDirectionBias: 4.0, equalColorTolerance: 20, steepDirectionThreshold: 2.0, dominantDirectionThreshold: 3.2 },
-  smooth: { centerDirectionBias: 4.0, equalColorTolerance: 40, steepDirectionThreshold: 2.4, dominantDirectionThreshold: 4.0 },
-  standard: { centerDirectionBias: 4.0, equalColorTolerance: 30, steepDirectionThreshold: 2.2, dominantDirectionThreshold: 3.6 },
-};
+// Preset catalogue generated from the Rust crate
+export { XBRZ_PRESETS } from './presets.generated.js';